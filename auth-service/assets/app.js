@@ -2,6 +2,17 @@ const loginSection = document.getElementById("login-section");
 const twoFASection = document.getElementById("2fa-section");
 const signupSection = document.getElementById("signup-section");
 
+// set when an OAuth client sent the user here to sign in first
+const returnTo = new URLSearchParams(window.location.search).get("return_to");
+
+function onLoggedIn() {
+    if (returnTo && returnTo.startsWith("/authorize?")) {
+        window.location.href = returnTo;
+    } else {
+        alert("You have successfully logged in.");
+    }
+}
+
 const signupLink = document.getElementById("signup-link");
 const twoFALoginLink = document.getElementById("2fa-login-link");
 const signupLoginLink = document.getElementById("signup-login-link");
@@ -66,7 +77,7 @@ loginButton.addEventListener("click", (e) => {
             loginForm.email.value = "";
             loginForm.password.value = "";
             loginErrAlter.style.display = "none";
-            alert("You have successfully logged in.");
+            onLoggedIn();
         } else {
             response.json().then(data => {
                 let error_msg = data.error;
@@ -81,6 +92,32 @@ loginButton.addEventListener("click", (e) => {
     });
 });
 
+const magicLinkButton = document.getElementById("magic-link-submit");
+
+magicLinkButton.addEventListener("click", (e) => {
+    e.preventDefault();
+
+    const email = loginForm.email.value;
+
+    fetch('/magic-link/request', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ email }),
+    }).then(response => {
+        response.json().then(data => {
+            if (response.ok) {
+                loginErrAlter.style.display = "none";
+                alert(data.message);
+            } else if (data.error) {
+                loginErrAlter.innerHTML = `<span><strong>Error: </strong>${data.error}</span>`;
+                loginErrAlter.style.display = "block";
+            }
+        });
+    });
+});
+
 const signupForm = document.getElementById("signup-form");
 const signupButton = document.getElementById("signup-form-submit");
 const signupErrAlter = document.getElementById("signup-err-alert");
@@ -104,7 +141,13 @@ signupButton.addEventListener("click", (e) => {
             signupForm.password.value = "";
             signupForm.twoFA.checked = false;
             signupErrAlter.style.display = "none";
-            alert("You have successfully created a user.");
+            response.json().then(data => {
+                let message = "You have successfully created a user. Check your email to verify your address before logging in.";
+                if (data.recoveryCodes) {
+                    message += "\n\nSave these recovery codes, each can be used once if you lose access to your second factor:\n" + data.recoveryCodes.join("\n");
+                }
+                alert(message);
+            });
             loginSection.style.display = "block";
             twoFASection.style.display = "none";
             signupSection.style.display = "none";
@@ -125,6 +168,7 @@ signupButton.addEventListener("click", (e) => {
 const TwoFAForm = document.getElementById("2fa-form");
 const TwoFAButton = document.getElementById("2fa-form-submit");
 const TwoFAErrAlter = document.getElementById("2fa-err-alert");
+const TwoFAResendLink = document.getElementById("2fa-resend-link");
 
 TwoFAButton.addEventListener("click", (e) => {
     e.preventDefault();
@@ -145,7 +189,7 @@ TwoFAButton.addEventListener("click", (e) => {
             TwoFAForm.email_code.value = "";
             TwoFAForm.login_attempt_id.value = "";
             TwoFAErrAlter.style.display = "none";
-            alert("You have successfully logged in.");
+            onLoggedIn();
             loginSection.style.display = "block";
             twoFASection.style.display = "none";
             signupSection.style.display = "none";
@@ -161,4 +205,156 @@ TwoFAButton.addEventListener("click", (e) => {
             });
         }
     });
-});
\ No newline at end of file
+});
+
+TwoFAResendLink.addEventListener("click", (e) => {
+    e.preventDefault();
+
+    const email = TwoFAForm.email.value;
+    const loginAttemptId = TwoFAForm.login_attempt_id.value;
+
+    fetch('/resend-2fa', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ email, loginAttemptId }),
+    }).then(response => {
+        if (response.ok) {
+            TwoFAErrAlter.style.display = "none";
+            alert("A new code has been sent to your email.");
+        } else {
+            response.json().then(data => {
+                let error_msg = data.error;
+                if (error_msg !== undefined && error_msg !== null && error_msg !== "") {
+                    TwoFAErrAlter.innerHTML = `<span><strong>Error: </strong>${error_msg}</span>`;
+                    TwoFAErrAlter.style.display = "block";
+                } else {
+                    TwoFAErrAlter.style.display = "none";
+                }
+            });
+        }
+    });
+});
+// -----------------------------------------------------
+
+const emailVerificationToken = new URLSearchParams(window.location.search).get("email_verification_token");
+
+if (emailVerificationToken) {
+    window.history.replaceState({}, document.title, window.location.pathname);
+
+    fetch('/verify-email', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token: emailVerificationToken }),
+    }).then(response => {
+        if (response.ok) {
+            alert("Your email address has been verified. You can now log in.");
+        } else {
+            alert("This verification link is invalid or has expired.");
+        }
+    });
+}
+
+const unlockToken = new URLSearchParams(window.location.search).get("unlock_token");
+
+if (unlockToken) {
+    window.history.replaceState({}, document.title, window.location.pathname);
+
+    fetch('/unlock-account', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token: unlockToken }),
+    }).then(response => {
+        if (response.ok) {
+            alert("Your account has been unlocked. You can now log in.");
+        } else {
+            alert("This unlock link is invalid or has expired.");
+        }
+    });
+}
+
+const magicLinkToken = new URLSearchParams(window.location.search).get("magic_link_token");
+
+if (magicLinkToken) {
+    window.history.replaceState({}, document.title, window.location.pathname);
+
+    fetch('/magic-link/login', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token: magicLinkToken }),
+    }).then(response => {
+        if (response.status === 206) {
+            response.json().then(data => {
+                TwoFAForm.email.value = data.email;
+                TwoFAForm.login_attempt_id.value = data.loginAttemptId;
+            });
+
+            loginSection.style.display = "none";
+            twoFASection.style.display = "block";
+            signupSection.style.display = "none";
+        } else if (response.ok) {
+            onLoggedIn();
+        } else {
+            alert("This login link is invalid or has expired.");
+        }
+    });
+}
+
+const passwordResetSection = document.getElementById("password-reset-section");
+const passwordResetForm = document.getElementById("password-reset-form");
+const passwordResetButton = document.getElementById("password-reset-form-submit");
+const passwordResetErrAlter = document.getElementById("password-reset-err-alert");
+
+const passwordResetToken = new URLSearchParams(window.location.search).get("password_reset_token");
+
+if (passwordResetToken) {
+    window.history.replaceState({}, document.title, window.location.pathname);
+
+    passwordResetForm.token.value = passwordResetToken;
+
+    loginSection.style.display = "none";
+    twoFASection.style.display = "none";
+    signupSection.style.display = "none";
+    passwordResetSection.style.display = "block";
+}
+
+passwordResetButton.addEventListener("click", (e) => {
+    e.preventDefault();
+
+    const token = passwordResetForm.token.value;
+    const password = passwordResetForm.password.value;
+
+    fetch('/password-reset/confirm', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token, password }),
+    }).then(response => {
+        if (response.ok) {
+            passwordResetForm.token.value = "";
+            passwordResetForm.password.value = "";
+            passwordResetErrAlter.style.display = "none";
+            alert("Your password has been reset. You can now log in.");
+            loginSection.style.display = "block";
+            passwordResetSection.style.display = "none";
+        } else {
+            response.json().then(data => {
+                let error_msg = data.error;
+                if (error_msg !== undefined && error_msg !== null && error_msg !== "") {
+                    passwordResetErrAlter.innerHTML = `<span><strong>Error: </strong>${error_msg}</span>`;
+                    passwordResetErrAlter.style.display = "block";
+                } else {
+                    passwordResetErrAlter.style.display = "none";
+                }
+            });
+        }
+    });
+});
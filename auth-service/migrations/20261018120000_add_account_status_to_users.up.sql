@@ -0,0 +1,3 @@
+-- accounts created before email verification existed are treated as verified
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS account_status TEXT NOT NULL DEFAULT 'active';
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS oauth_clients(
+    client_id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    redirect_uris TEXT[] NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS machine_clients(
+    client_id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    client_secret_hash TEXT NOT NULL,
+    scopes TEXT[] NOT NULL
+);
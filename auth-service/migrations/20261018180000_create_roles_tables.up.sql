@@ -0,0 +1,33 @@
+CREATE TABLE IF NOT EXISTS roles(
+    name TEXT NOT NULL PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE IF NOT EXISTS permissions(
+    name TEXT NOT NULL PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE IF NOT EXISTS role_permissions(
+    role TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    permission TEXT NOT NULL REFERENCES permissions(name) ON DELETE CASCADE,
+    PRIMARY KEY (role, permission)
+);
+
+CREATE TABLE IF NOT EXISTS user_roles(
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    role TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    PRIMARY KEY (email, role)
+);
+
+INSERT INTO permissions (name, description)
+VALUES ('roles:assign', 'Assign roles to users and remove them')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO roles (name, description)
+VALUES ('admin', 'Administers the auth service')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role, permission)
+VALUES ('admin', 'roles:assign')
+ON CONFLICT (role, permission) DO NOTHING;
@@ -0,0 +1,7 @@
+INSERT INTO permissions (name, description)
+VALUES ('users:manage', 'List, disable, enable and delete users and force password resets')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role, permission)
+VALUES ('admin', 'users:manage')
+ON CONFLICT (role, permission) DO NOTHING;
@@ -0,0 +1,3 @@
+-- access tokens embed the version they were issued at, bumping it invalidates all of them
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS token_version INTEGER NOT NULL DEFAULT 0;
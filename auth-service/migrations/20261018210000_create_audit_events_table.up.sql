@@ -0,0 +1,23 @@
+-- actors aren't foreign keys, the log outlives deleted users
+CREATE TABLE IF NOT EXISTS audit_events(
+    id UUID NOT NULL PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    event_type TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    actor TEXT,
+    ip TEXT,
+    user_agent TEXT,
+    request_id TEXT,
+    reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS audit_events_occurred_at_idx ON audit_events(occurred_at DESC);
+CREATE INDEX IF NOT EXISTS audit_events_actor_idx ON audit_events(actor, occurred_at DESC);
+
+INSERT INTO permissions (name, description)
+VALUES ('audit:read', 'Query the security audit log')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role, permission)
+VALUES ('admin', 'audit:read')
+ON CONFLICT (role, permission) DO NOTHING;
@@ -0,0 +1,237 @@
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{eyre, Result};
+use uuid::Uuid;
+
+pub const MAX_AUDIT_EVENTS_PER_PAGE: u32 = 100;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AuditEventKind {
+    Signup,
+    Login,
+    TwoFAVerification,
+    Logout,
+    TokenRejected,
+}
+
+impl AuditEventKind {
+    pub fn parse(kind: &str) -> Result<Self> {
+        match kind {
+            "signup" => Ok(Self::Signup),
+            "login" => Ok(Self::Login),
+            "2fa_verification" => Ok(Self::TwoFAVerification),
+            "logout" => Ok(Self::Logout),
+            "token_rejected" => Ok(Self::TokenRejected),
+            _ => Err(eyre!("Invalid audit event type: {}", kind)),
+        }
+    }
+}
+
+impl AsRef<str> for AuditEventKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Signup => "signup",
+            Self::Login => "login",
+            Self::TwoFAVerification => "2fa_verification",
+            Self::Logout => "logout",
+            Self::TokenRejected => "token_rejected",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    pub fn parse(outcome: &str) -> Result<Self> {
+        match outcome {
+            "success" => Ok(Self::Success),
+            "failure" => Ok(Self::Failure),
+            _ => Err(eyre!("Invalid audit outcome: {}", outcome)),
+        }
+    }
+}
+
+impl AsRef<str> for AuditOutcome {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub kind: AuditEventKind,
+    pub outcome: AuditOutcome,
+    // the email or client id the event is about, when the request identified one
+    pub actor: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    // why a failed attempt was refused
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub kind: Option<AuditEventKind>,
+    pub outcome: Option<AuditOutcome>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+// one page of the audit log, newest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditQuery {
+    pub filter: AuditFilter,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl AuditQuery {
+    pub fn new(filter: AuditFilter, page: u32, per_page: u32) -> Result<Self> {
+        if page == 0 {
+            return Err(eyre!("Pages start at 1"));
+        }
+
+        if per_page == 0 || per_page > MAX_AUDIT_EVENTS_PER_PAGE {
+            return Err(eyre!(
+                "Page size must be between 1 and {}",
+                MAX_AUDIT_EVENTS_PER_PAGE
+            ));
+        }
+
+        if let (Some(since), Some(until)) = (filter.since, filter.until) {
+            if since > until {
+                return Err(eyre!("since must not be after until"));
+            }
+        }
+
+        let actor = filter
+            .actor
+            .map(|actor| actor.trim().to_owned())
+            .filter(|actor| !actor.is_empty());
+
+        Ok(Self {
+            filter: AuditFilter { actor, ..filter },
+            page,
+            per_page,
+        })
+    }
+
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page - 1) * u64::from(self.per_page)
+    }
+
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        let filter = &self.filter;
+
+        filter
+            .actor
+            .as_ref()
+            .is_none_or(|actor| event.actor.as_ref() == Some(actor))
+            && filter.kind.is_none_or(|kind| event.kind == kind)
+            && filter
+                .outcome
+                .is_none_or(|outcome| event.outcome == outcome)
+            && filter.since.is_none_or(|since| event.occurred_at >= since)
+            && filter.until.is_none_or(|until| event.occurred_at <= until)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    pub total: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: AuditEventKind, outcome: AuditOutcome, actor: &str) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            occurred_at: Utc::now(),
+            kind,
+            outcome,
+            actor: Some(actor.to_owned()),
+            ip: None,
+            user_agent: None,
+            request_id: None,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_kinds_and_outcomes_round_trip() {
+        for kind in [
+            AuditEventKind::Signup,
+            AuditEventKind::Login,
+            AuditEventKind::TwoFAVerification,
+            AuditEventKind::Logout,
+            AuditEventKind::TokenRejected,
+        ] {
+            assert_eq!(AuditEventKind::parse(kind.as_ref()).unwrap(), kind);
+        }
+
+        for outcome in [AuditOutcome::Success, AuditOutcome::Failure] {
+            assert_eq!(AuditOutcome::parse(outcome.as_ref()).unwrap(), outcome);
+        }
+    }
+
+    #[test]
+    fn test_audit_query_validates_pagination_and_range() {
+        assert!(AuditQuery::new(AuditFilter::default(), 0, 20).is_err());
+        assert!(AuditQuery::new(AuditFilter::default(), 1, MAX_AUDIT_EVENTS_PER_PAGE + 1).is_err());
+
+        let now = Utc::now();
+        let backwards = AuditFilter {
+            since: Some(now),
+            until: Some(now - chrono::Duration::hours(1)),
+            ..AuditFilter::default()
+        };
+        assert!(AuditQuery::new(backwards, 1, 20).is_err());
+
+        let blank_actor = AuditFilter {
+            actor: Some(" ".to_owned()),
+            ..AuditFilter::default()
+        };
+        let query = AuditQuery::new(blank_actor, 2, 20).unwrap();
+        assert_eq!(query.filter.actor, None);
+        assert_eq!(query.offset(), 20);
+    }
+
+    #[test]
+    fn test_audit_query_matches_filters() {
+        let filter = AuditFilter {
+            actor: Some("a@example.com".to_owned()),
+            outcome: Some(AuditOutcome::Failure),
+            ..AuditFilter::default()
+        };
+        let query = AuditQuery::new(filter, 1, 20).unwrap();
+
+        assert!(query.matches(&event(
+            AuditEventKind::Login,
+            AuditOutcome::Failure,
+            "a@example.com"
+        )));
+        assert!(!query.matches(&event(
+            AuditEventKind::Login,
+            AuditOutcome::Success,
+            "a@example.com"
+        )));
+        assert!(!query.matches(&event(
+            AuditEventKind::Login,
+            AuditOutcome::Failure,
+            "b@example.com"
+        )));
+    }
+}
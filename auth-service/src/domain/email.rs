@@ -7,7 +7,7 @@ pub struct Email(Secret<String>);
 
 impl AsRef<str> for Email {
     fn as_ref(&self) -> &str {
-        &self.0.expose_secret()
+        self.0.expose_secret()
     }
 }
 
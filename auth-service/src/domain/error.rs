@@ -1,7 +1,10 @@
 use std::error::Error;
 
 use axum::{
-    http::StatusCode,
+    http::{
+        header::{CACHE_CONTROL, RETRY_AFTER, WWW_AUTHENTICATE},
+        HeaderValue, StatusCode,
+    },
     response::{IntoResponse, Response},
     Json,
 };
@@ -14,14 +17,51 @@ use thiserror::Error;
 pub enum AuthAPIError {
     #[error("User Already Exists")]
     UserAlreadyExists,
+    // a malformed path or query parameter
+    #[error("Bad request: {0}")]
+    BadRequest(&'static str),
     #[error("Invalid credentials")]
     InvalidCredentials,
     #[error("Incorrect credentials")]
     IncorrectCredentials,
+    #[error("Email not verified")]
+    EmailNotVerified,
+    #[error("Account disabled")]
+    AccountDisabled,
+    #[error("Password reset required")]
+    PasswordResetRequired,
+    // seconds until the account unlocks
+    #[error("Account locked")]
+    AccountLocked(u64),
+    // seconds until the rate limit window resets
+    #[error("Too many requests")]
+    TooManyRequests(u64),
+    #[error("Too many 2FA code resends")]
+    TooManyResends,
+    #[error("TOTP already enabled")]
+    TotpAlreadyEnabled,
+    #[error("2FA already enabled")]
+    TwoFAAlreadyEnabled,
+    #[error("2FA not enabled")]
+    TwoFANotEnabled,
     #[error("Missing token")]
     MissingToken,
     #[error("Invalid token")]
     InvalidToken,
+    #[error("API key not found")]
+    ApiKeyNotFound,
+    #[error("Missing permission")]
+    MissingPermission,
+    #[error("Role not found")]
+    RoleNotFound,
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Session not found")]
+    SessionNotFound,
+    #[error("Webhook not found")]
+    WebhookNotFound,
+    #[error("Webhook delivery not found")]
+    WebhookDeliveryNotFound,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
 }
@@ -31,10 +71,27 @@ impl PartialEq for AuthAPIError {
         matches!(
             (self, other),
             (Self::UserAlreadyExists, Self::UserAlreadyExists)
+                | (Self::BadRequest(_), Self::BadRequest(_))
                 | (Self::InvalidCredentials, Self::InvalidCredentials)
                 | (Self::IncorrectCredentials, Self::IncorrectCredentials)
+                | (Self::EmailNotVerified, Self::EmailNotVerified)
+                | (Self::AccountDisabled, Self::AccountDisabled)
+                | (Self::PasswordResetRequired, Self::PasswordResetRequired)
+                | (Self::AccountLocked(_), Self::AccountLocked(_))
+                | (Self::TooManyRequests(_), Self::TooManyRequests(_))
+                | (Self::TooManyResends, Self::TooManyResends)
+                | (Self::TotpAlreadyEnabled, Self::TotpAlreadyEnabled)
+                | (Self::TwoFAAlreadyEnabled, Self::TwoFAAlreadyEnabled)
+                | (Self::TwoFANotEnabled, Self::TwoFANotEnabled)
                 | (Self::MissingToken, Self::MissingToken)
                 | (Self::InvalidToken, Self::InvalidToken)
+                | (Self::ApiKeyNotFound, Self::ApiKeyNotFound)
+                | (Self::MissingPermission, Self::MissingPermission)
+                | (Self::RoleNotFound, Self::RoleNotFound)
+                | (Self::UserNotFound, Self::UserNotFound)
+                | (Self::SessionNotFound, Self::SessionNotFound)
+                | (Self::WebhookNotFound, Self::WebhookNotFound)
+                | (Self::WebhookDeliveryNotFound, Self::WebhookDeliveryNotFound)
                 | (Self::UnexpectedError(_), Self::UnexpectedError(_))
         )
     }
@@ -48,12 +105,59 @@ pub struct ErrorResponse {
 impl IntoResponse for AuthAPIError {
     fn into_response(self) -> Response {
         log_error_chain(&self);
+        let retry_after = match self {
+            AuthAPIError::AccountLocked(seconds) | AuthAPIError::TooManyRequests(seconds) => {
+                Some(seconds)
+            }
+            _ => None,
+        };
         let (status, error_message) = match self {
             AuthAPIError::UserAlreadyExists => (StatusCode::CONFLICT, "User already exists"),
+            AuthAPIError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
             AuthAPIError::InvalidCredentials => (StatusCode::BAD_REQUEST, "Invalid credentials"),
             AuthAPIError::IncorrectCredentials => (StatusCode::UNAUTHORIZED, "Invalid credentials"),
+            AuthAPIError::EmailNotVerified => {
+                (StatusCode::FORBIDDEN, "Email address has not been verified")
+            }
+            AuthAPIError::AccountDisabled => (StatusCode::FORBIDDEN, "Account is disabled"),
+            AuthAPIError::PasswordResetRequired => (
+                StatusCode::FORBIDDEN,
+                "Password must be reset before logging in",
+            ),
+            AuthAPIError::AccountLocked(_) => (
+                StatusCode::LOCKED,
+                "Account is temporarily locked after too many failed logins",
+            ),
+            AuthAPIError::TooManyRequests(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests, please try again later",
+            ),
+            AuthAPIError::TooManyResends => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many codes sent, please log in again",
+            ),
+            AuthAPIError::TotpAlreadyEnabled => {
+                (StatusCode::CONFLICT, "Authenticator app is already enabled")
+            }
+            AuthAPIError::TwoFAAlreadyEnabled => (
+                StatusCode::CONFLICT,
+                "Two-factor authentication is already enabled",
+            ),
+            AuthAPIError::TwoFANotEnabled => (
+                StatusCode::CONFLICT,
+                "Two-factor authentication is not enabled",
+            ),
             AuthAPIError::MissingToken => (StatusCode::BAD_REQUEST, "Missing Token"),
             AuthAPIError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid Request"),
+            AuthAPIError::ApiKeyNotFound => (StatusCode::NOT_FOUND, "API key not found"),
+            AuthAPIError::MissingPermission => (StatusCode::FORBIDDEN, "Missing permission"),
+            AuthAPIError::RoleNotFound => (StatusCode::NOT_FOUND, "Role not found"),
+            AuthAPIError::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+            AuthAPIError::SessionNotFound => (StatusCode::NOT_FOUND, "Session not found"),
+            AuthAPIError::WebhookNotFound => (StatusCode::NOT_FOUND, "Webhook not found"),
+            AuthAPIError::WebhookDeliveryNotFound => {
+                (StatusCode::NOT_FOUND, "Webhook delivery not found")
+            }
             AuthAPIError::UnexpectedError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "An unexpected error occurred",
@@ -62,7 +166,109 @@ impl IntoResponse for AuthAPIError {
         let body = Json(ErrorResponse {
             error: error_message.to_string(),
         });
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+// errors from the OAuth endpoints, shaped as RFC 6749 section 5.2 requires
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(&'static str),
+    #[error("Invalid client")]
+    InvalidClient,
+    #[error("Invalid grant")]
+    InvalidGrant,
+    #[error("Unsupported grant type")]
+    UnsupportedGrantType,
+    #[error("Invalid scope")]
+    InvalidScope,
+    #[error("Invalid token")]
+    InvalidToken,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for OAuthError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::InvalidRequest(_), Self::InvalidRequest(_))
+                | (Self::InvalidClient, Self::InvalidClient)
+                | (Self::InvalidGrant, Self::InvalidGrant)
+                | (Self::UnsupportedGrantType, Self::UnsupportedGrantType)
+                | (Self::InvalidScope, Self::InvalidScope)
+                | (Self::InvalidToken, Self::InvalidToken)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OAuthErrorResponse {
+    pub error: String,
+    pub error_description: String,
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        log_error_chain(&self);
+        let is_invalid_token = matches!(self, OAuthError::InvalidToken);
+        let (status, error, description) = match self {
+            OAuthError::InvalidRequest(description) => {
+                (StatusCode::BAD_REQUEST, "invalid_request", description)
+            }
+            OAuthError::InvalidClient => {
+                (StatusCode::UNAUTHORIZED, "invalid_client", "Unknown client")
+            }
+            OAuthError::InvalidGrant => (
+                StatusCode::BAD_REQUEST,
+                "invalid_grant",
+                "The authorization code is invalid, expired or was issued to another client",
+            ),
+            OAuthError::UnsupportedGrantType => (
+                StatusCode::BAD_REQUEST,
+                "unsupported_grant_type",
+                "Unsupported grant type",
+            ),
+            OAuthError::InvalidScope => (
+                StatusCode::BAD_REQUEST,
+                "invalid_scope",
+                "The requested scope exceeds what the client is allowed",
+            ),
+            OAuthError::InvalidToken => (
+                StatusCode::UNAUTHORIZED,
+                "invalid_token",
+                "The access token is invalid or has expired",
+            ),
+            OAuthError::UnexpectedError(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                "An unexpected error occurred",
+            ),
+        };
+        let body = Json(OAuthErrorResponse {
+            error: error.to_owned(),
+            error_description: description.to_owned(),
+        });
+        let mut response = (status, body).into_response();
+        response
+            .headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        // bearer token errors are also reported in a challenge, per RFC 6750
+        if is_invalid_token {
+            response.headers_mut().insert(
+                WWW_AUTHENTICATE,
+                HeaderValue::from_static("Bearer error=\"invalid_token\""),
+            );
+        }
+        response
     }
 }
 
@@ -1,13 +1,23 @@
+pub mod audit;
 pub mod data_stores;
 pub mod email;
 pub mod email_client;
 pub mod error;
+pub mod oauth;
 pub mod password;
+pub mod role;
+pub mod session;
 pub mod user;
+pub mod webhook;
 
+pub use audit::*;
 pub use data_stores::*;
 pub use email::*;
 pub use email_client::*;
 pub use error::*;
+pub use oauth::*;
 pub use password::*;
+pub use role::*;
+pub use session::*;
 pub use user::*;
+pub use webhook::*;
@@ -0,0 +1,263 @@
+use color_eyre::eyre::{eyre, Result};
+use data_encoding::BASE64URL_NOPAD;
+use ring::digest::{digest, SHA256};
+use secrecy::{ExposeSecret, Secret};
+use url::Url;
+
+use super::ClientSecret;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+}
+
+impl OAuthClient {
+    pub fn parse(client_id: String, name: String, redirect_uris: Vec<String>) -> Result<Self> {
+        if client_id.is_empty() || name.is_empty() {
+            return Err(eyre!("Client id and name are required"));
+        }
+
+        if redirect_uris.is_empty() {
+            return Err(eyre!("At least one redirect uri is required"));
+        }
+
+        // fragments aren't allowed by RFC 6749, and the code is appended to the query
+        if let Some(uri) = redirect_uris
+            .iter()
+            .find(|uri| Url::parse(uri).map_or(true, |url| url.fragment().is_some()))
+        {
+            return Err(eyre!("{} is not a valid redirect uri", uri));
+        }
+
+        Ok(Self {
+            client_id,
+            name,
+            redirect_uris,
+        })
+    }
+
+    // exact matches only, so a registered prefix can't be extended to an attacker's path
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|uri| uri == redirect_uri)
+    }
+}
+
+// a backend service that gets tokens for itself with the client credentials grant
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachineClient {
+    pub client_id: String,
+    pub name: String,
+    pub secret_hash: String,
+    pub scopes: Vec<String>,
+}
+
+impl MachineClient {
+    pub fn parse(
+        client_id: String,
+        name: String,
+        secret_hash: String,
+        scopes: Vec<String>,
+    ) -> Result<Self> {
+        if client_id.is_empty() || name.is_empty() || secret_hash.is_empty() {
+            return Err(eyre!("Client id, name and secret hash are required"));
+        }
+
+        if let Some(scope) = scopes.iter().find(|scope| !is_scope_token(scope)) {
+            return Err(eyre!("{} is not a valid scope", scope));
+        }
+
+        Ok(Self {
+            client_id,
+            name,
+            secret_hash,
+            scopes,
+        })
+    }
+
+    pub fn verify_secret(&self, secret: &ClientSecret) -> bool {
+        secret.hash() == self.secret_hash
+    }
+
+    // all of the client's scopes when none are requested, nothing if any aren't allowed
+    pub fn grant_scopes(&self, requested: Option<&str>) -> Option<Vec<String>> {
+        let requested: Vec<&str> = match requested {
+            Some(scope) => scope.split(' ').filter(|s| !s.is_empty()).collect(),
+            None => vec![],
+        };
+
+        if requested.is_empty() {
+            return Some(self.scopes.clone());
+        }
+
+        requested
+            .into_iter()
+            .map(|scope| {
+                self.scopes
+                    .iter()
+                    .find(|allowed| allowed.as_str() == scope)
+                    .cloned()
+            })
+            .collect()
+    }
+}
+
+// printable ascii without spaces, quotes or backslashes, per RFC 6749 section 3.3
+pub fn is_scope_token(scope: &str) -> bool {
+    !scope.is_empty()
+        && scope
+            .bytes()
+            .all(|c| (0x21..=0x7e).contains(&c) && c != b'"' && c != b'\\')
+}
+
+// the S256 PKCE challenge, a base64url encoded SHA-256 of the verifier
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeChallenge(String);
+
+impl CodeChallenge {
+    pub fn parse(challenge: String) -> Result<Self> {
+        match BASE64URL_NOPAD.decode(challenge.as_bytes()) {
+            Ok(hash) if hash.len() == SHA256.output_len() => Ok(Self(challenge)),
+            _ => Err(eyre!("Invalid code challenge")),
+        }
+    }
+
+    pub fn verify(&self, verifier: &CodeVerifier) -> bool {
+        let hash = digest(&SHA256, verifier.0.expose_secret().as_bytes());
+        BASE64URL_NOPAD.encode(hash.as_ref()) == self.0
+    }
+}
+
+impl AsRef<str> for CodeChallenge {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CodeVerifier(Secret<String>);
+
+impl CodeVerifier {
+    // 43 to 128 unreserved characters, per RFC 7636
+    pub fn parse(verifier: Secret<String>) -> Result<Self> {
+        let value = verifier.expose_secret();
+        let is_valid = (43..=128).contains(&value.len())
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c));
+
+        if is_valid {
+            Ok(Self(verifier))
+        } else {
+            Err(eyre!("Invalid code verifier"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the example from RFC 7636 appendix B
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn test_code_challenge_verifies_rfc_example() {
+        let challenge = CodeChallenge::parse(CHALLENGE.to_owned()).unwrap();
+        let verifier = CodeVerifier::parse(Secret::new(VERIFIER.to_owned())).unwrap();
+
+        assert!(challenge.verify(&verifier));
+    }
+
+    #[test]
+    fn test_code_challenge_rejects_other_verifier() {
+        let challenge = CodeChallenge::parse(CHALLENGE.to_owned()).unwrap();
+        let verifier = CodeVerifier::parse(Secret::new("a".repeat(43))).unwrap();
+
+        assert!(!challenge.verify(&verifier));
+    }
+
+    #[test]
+    fn test_invalid_code_challenge() {
+        assert!(CodeChallenge::parse("plain-challenge".to_owned()).is_err());
+        assert!(CodeChallenge::parse(format!("{}=", CHALLENGE)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_code_verifier() {
+        assert!(CodeVerifier::parse(Secret::new("a".repeat(42))).is_err());
+        assert!(CodeVerifier::parse(Secret::new("a".repeat(129))).is_err());
+        assert!(CodeVerifier::parse(Secret::new(format!("{}+", "a".repeat(43)))).is_err());
+    }
+
+    #[test]
+    fn test_redirect_uri_must_match_exactly() {
+        let client = OAuthClient::parse(
+            "client".to_owned(),
+            "Client".to_owned(),
+            vec!["https://app.example.com/callback".to_owned()],
+        )
+        .unwrap();
+
+        assert!(client.allows_redirect_uri("https://app.example.com/callback"));
+        assert!(!client.allows_redirect_uri("https://app.example.com/callback/evil"));
+        assert!(!client.allows_redirect_uri("https://app.example.com/callback?x=1"));
+    }
+
+    fn machine_client(secret: &ClientSecret) -> MachineClient {
+        MachineClient::parse(
+            "service".to_owned(),
+            "Service".to_owned(),
+            secret.hash(),
+            vec!["users:read".to_owned(), "users:write".to_owned()],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_machine_client_verifies_secret() {
+        let secret = ClientSecret::default();
+        let client = machine_client(&secret);
+
+        assert!(client.verify_secret(&secret));
+        assert!(!client.verify_secret(&ClientSecret::default()));
+    }
+
+    #[test]
+    fn test_machine_client_grants_only_allowed_scopes() {
+        let client = machine_client(&ClientSecret::default());
+
+        assert_eq!(client.grant_scopes(None), Some(client.scopes.clone()));
+        assert_eq!(
+            client.grant_scopes(Some("users:read")),
+            Some(vec!["users:read".to_owned()])
+        );
+        assert_eq!(client.grant_scopes(Some("users:read admin")), None);
+    }
+
+    #[test]
+    fn test_invalid_scopes_are_rejected() {
+        for scope in ["", "two words", "quo\"te"] {
+            assert!(MachineClient::parse(
+                "service".to_owned(),
+                "Service".to_owned(),
+                "hash".to_owned(),
+                vec![scope.to_owned()],
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_redirect_uris_are_rejected() {
+        for uris in [
+            vec![],
+            vec!["not a url".to_owned()],
+            vec!["https://app.example.com/callback#fragment".to_owned()],
+        ] {
+            assert!(OAuthClient::parse("client".to_owned(), "Client".to_owned(), uris).is_err());
+        }
+    }
+}
@@ -0,0 +1,45 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct Role {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+}
+
+// what a user's roles allow, embedded in every token issued to them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserAccess {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+impl UserAccess {
+    // sorted and deduplicated, since several roles can grant the same permission
+    pub fn new(mut roles: Vec<String>, mut permissions: Vec<String>) -> Self {
+        roles.sort();
+        roles.dedup();
+        permissions.sort();
+        permissions.dedup();
+
+        Self { roles, permissions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_access_is_deduplicated() {
+        let access = UserAccess::new(
+            vec!["support".to_owned(), "admin".to_owned()],
+            vec![
+                "users:read".to_owned(),
+                "roles:assign".to_owned(),
+                "users:read".to_owned(),
+            ],
+        );
+
+        assert_eq!(access.roles, vec!["admin", "support"]);
+        assert_eq!(access.permissions, vec!["roles:assign", "users:read"]);
+    }
+}
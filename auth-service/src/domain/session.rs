@@ -0,0 +1,66 @@
+use std::net::SocketAddr;
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header::USER_AGENT, request::Parts},
+};
+use chrono::{DateTime, Utc};
+
+use super::{Email, RefreshTokenFamilyId};
+
+// a session lasts as long as its refresh token family, so they share an id
+pub type SessionId = RefreshTokenFamilyId;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id: SessionId,
+    pub email: Email,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub client: SessionClient,
+}
+
+impl Session {
+    pub fn new(email: Email, client: SessionClient) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: SessionId::default(),
+            email,
+            created_at: now,
+            last_used_at: now,
+            client,
+        }
+    }
+}
+
+// where a session was started from, shown to the user when they list their sessions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for SessionClient
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        Ok(Self { ip, user_agent })
+    }
+}
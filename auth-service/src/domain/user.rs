@@ -1,10 +1,17 @@
+use color_eyre::eyre::{eyre, Result};
+
 use super::{Email, Password};
 
+pub const MAX_USERS_PER_PAGE: u32 = 100;
+
 #[derive(PartialEq, Clone)]
 pub struct User {
     pub email: Email,
     pub password: Password,
     pub requires_2fa: bool,
+    pub status: AccountStatus,
+    // access tokens issued at an older version are rejected
+    pub token_version: u32,
 }
 
 impl User {
@@ -13,6 +20,112 @@ impl User {
             email,
             password,
             requires_2fa,
+            status: AccountStatus::PendingVerification,
+            token_version: 0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AccountStatus {
+    PendingVerification,
+    Active,
+    // set by an admin, the user can't log in until an admin enables the account again
+    Disabled,
+    // set by an admin, cleared once the user resets their password by email
+    PasswordResetRequired,
+}
+
+impl AccountStatus {
+    pub fn parse(status: &str) -> Result<Self> {
+        match status {
+            "pending_verification" => Ok(Self::PendingVerification),
+            "active" => Ok(Self::Active),
+            "disabled" => Ok(Self::Disabled),
+            "password_reset_required" => Ok(Self::PasswordResetRequired),
+            _ => Err(eyre!("Invalid account status: {}", status)),
+        }
+    }
+}
+
+impl AsRef<str> for AccountStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::PendingVerification => "pending_verification",
+            Self::Active => "active",
+            Self::Disabled => "disabled",
+            Self::PasswordResetRequired => "password_reset_required",
+        }
+    }
+}
+
+// one page of the admin user listing, optionally narrowed to emails containing `email`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserQuery {
+    pub email: Option<String>,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl UserQuery {
+    pub fn new(email: Option<String>, page: u32, per_page: u32) -> Result<Self> {
+        if page == 0 {
+            return Err(eyre!("Pages start at 1"));
         }
+
+        if per_page == 0 || per_page > MAX_USERS_PER_PAGE {
+            return Err(eyre!(
+                "Page size must be between 1 and {}",
+                MAX_USERS_PER_PAGE
+            ));
+        }
+
+        let email = email
+            .map(|email| email.trim().to_owned())
+            .filter(|email| !email.is_empty());
+
+        Ok(Self {
+            email,
+            page,
+            per_page,
+        })
+    }
+
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page - 1) * u64::from(self.per_page)
+    }
+}
+
+pub struct UserPage {
+    pub users: Vec<User>,
+    // users matching the query across all pages
+    pub total: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_status_round_trips() {
+        for status in [
+            AccountStatus::PendingVerification,
+            AccountStatus::Active,
+            AccountStatus::Disabled,
+            AccountStatus::PasswordResetRequired,
+        ] {
+            assert_eq!(AccountStatus::parse(status.as_ref()).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn test_user_query_validates_pagination() {
+        assert!(UserQuery::new(None, 0, 20).is_err());
+        assert!(UserQuery::new(None, 1, 0).is_err());
+        assert!(UserQuery::new(None, 1, MAX_USERS_PER_PAGE + 1).is_err());
+
+        let query = UserQuery::new(Some("  ".to_owned()), 3, 20).unwrap();
+        assert_eq!(query.email, None);
+        assert_eq!(query.offset(), 40);
     }
 }
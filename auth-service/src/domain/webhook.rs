@@ -0,0 +1,395 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::eyre::{eyre, Result};
+use data_encoding::HEXLOWER;
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::Url;
+use ring::hmac;
+use secrecy::{ExposeSecret, Secret};
+use url::Host;
+use uuid::Uuid;
+
+pub const WEBHOOK_ID_HEADER: &str = "x-webhook-id";
+pub const WEBHOOK_EVENT_HEADER: &str = "x-webhook-event";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+// attempts made before a delivery is given up on, spread over about four hours
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 10;
+const FIRST_RETRY_DELAY_SECONDS: i64 = 30;
+const MAX_RETRY_DELAY_SECONDS: i64 = 60 * 60 * 6;
+
+const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+const WEBHOOK_SECRET_LENGTH: usize = 48;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WebhookEventKind {
+    UserSignedUp,
+    UserLoggedIn,
+    TwoFAEnabled,
+    UserDeleted,
+}
+
+impl WebhookEventKind {
+    pub fn parse(kind: &str) -> Result<Self> {
+        match kind {
+            "user.signed_up" => Ok(Self::UserSignedUp),
+            "user.logged_in" => Ok(Self::UserLoggedIn),
+            "user.2fa_enabled" => Ok(Self::TwoFAEnabled),
+            "user.deleted" => Ok(Self::UserDeleted),
+            _ => Err(eyre!("Invalid webhook event type: {}", kind)),
+        }
+    }
+}
+
+impl AsRef<str> for WebhookEventKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::UserSignedUp => "user.signed_up",
+            Self::UserLoggedIn => "user.logged_in",
+            Self::TwoFAEnabled => "user.2fa_enabled",
+            Self::UserDeleted => "user.deleted",
+        }
+    }
+}
+
+// shared with the subscriber so it can check deliveries really came from us
+#[derive(Debug, Clone)]
+pub struct WebhookSecret(Secret<String>);
+
+impl WebhookSecret {
+    pub fn parse(secret: Secret<String>) -> Result<Self> {
+        match secret.expose_secret().strip_prefix(WEBHOOK_SECRET_PREFIX) {
+            Some(token)
+                if token.len() == WEBHOOK_SECRET_LENGTH
+                    && token.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                Ok(Self(secret))
+            }
+            _ => Err(eyre!("Invalid webhook secret")),
+        }
+    }
+
+    // the timestamp is signed along with the body so a captured delivery can't be replayed later
+    pub fn sign(&self, timestamp: i64, body: &str) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, self.0.expose_secret().as_bytes());
+        let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+        format!("sha256={}", HEXLOWER.encode(tag.as_ref()))
+    }
+}
+
+impl Default for WebhookSecret {
+    fn default() -> Self {
+        let token: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(WEBHOOK_SECRET_LENGTH)
+            .map(char::from)
+            .collect();
+
+        Self(Secret::new(format!("{}{}", WEBHOOK_SECRET_PREFIX, token)))
+    }
+}
+
+impl AsRef<Secret<String>> for WebhookSecret {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: WebhookSecret,
+    pub events: Vec<WebhookEventKind>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    // `allow_private_hosts` lets local development point webhooks at services on the same machine
+    pub fn new(
+        url: &str,
+        events: Vec<WebhookEventKind>,
+        allow_private_hosts: bool,
+    ) -> Result<Self> {
+        let url = Url::parse(url.trim()).map_err(|_| eyre!("Invalid webhook URL"))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(eyre!("Webhook URL must be http or https"));
+        }
+        if !allow_private_hosts && is_private_host(&url) {
+            return Err(eyre!("Webhook URL must not point at a private address"));
+        }
+
+        let events = events.into_iter().fold(Vec::new(), |mut unique, kind| {
+            if !unique.contains(&kind) {
+                unique.push(kind);
+            }
+            unique
+        });
+        if events.is_empty() {
+            return Err(eyre!("A webhook must subscribe to at least one event"));
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            url: url.to_string(),
+            secret: WebhookSecret::default(),
+            events,
+            created_at: Utc::now(),
+        })
+    }
+
+    pub fn subscribes_to(&self, kind: WebhookEventKind) -> bool {
+        self.events.contains(&kind)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn parse(status: &str) -> Result<Self> {
+        match status {
+            "pending" => Ok(Self::Pending),
+            "delivered" => Ok(Self::Delivered),
+            "failed" => Ok(Self::Failed),
+            _ => Err(eyre!("Invalid delivery status: {}", status)),
+        }
+    }
+}
+
+impl AsRef<str> for DeliveryStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+// one event queued for one subscription, kept after it's delivered so it can be inspected and resent
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event: WebhookEventKind,
+    // the exact body that is signed and sent on every attempt
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    pub fn new(subscription_id: Uuid, event: WebhookEventKind, payload: String) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            subscription_id,
+            event,
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+        }
+    }
+
+    pub fn record_success(&mut self, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.status = DeliveryStatus::Delivered;
+        self.last_error = None;
+        self.delivered_at = Some(now);
+    }
+
+    pub fn record_failure(&mut self, error: String, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_error = Some(error);
+
+        if self.attempts >= MAX_DELIVERY_ATTEMPTS {
+            self.status = DeliveryStatus::Failed;
+        } else {
+            self.next_attempt_at = now + retry_delay(self.attempts);
+        }
+    }
+
+    // a manual redelivery gets one more attempt right away, even after retries ran out
+    pub fn schedule_redelivery(&mut self, now: DateTime<Utc>) {
+        self.status = DeliveryStatus::Pending;
+        self.next_attempt_at = now;
+    }
+}
+
+// deliveries are sent from inside our network, so they mustn't be aimed back into it.
+// Host names are resolved and checked again on every delivery.
+fn is_private_host(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_private_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_private_ip(IpAddr::V6(ip)),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        None => true,
+    }
+}
+
+// loopback, link-local, private and unspecified addresses
+pub fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // unique local fc00::/7 and link-local fe80::/10
+                || ip.segments()[0] & 0xfe00 == 0xfc00
+                || ip.segments()[0] & 0xffc0 == 0xfe80
+                || ip.to_ipv4_mapped().is_some_and(is_private_ipv4)
+        }
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+}
+
+// exponential backoff after the given number of failed attempts
+pub fn retry_delay(attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(20);
+    let seconds = FIRST_RETRY_DELAY_SECONDS.saturating_mul(1 << exponent);
+    Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_kinds_round_trip() {
+        for kind in [
+            WebhookEventKind::UserSignedUp,
+            WebhookEventKind::UserLoggedIn,
+            WebhookEventKind::TwoFAEnabled,
+            WebhookEventKind::UserDeleted,
+        ] {
+            assert_eq!(WebhookEventKind::parse(kind.as_ref()).unwrap(), kind);
+        }
+
+        assert!(WebhookEventKind::parse("user.updated").is_err());
+    }
+
+    #[test]
+    fn test_subscription_validates_url_and_events() {
+        let events = vec![WebhookEventKind::UserSignedUp];
+
+        assert!(
+            WebhookSubscription::new("https://example.com/hooks", events.clone(), false).is_ok()
+        );
+        assert!(
+            WebhookSubscription::new("ftp://example.com/hooks", events.clone(), false).is_err()
+        );
+        assert!(WebhookSubscription::new("not a url", events, false).is_err());
+        assert!(WebhookSubscription::new("https://example.com/hooks", vec![], false).is_err());
+    }
+
+    #[test]
+    fn test_subscription_rejects_private_hosts() {
+        let events = vec![WebhookEventKind::UserSignedUp];
+
+        for url in [
+            "http://localhost:8080/hooks",
+            "http://api.localhost/hooks",
+            "http://127.0.0.1/hooks",
+            "http://2130706433/hooks",
+            "http://10.0.0.5/hooks",
+            "http://172.16.0.1/hooks",
+            "http://192.168.1.10/hooks",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0/hooks",
+            "http://[::1]/hooks",
+            "http://[fd00::1]/hooks",
+            "http://[fe80::1]/hooks",
+            "http://[::ffff:10.0.0.5]/hooks",
+        ] {
+            assert!(
+                WebhookSubscription::new(url, events.clone(), false).is_err(),
+                "{} should be rejected",
+                url
+            );
+            assert!(WebhookSubscription::new(url, events.clone(), true).is_ok());
+        }
+
+        assert!(WebhookSubscription::new("http://172.32.0.1/hooks", events.clone(), false).is_ok());
+        assert!(WebhookSubscription::new("https://[2606:4700::1111]/hooks", events, false).is_ok());
+    }
+
+    #[test]
+    fn test_secret_signs_timestamp_and_body() {
+        let secret = WebhookSecret::default();
+        assert!(WebhookSecret::parse(secret.as_ref().clone()).is_ok());
+
+        let signature = secret.sign(1_700_000_000, r#"{"type":"user.deleted"}"#);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(
+            signature,
+            secret.sign(1_700_000_000, r#"{"type":"user.deleted"}"#)
+        );
+        assert_ne!(
+            signature,
+            secret.sign(1_700_000_001, r#"{"type":"user.deleted"}"#)
+        );
+        assert_ne!(
+            signature,
+            WebhookSecret::default().sign(1_700_000_000, r#"{"type":"user.deleted"}"#)
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_up_to_a_cap() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(3), Duration::seconds(120));
+        assert_eq!(retry_delay(40), Duration::hours(6));
+    }
+
+    #[test]
+    fn test_failures_give_up_after_max_attempts() {
+        let now = Utc::now();
+        let mut delivery =
+            WebhookDelivery::new(Uuid::new_v4(), WebhookEventKind::UserDeleted, "{}".into());
+
+        delivery.record_failure("HTTP 500".to_owned(), now);
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert_eq!(delivery.next_attempt_at, now + Duration::seconds(30));
+
+        for _ in 1..MAX_DELIVERY_ATTEMPTS {
+            delivery.record_failure("HTTP 500".to_owned(), now);
+        }
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert_eq!(delivery.attempts, MAX_DELIVERY_ATTEMPTS);
+
+        delivery.schedule_redelivery(now);
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+
+        delivery.record_success(now);
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+        assert_eq!(delivery.delivered_at, Some(now));
+        assert_eq!(delivery.last_error, None);
+    }
+}
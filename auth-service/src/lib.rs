@@ -1,10 +1,24 @@
-use axum::{http::Method, routing::post, serve::Serve, Router};
+use axum::{
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
+    http::Method,
+    middleware::{self, AddExtension},
+    routing::{delete, get, post, put},
+    serve::Serve,
+    Router,
+};
 use redis::{Client, RedisResult};
 use secrecy::{ExposeSecret, Secret};
 use sqlx::{postgres::PgPoolOptions, PgPool};
-use std::error::Error;
+use std::{error::Error, net::SocketAddr};
 use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
-use utils::tracing::{make_span_with_request_id, on_request, on_response};
+use utils::{
+    rate_limit::{
+        rate_limit, ENABLE_2FA_REQUEST_RATE_LIMIT, LOGIN_RATE_LIMIT, MAGIC_LINK_RATE_LIMIT,
+        OAUTH_TOKEN_RATE_LIMIT, PASSWORD_RESET_RATE_LIMIT, RESEND_2FA_RATE_LIMIT,
+        SIGNUP_RATE_LIMIT, VERIFY_2FA_RATE_LIMIT, VERIFY_EMAIL_RESEND_RATE_LIMIT,
+    },
+    tracing::{assign_request_id, make_span_with_request_id, on_request, on_response},
+};
 
 pub mod domain;
 pub mod routes;
@@ -16,7 +30,10 @@ use routes::*;
 use store::AppState;
 
 pub struct Application {
-    server: Serve<Router, Router>,
+    server: Serve<
+        IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+        AddExtension<Router, ConnectInfo<SocketAddr>>,
+    >,
     pub address: String,
 }
 
@@ -28,17 +45,97 @@ impl Application {
         ];
 
         let cors = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
             .allow_credentials(true)
             .allow_origin(allowed_origins);
 
+        let rate_limit_store = app_state.rate_limit_store.clone();
+        let limited =
+            |limit| middleware::from_fn_with_state((rate_limit_store.clone(), limit), rate_limit);
+
         let router = Router::new()
             .nest_service("/", ServeDir::new("assets"))
-            .route("/signup", post(signup))
-            .route("/login", post(login))
+            .route("/signup", post(signup).layer(limited(SIGNUP_RATE_LIMIT)))
+            .route("/verify-email", post(verify_email))
+            .route(
+                "/verify-email/resend",
+                post(resend_verification_email).layer(limited(VERIFY_EMAIL_RESEND_RATE_LIMIT)),
+            )
+            .route("/login", post(login).layer(limited(LOGIN_RATE_LIMIT)))
+            .route(
+                "/magic-link/request",
+                post(request_magic_link).layer(limited(MAGIC_LINK_RATE_LIMIT)),
+            )
+            .route("/magic-link/login", post(magic_link_login))
+            .route("/unlock-account", post(unlock_account))
             .route("/logout", post(logout))
-            .route("/verify-2fa", post(verify_2fa))
+            .route(
+                "/verify-2fa",
+                post(verify_2fa).layer(limited(VERIFY_2FA_RATE_LIMIT)),
+            )
+            .route(
+                "/resend-2fa",
+                post(resend_2fa).layer(limited(RESEND_2FA_RATE_LIMIT)),
+            )
+            .route(
+                "/2fa/enable/request",
+                post(request_enable_2fa).layer(limited(ENABLE_2FA_REQUEST_RATE_LIMIT)),
+            )
+            .route("/2fa/enable", post(enable_2fa))
+            .route("/2fa/disable", post(disable_2fa))
+            .route("/2fa/totp/enroll", post(enroll_totp))
+            .route("/2fa/totp/confirm", post(confirm_totp))
+            .route("/2fa/recovery-codes", post(regenerate_recovery_codes))
             .route("/verify-token", post(verify_token))
+            .route("/token/refresh", post(refresh_token))
+            .route(
+                "/password-reset/request",
+                post(request_password_reset).layer(limited(PASSWORD_RESET_RATE_LIMIT)),
+            )
+            .route("/password-reset/confirm", post(confirm_password_reset))
+            .route("/change-password", post(change_password))
+            .route("/account", delete(delete_account))
+            .route("/api-keys", post(create_api_key).get(list_api_keys))
+            .route("/api-keys/:id", delete(revoke_api_key))
+            .route("/sessions", get(list_sessions).delete(revoke_all_sessions))
+            .route("/sessions/:id", delete(revoke_session))
+            .route("/admin/roles", get(list_roles))
+            .route("/admin/audit-events", get(list_audit_events))
+            .route("/admin/webhooks", post(create_webhook).get(list_webhooks))
+            .route("/admin/webhooks/:id", delete(delete_webhook))
+            .route(
+                "/admin/webhooks/:id/deliveries",
+                get(list_webhook_deliveries),
+            )
+            .route(
+                "/admin/webhooks/:id/deliveries/:delivery_id/redeliver",
+                post(redeliver_webhook),
+            )
+            .route("/admin/users", get(list_users))
+            .route("/admin/users/:email", get(get_user).delete(delete_user))
+            .route("/admin/users/:email/disable", post(disable_user))
+            .route("/admin/users/:email/enable", post(enable_user))
+            .route(
+                "/admin/users/:email/password-reset",
+                post(force_password_reset),
+            )
+            .route("/admin/users/:email/2fa", put(update_user_2fa))
+            .route("/admin/users/:email/roles", get(get_user_roles))
+            .route(
+                "/admin/users/:email/roles/:role",
+                put(assign_role).delete(unassign_role),
+            )
+            .route("/.well-known/jwks.json", get(jwks))
+            .route(
+                "/.well-known/openid-configuration",
+                get(openid_configuration),
+            )
+            .route("/authorize", get(authorize))
+            .route(
+                "/token",
+                post(issue_token).layer(limited(OAUTH_TOKEN_RATE_LIMIT)),
+            )
+            .route("/userinfo", get(userinfo).post(userinfo))
             .with_state(app_state)
             .layer(cors)
             .layer(
@@ -46,11 +143,15 @@ impl Application {
                     .make_span_with(make_span_with_request_id)
                     .on_request(on_request)
                     .on_response(on_response),
-            );
+            )
+            .layer(middleware::from_fn(assign_request_id));
 
         let listener = tokio::net::TcpListener::bind(address).await?;
         let address = listener.local_addr()?.to_string();
-        let server = axum::serve(listener, router);
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        );
         Ok(Application { address, server })
     }
 
@@ -2,11 +2,26 @@ use auth_service::{
     domain::Email,
     get_postgres_pool, get_redis_client,
     services::{
-        PostgresUserStore, PostmarkEmailClient, RedisBannedTokenStore, RedisTwoFACodeStore,
+        webhook_http_client, PostgresApiKeyStore, PostgresAuditLogStore,
+        PostgresMachineClientStore, PostgresOAuthClientStore, PostgresRecoveryCodeStore,
+        PostgresRoleStore, PostgresTotpSecretStore, PostgresUserStore, PostgresWebhookStore,
+        PostmarkEmailClient, RedisAuthorizationCodeStore, RedisBannedTokenStore,
+        RedisEmailVerificationTokenStore, RedisLoginLockoutStore, RedisMagicLinkTokenStore,
+        RedisPasswordResetTokenStore, RedisRateLimitStore, RedisRefreshTokenStore,
+        RedisSessionStore, RedisTwoFACodeStore, WebhookDispatcher,
+    },
+    store::{
+        ApiKeyStoreType, AppState, AuditLogStoreType, AuthorizationCodeStoreType,
+        BannedTokenStoreType, EmailClientType, EmailVerificationTokenStoreType,
+        LoginLockoutStoreType, MachineClientStoreType, MagicLinkTokenStoreType,
+        OAuthClientStoreType, PasswordResetTokenStoreType, RateLimitStoreType,
+        RecoveryCodeStoreType, RefreshTokenStoreType, RoleStoreType, SessionStoreType,
+        TotpSecretStoreType, TwoFACodeStoreType, UserStoreType, WebhookStoreType,
     },
-    store::{AppState, BannedTokenStoreType, EmailClientType, TwoFACodeStoreType, UserStoreType},
     utils::{
-        constants::{prod, DATABASE_URL, POSTMARK_AUTH_TOKEN, REDIS_HOST_NAME},
+        constants::{
+            prod, DATABASE_URL, POSTMARK_AUTH_TOKEN, REDIS_HOST_NAME, WEBHOOK_ALLOW_PRIVATE_HOSTS,
+        },
         tracing::init_tracing,
     },
     Application,
@@ -23,14 +38,72 @@ async fn main() {
 
     let pg_pool = configure_postgresql().await;
     let redis_connection = Arc::new(RwLock::new(configure_redis()));
-    let user_store: UserStoreType = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool)));
+    let user_store: UserStoreType = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool.clone())));
 
     let banned_token_store: BannedTokenStoreType = Arc::new(RwLock::new(
         RedisBannedTokenStore::new(redis_connection.clone()),
     ));
 
-    let two_fa_code_store: TwoFACodeStoreType =
-        Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_connection)));
+    let two_fa_code_store: TwoFACodeStoreType = Arc::new(RwLock::new(RedisTwoFACodeStore::new(
+        redis_connection.clone(),
+    )));
+
+    let refresh_token_store: RefreshTokenStoreType = Arc::new(RwLock::new(
+        RedisRefreshTokenStore::new(redis_connection.clone()),
+    ));
+
+    let password_reset_token_store: PasswordResetTokenStoreType = Arc::new(RwLock::new(
+        RedisPasswordResetTokenStore::new(redis_connection.clone()),
+    ));
+
+    let email_verification_token_store: EmailVerificationTokenStoreType = Arc::new(RwLock::new(
+        RedisEmailVerificationTokenStore::new(redis_connection.clone()),
+    ));
+
+    let login_lockout_store: LoginLockoutStoreType = Arc::new(RwLock::new(
+        RedisLoginLockoutStore::new(redis_connection.clone()),
+    ));
+
+    let rate_limit_store: RateLimitStoreType = Arc::new(RwLock::new(RedisRateLimitStore::new(
+        redis_connection.clone(),
+    )));
+
+    let magic_link_token_store: MagicLinkTokenStoreType = Arc::new(RwLock::new(
+        RedisMagicLinkTokenStore::new(redis_connection.clone()),
+    ));
+
+    let session_store: SessionStoreType = Arc::new(RwLock::new(RedisSessionStore::new(
+        redis_connection.clone(),
+    )));
+
+    let authorization_code_store: AuthorizationCodeStoreType = Arc::new(RwLock::new(
+        RedisAuthorizationCodeStore::new(redis_connection),
+    ));
+
+    let totp_secret_store: TotpSecretStoreType =
+        Arc::new(RwLock::new(PostgresTotpSecretStore::new(pg_pool.clone())));
+
+    let recovery_code_store: RecoveryCodeStoreType =
+        Arc::new(RwLock::new(PostgresRecoveryCodeStore::new(pg_pool.clone())));
+
+    let oauth_client_store: OAuthClientStoreType =
+        Arc::new(RwLock::new(PostgresOAuthClientStore::new(pg_pool.clone())));
+
+    let machine_client_store: MachineClientStoreType = Arc::new(RwLock::new(
+        PostgresMachineClientStore::new(pg_pool.clone()),
+    ));
+
+    let api_key_store: ApiKeyStoreType =
+        Arc::new(RwLock::new(PostgresApiKeyStore::new(pg_pool.clone())));
+
+    let role_store: RoleStoreType = Arc::new(RwLock::new(PostgresRoleStore::new(pg_pool.clone())));
+
+    let audit_log_store: AuditLogStoreType =
+        Arc::new(RwLock::new(PostgresAuditLogStore::new(pg_pool.clone())));
+
+    let webhook_store: WebhookStoreType = Arc::new(RwLock::new(PostgresWebhookStore::new(pg_pool)));
+
+    let webhook_dispatcher = configure_webhook_dispatcher(webhook_store.clone());
 
     let email_client: EmailClientType = Arc::new(configure_postmark_email_client());
 
@@ -38,9 +111,28 @@ async fn main() {
         user_store,
         banned_token_store,
         two_fa_code_store,
+        refresh_token_store,
+        password_reset_token_store,
+        email_verification_token_store,
+        totp_secret_store,
+        recovery_code_store,
+        login_lockout_store,
+        rate_limit_store,
+        magic_link_token_store,
+        oauth_client_store,
+        authorization_code_store,
+        machine_client_store,
+        api_key_store,
+        role_store,
+        session_store,
+        audit_log_store,
+        webhook_store,
+        webhook_dispatcher.wakeup(),
         email_client,
     );
 
+    tokio::spawn(webhook_dispatcher.run());
+
     let app = Application::build(app_state, prod::APP_ADDRESS)
         .await
         .expect("Failed to build app");
@@ -81,3 +173,10 @@ fn configure_postmark_email_client() -> PostmarkEmailClient {
         http_client,
     )
 }
+
+fn configure_webhook_dispatcher(webhook_store: WebhookStoreType) -> WebhookDispatcher {
+    let http_client = webhook_http_client(prod::webhooks::TIMEOUT, *WEBHOOK_ALLOW_PRIVATE_HOSTS)
+        .expect("Failed to build HTTP client");
+
+    WebhookDispatcher::new(webhook_store, http_client, prod::webhooks::POLL_INTERVAL)
+}
@@ -0,0 +1,293 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        AccountStatus, AuthAPIError, Email, TwoFACodeStoreError, User, UserQuery, UserStoreError,
+        WebhookEventKind,
+    },
+    routes::{remove_second_factors, send_password_reset_email},
+    store::AppState,
+    utils::{
+        auth::end_all_sessions,
+        permission::{ManageUsers, RequirePermission},
+        webhooks::publish_event,
+    },
+};
+
+const DEFAULT_USERS_PER_PAGE: u32 = 20;
+
+#[derive(Deserialize)]
+pub struct ListUsersParams {
+    pub email: Option<String>,
+    pub page: Option<u32>,
+    #[serde(rename = "perPage")]
+    pub per_page: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateUserTwoFARequest {
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdminUserResponse {
+    pub email: String,
+    pub status: String,
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+}
+
+impl From<User> for AdminUserResponse {
+    fn from(user: User) -> Self {
+        Self {
+            email: user.email.as_ref().to_owned(),
+            status: user.status.as_ref().to_owned(),
+            requires_2fa: user.requires_2fa,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListUsersResponse {
+    pub users: Vec<AdminUserResponse>,
+    pub page: u32,
+    #[serde(rename = "perPage")]
+    pub per_page: u32,
+    pub total: u64,
+}
+
+#[tracing::instrument(name = "List users", skip_all)]
+pub async fn list_users(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Query(params): Query<ListUsersParams>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let query = match UserQuery::new(
+        params.email,
+        params.page.unwrap_or(1),
+        params.per_page.unwrap_or(DEFAULT_USERS_PER_PAGE),
+    ) {
+        Ok(query) => query,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid query parameters")),
+    };
+
+    let page = match state.user_store.read().await.list_users(&query).await {
+        Ok(page) => page,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(ListUsersResponse {
+            users: page
+                .users
+                .into_iter()
+                .map(AdminUserResponse::from)
+                .collect(),
+            page: query.page,
+            per_page: query.per_page,
+            total: page.total,
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "Get user", skip_all)]
+pub async fn get_user(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(Secret::new(email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid email address")),
+    };
+
+    match state.user_store.read().await.get_user(&email).await {
+        Ok(user) => Ok((StatusCode::OK, Json(AdminUserResponse::from(user)))),
+        Err(UserStoreError::UserNotFound) => Err(AuthAPIError::UserNotFound),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+#[tracing::instrument(name = "Delete user", skip_all)]
+pub async fn delete_user(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(Secret::new(email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid email address")),
+    };
+
+    match state.user_store.write().await.delete_user(&email).await {
+        Ok(_) => (),
+        Err(UserStoreError::UserNotFound) => return Err(AuthAPIError::UserNotFound),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    publish_event(&state, WebhookEventKind::UserDeleted, &email).await;
+
+    end_sessions(&state, &email).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[tracing::instrument(name = "Disable user", skip_all)]
+pub async fn disable_user(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match set_account_status(&state, email, AccountStatus::Disabled).await {
+        Ok(email) => email,
+        Err(e) => return Err(e),
+    };
+
+    end_sessions(&state, &email).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// also marks unverified accounts as verified, the admin vouches for them
+#[tracing::instrument(name = "Enable user", skip_all)]
+pub async fn enable_user(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    match set_account_status(&state, email, AccountStatus::Active).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(e),
+    }
+}
+
+// the old password stops working at once and the user is emailed a reset link
+#[tracing::instrument(name = "Force password reset", skip_all)]
+pub async fn force_password_reset(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(Secret::new(email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid email address")),
+    };
+
+    {
+        let mut user_store = state.user_store.write().await;
+
+        let user = match user_store.get_user(&email).await {
+            Ok(user) => user,
+            Err(UserStoreError::UserNotFound) => return Err(AuthAPIError::UserNotFound),
+            Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+        };
+
+        // a disabled account stays disabled, the reset only replaces its password
+        if user.status != AccountStatus::Disabled {
+            if let Err(e) = user_store
+                .update_account_status(&email, AccountStatus::PasswordResetRequired)
+                .await
+            {
+                return Err(AuthAPIError::UnexpectedError(e.into()));
+            }
+        }
+    }
+
+    end_sessions(&state, &email).await?;
+
+    send_password_reset_email(&state, &email).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[tracing::instrument(name = "Update user 2FA", skip_all)]
+pub async fn update_user_2fa(
+    _: RequirePermission<ManageUsers>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+    Json(request): Json<UpdateUserTwoFARequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(Secret::new(email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid email address")),
+    };
+
+    let already_enabled = match state.user_store.read().await.get_user(&email).await {
+        Ok(user) => user.requires_2fa,
+        Err(UserStoreError::UserNotFound) => return Err(AuthAPIError::UserNotFound),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    if !request.requires_2fa {
+        return match remove_second_factors(&state, &email).await {
+            Ok(_) => Ok(StatusCode::NO_CONTENT),
+            Err(e) => Err(e),
+        };
+    }
+
+    if let Err(e) = state
+        .user_store
+        .write()
+        .await
+        .update_requires_2fa(&email, true)
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    if !already_enabled {
+        publish_event(&state, WebhookEventKind::TwoFAEnabled, &email).await;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn set_account_status(
+    state: &AppState,
+    email: String,
+    status: AccountStatus,
+) -> Result<Email, AuthAPIError> {
+    let email = match Email::parse(Secret::new(email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid email address")),
+    };
+
+    match state
+        .user_store
+        .write()
+        .await
+        .update_account_status(&email, status)
+        .await
+    {
+        Ok(_) => Ok(email),
+        Err(UserStoreError::UserNotFound) => Err(AuthAPIError::UserNotFound),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+// every session, including half-finished 2FA logins
+async fn end_sessions(state: &AppState, email: &Email) -> Result<(), AuthAPIError> {
+    if let Err(e) = end_all_sessions(state, email).await {
+        return Err(AuthAPIError::UnexpectedError(e));
+    }
+
+    match state
+        .two_fa_code_store
+        .write()
+        .await
+        .remove_code(email)
+        .await
+    {
+        Ok(_) | Err(TwoFACodeStoreError::LoginAttemptIdNotFound) => Ok(()),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::{ApiKey, ApiKeyRecord, ApiKeyStoreError, AuthAPIError},
+    store::AppState,
+    utils::auth::AuthenticatedUser,
+};
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(rename = "expiresInDays")]
+    pub expires_in_days: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<ApiKeyRecord> for ApiKeyResponse {
+    fn from(record: ApiKeyRecord) -> Self {
+        Self {
+            id: record.id,
+            name: record.name,
+            scopes: record.scopes,
+            created_at: record.created_at,
+            expires_at: record.expires_at,
+        }
+    }
+}
+
+// the only response that ever contains the key itself
+#[derive(Serialize, Deserialize)]
+pub struct CreateApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
+
+// keys are managed from a browser session, an API key can't create more keys
+#[tracing::instrument(name = "Create API key", skip_all)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let record =
+        match ApiKeyRecord::new(email, request.name, request.scopes, request.expires_in_days) {
+            Ok(record) => record,
+            Err(_) => {
+                return Err(AuthAPIError::BadRequest(
+                    "Invalid API key name, scopes or expiry",
+                ))
+            }
+        };
+
+    let key = ApiKey::default();
+
+    if let Err(e) = state
+        .api_key_store
+        .write()
+        .await
+        .add_key(&key, record.clone())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateApiKeyResponse {
+            key: key.as_ref().expose_secret().to_owned(),
+            api_key: record.into(),
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "List API keys", skip_all)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let keys = match state.api_key_store.read().await.list_keys(&email).await {
+        Ok(keys) => keys,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    let keys: Vec<ApiKeyResponse> = keys.into_iter().map(ApiKeyResponse::from).collect();
+
+    Ok((StatusCode::OK, Json(keys)))
+}
+
+#[tracing::instrument(name = "Revoke API key", skip_all)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid API key id")),
+    };
+
+    match state
+        .api_key_store
+        .write()
+        .await
+        .revoke_key(&email, id)
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(ApiKeyStoreError::KeyNotFound) => Err(AuthAPIError::ApiKeyNotFound),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::{AuditEvent, AuditEventKind, AuditFilter, AuditOutcome, AuditQuery, AuthAPIError},
+    store::AppState,
+    utils::permission::{ReadAuditLog, RequirePermission},
+};
+
+const DEFAULT_AUDIT_EVENTS_PER_PAGE: u32 = 50;
+
+#[derive(Deserialize)]
+pub struct ListAuditEventsParams {
+    pub actor: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub outcome: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub page: Option<u32>,
+    #[serde(rename = "perPage")]
+    pub per_page: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditEventResponse {
+    pub id: Uuid,
+    #[serde(rename = "occurredAt")]
+    pub occurred_at: DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub outcome: String,
+    pub actor: Option<String>,
+    pub ip: Option<String>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl From<AuditEvent> for AuditEventResponse {
+    fn from(event: AuditEvent) -> Self {
+        Self {
+            id: event.id,
+            occurred_at: event.occurred_at,
+            kind: event.kind.as_ref().to_owned(),
+            outcome: event.outcome.as_ref().to_owned(),
+            actor: event.actor,
+            ip: event.ip,
+            user_agent: event.user_agent,
+            request_id: event.request_id,
+            reason: event.reason,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ListAuditEventsResponse {
+    pub events: Vec<AuditEventResponse>,
+    pub page: u32,
+    #[serde(rename = "perPage")]
+    pub per_page: u32,
+    pub total: u64,
+}
+
+#[tracing::instrument(name = "List audit events", skip_all)]
+pub async fn list_audit_events(
+    _: RequirePermission<ReadAuditLog>,
+    State(state): State<AppState>,
+    Query(params): Query<ListAuditEventsParams>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let kind = match params
+        .kind
+        .as_deref()
+        .map(AuditEventKind::parse)
+        .transpose()
+    {
+        Ok(kind) => kind,
+        Err(_) => return Err(AuthAPIError::BadRequest("Unknown event type")),
+    };
+
+    let outcome = match params
+        .outcome
+        .as_deref()
+        .map(AuditOutcome::parse)
+        .transpose()
+    {
+        Ok(outcome) => outcome,
+        Err(_) => return Err(AuthAPIError::BadRequest("Unknown outcome")),
+    };
+
+    let filter = AuditFilter {
+        actor: params.actor,
+        kind,
+        outcome,
+        since: params.since,
+        until: params.until,
+    };
+
+    let query = match AuditQuery::new(
+        filter,
+        params.page.unwrap_or(1),
+        params.per_page.unwrap_or(DEFAULT_AUDIT_EVENTS_PER_PAGE),
+    ) {
+        Ok(query) => query,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid query parameters")),
+    };
+
+    let page = match state.audit_log_store.read().await.list_events(&query).await {
+        Ok(page) => page,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(ListAuditEventsResponse {
+            events: page
+                .events
+                .into_iter()
+                .map(AuditEventResponse::from)
+                .collect(),
+            page: query.page,
+            per_page: query.per_page,
+            total: page.total,
+        }),
+    ))
+}
@@ -0,0 +1,79 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Password, SessionClient},
+    store::AppState,
+    utils::auth::{end_all_sessions, start_session, AuthenticatedUser},
+};
+
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    #[serde(rename = "currentPassword")]
+    pub current_password: Secret<String>,
+    #[serde(rename = "newPassword")]
+    pub new_password: Secret<String>,
+}
+
+#[derive(Serialize)]
+pub struct ChangePasswordResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Change password", skip_all)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    client: SessionClient,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    jar: CookieJar,
+    Json(request): Json<ChangePasswordRequest>,
+) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let current_password = match Password::parse(request.current_password) {
+        Ok(password) => password,
+        Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
+    };
+    let new_password = match Password::parse(request.new_password) {
+        Ok(password) => password,
+        Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
+    };
+
+    {
+        let mut user_store = state.user_store.write().await;
+
+        if user_store
+            .verify_user(&email, &current_password)
+            .await
+            .is_err()
+        {
+            return (jar, Err(AuthAPIError::IncorrectCredentials));
+        }
+
+        if let Err(e) = user_store.update_password(&email, new_password).await {
+            return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+        }
+    }
+
+    // revoke every session, then start a fresh one so only this client stays signed in
+    if let Err(e) = end_all_sessions(&state, &email).await {
+        return (jar, Err(AuthAPIError::UnexpectedError(e)));
+    }
+
+    let (auth_cookie, refresh_cookie) = match start_session(&state, &email, client).await {
+        Ok(cookies) => cookies,
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
+    };
+
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    (
+        updated_jar,
+        Ok((
+            StatusCode::OK,
+            Json(ChangePasswordResponse {
+                message: "Password changed successfully".to_owned(),
+            }),
+        )),
+    )
+}
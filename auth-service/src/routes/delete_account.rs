@@ -0,0 +1,77 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    domain::{AuthAPIError, Password, TwoFACodeStoreError, WebhookEventKind},
+    store::AppState,
+    utils::{
+        auth::{end_all_sessions, AuthenticatedUser},
+        constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+        webhooks::publish_event,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: Secret<String>,
+}
+
+#[tracing::instrument(name = "Delete account", skip_all)]
+pub async fn delete_account(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, token, .. }: AuthenticatedUser,
+    jar: CookieJar,
+    Json(request): Json<DeleteAccountRequest>,
+) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let password = match Password::parse(request.password) {
+        Ok(password) => password,
+        Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
+    };
+
+    {
+        let mut user_store = state.user_store.write().await;
+
+        if user_store.verify_user(&email, &password).await.is_err() {
+            return (jar, Err(AuthAPIError::IncorrectCredentials));
+        }
+
+        if let Err(e) = user_store.delete_user(&email).await {
+            return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+        }
+    }
+
+    publish_event(&state, WebhookEventKind::UserDeleted, &email).await;
+
+    match state
+        .two_fa_code_store
+        .write()
+        .await
+        .remove_code(&email)
+        .await
+    {
+        Ok(_) | Err(TwoFACodeStoreError::LoginAttemptIdNotFound) => (),
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+    }
+
+    if let Err(e) = end_all_sessions(&state, &email).await {
+        return (jar, Err(AuthAPIError::UnexpectedError(e)));
+    }
+
+    if let Err(e) = state
+        .banned_tokens_store
+        .write()
+        .await
+        .add_token(token)
+        .await
+    {
+        return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+    }
+
+    let jar = jar
+        .remove(JWT_COOKIE_NAME)
+        .remove(REFRESH_TOKEN_COOKIE_NAME);
+
+    (jar, Ok(StatusCode::NO_CONTENT))
+}
@@ -0,0 +1,8 @@
+use axum::{response::IntoResponse, Json};
+
+use crate::utils::signing_key::KEY_RING;
+
+#[tracing::instrument(name = "JWKS", skip_all)]
+pub async fn jwks() -> impl IntoResponse {
+    Json(KEY_RING.jwks())
+}
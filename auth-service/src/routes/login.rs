@@ -1,12 +1,25 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use axum_extra::extract::CookieJar;
+use color_eyre::eyre::Result;
 use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{AuthAPIError, Email, LoginAttemptId, Password, TwoFACode},
+    domain::{
+        lockout_duration, AccountStatus, AccountUnlockToken, AuditEventKind, AuthAPIError, Email,
+        LoginAttemptId, Password, SessionClient, TwoFACode, WebhookEventKind,
+    },
     store::AppState,
-    utils::auth::generate_auth_cookie,
+    utils::{
+        audit::{record_outcome, AuditContext},
+        auth::start_session,
+        constants::{
+            AUTH_SERVICE_URL, LOGIN_FAILURE_WINDOW_SECONDS, LOGIN_LOCKOUT_SECONDS,
+            LOGIN_MAX_FAILED_ATTEMPTS,
+        },
+        totp::totp_enabled,
+        webhooks::publish_event,
+    },
 };
 
 #[derive(Deserialize)]
@@ -30,16 +43,53 @@ pub struct RegularAuth {
 #[derive(Serialize, Debug, Deserialize)]
 pub struct TwoFactorAuthResponse {
     pub message: String,
+    // echoed back for clients that didn't submit it, like magic link logins
+    pub email: String,
     #[serde(rename = "loginAttemptId")]
     pub login_attempt_id: String,
+    #[serde(rename = "twoFAMethod")]
+    pub two_fa_method: TwoFAMethod,
+}
+
+#[derive(Serialize, Debug, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum TwoFAMethod {
+    Email,
+    Totp,
 }
 
 #[tracing::instrument(name = "Login", skip_all)]
 pub async fn login(
     State(state): State<AppState>,
+    client: SessionClient,
+    audit: AuditContext,
     jar: CookieJar,
     Json(request): Json<LoginRequest>,
 ) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let actor = request.email.clone();
+
+    let (jar, result) = attempt_login(&state, client, jar, request).await;
+    record_outcome(
+        &state,
+        &audit,
+        AuditEventKind::Login,
+        Some(actor),
+        result.as_ref().err(),
+    )
+    .await;
+
+    (jar, result)
+}
+
+async fn attempt_login(
+    state: &AppState,
+    client: SessionClient,
+    jar: CookieJar,
+    request: LoginRequest,
+) -> (
+    CookieJar,
+    Result<(StatusCode, Json<LoginResponse>), AuthAPIError>,
+) {
     let LoginRequest {
         email: email_json,
         password: password_json,
@@ -54,10 +104,39 @@ pub async fn login(
         Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
     };
 
+    // checked before the password so a locked account can't keep being guessed at
+    match state
+        .login_lockout_store
+        .read()
+        .await
+        .get_lock_ttl(&email)
+        .await
+    {
+        Ok(Some(seconds)) => return (jar, Err(AuthAPIError::AccountLocked(seconds))),
+        Ok(None) => (),
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+    }
+
     let user_store = state.user_store.read().await;
 
     if user_store.verify_user(&email, &password).await.is_err() {
-        return (jar, Err(AuthAPIError::IncorrectCredentials));
+        // unknown addresses aren't tracked, so unlock emails only go to real accounts
+        let user_exists = user_store.get_user(&email).await.is_ok();
+        drop(user_store);
+
+        if !user_exists {
+            return (jar, Err(AuthAPIError::IncorrectCredentials));
+        }
+
+        return match record_failed_login(state, &email).await {
+            Ok(Some(seconds)) => (jar, Err(AuthAPIError::AccountLocked(seconds))),
+            Ok(None) => (jar, Err(AuthAPIError::IncorrectCredentials)),
+            Err(e) => (jar, Err(AuthAPIError::UnexpectedError(e))),
+        };
+    }
+
+    if let Err(e) = state.login_lockout_store.write().await.reset(&email).await {
+        return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
     }
 
     let user = match user_store.get_user(&email).await {
@@ -65,15 +144,88 @@ pub async fn login(
         Err(_) => return (jar, Err(AuthAPIError::IncorrectCredentials)),
     };
 
-    match user.requires_2fa {
-        true => handle_2fa(&user.email, &state, jar).await,
-        false => handle_no_2fa(&user.email, jar).await,
+    if let Err(e) = ensure_can_log_in(user.status) {
+        return (jar, Err(e));
+    }
+
+    complete_login(&user.email, user.requires_2fa, state, client, jar).await
+}
+
+pub(crate) fn ensure_can_log_in(status: AccountStatus) -> Result<(), AuthAPIError> {
+    match status {
+        AccountStatus::Active => Ok(()),
+        AccountStatus::PendingVerification => Err(AuthAPIError::EmailNotVerified),
+        AccountStatus::Disabled => Err(AuthAPIError::AccountDisabled),
+        AccountStatus::PasswordResetRequired => Err(AuthAPIError::PasswordResetRequired),
+    }
+}
+
+// asks for a second factor if the account has one, otherwise starts the session
+#[tracing::instrument(name = "Complete login", skip_all)]
+pub(crate) async fn complete_login(
+    email: &Email,
+    requires_2fa: bool,
+    state: &AppState,
+    client: SessionClient,
+    jar: CookieJar,
+) -> (
+    CookieJar,
+    Result<(StatusCode, Json<LoginResponse>), AuthAPIError>,
+) {
+    // an enrolled authenticator app takes over from emailed codes
+    let totp_enabled = match totp_enabled(&state.totp_secret_store, email).await {
+        Ok(enabled) => enabled,
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
+    };
+
+    match (totp_enabled, requires_2fa) {
+        (true, _) => handle_2fa(email, TwoFAMethod::Totp, state, jar).await,
+        (false, true) => handle_2fa(email, TwoFAMethod::Email, state, jar).await,
+        (false, false) => handle_no_2fa(email, state, client, jar).await,
+    }
+}
+
+// locks the account once too many failures pile up, returning how long it's locked for
+#[tracing::instrument(name = "Record failed login", skip_all)]
+async fn record_failed_login(state: &AppState, email: &Email) -> Result<Option<u64>> {
+    let mut login_lockout_store = state.login_lockout_store.write().await;
+
+    let failures = login_lockout_store
+        .record_failure(email, *LOGIN_FAILURE_WINDOW_SECONDS)
+        .await?;
+
+    if failures < *LOGIN_MAX_FAILED_ATTEMPTS {
+        return Ok(None);
     }
+
+    let previous_lockouts = login_lockout_store.get_lockout_count(email).await?;
+    let lock_seconds = lockout_duration(*LOGIN_LOCKOUT_SECONDS, previous_lockouts);
+    let unlock_token = AccountUnlockToken::default();
+
+    login_lockout_store
+        .lock(email, unlock_token.clone(), lock_seconds)
+        .await?;
+
+    drop(login_lockout_store);
+
+    let link = format!(
+        "{}/?unlock_token={}",
+        AUTH_SERVICE_URL.as_str(),
+        unlock_token.as_ref()
+    );
+
+    state
+        .email_client
+        .send_email(email, "Your account has been locked", &link)
+        .await?;
+
+    Ok(Some(lock_seconds))
 }
 
 #[tracing::instrument(name = "Handle 2FA", skip_all)]
 async fn handle_2fa(
     email: &Email,
+    two_fa_method: TwoFAMethod,
     state: &AppState,
     jar: CookieJar,
 ) -> (
@@ -94,14 +246,16 @@ async fn handle_2fa(
         return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
     }
 
-    let email_client = state.email_client.as_ref();
-    match email_client
-        .send_email(email, "2FA auth code", two_fa_code.as_ref())
-        .await
-    {
-        Ok(_) => (),
-        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
-    };
+    if two_fa_method == TwoFAMethod::Email {
+        let email_client = state.email_client.as_ref();
+        match email_client
+            .send_email(email, "2FA auth code", two_fa_code.as_ref())
+            .await
+        {
+            Ok(_) => (),
+            Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
+        };
+    }
 
     (
         jar,
@@ -109,7 +263,9 @@ async fn handle_2fa(
             StatusCode::PARTIAL_CONTENT,
             Json(LoginResponse::TwoFactorAuth(TwoFactorAuthResponse {
                 message: "2FA required".to_owned(),
+                email: email.as_ref().to_owned(),
                 login_attempt_id: login_attempt_id.as_ref().to_string(),
+                two_fa_method,
             })),
         )),
     )
@@ -118,17 +274,21 @@ async fn handle_2fa(
 #[tracing::instrument(name = "Handle no 2FA", skip_all)]
 async fn handle_no_2fa(
     email: &Email,
+    state: &AppState,
+    client: SessionClient,
     jar: CookieJar,
 ) -> (
     CookieJar,
     Result<(StatusCode, Json<LoginResponse>), AuthAPIError>,
 ) {
-    let auth_cookie = match generate_auth_cookie(email) {
-        Ok(cookie) => cookie,
+    let (auth_cookie, refresh_cookie) = match start_session(state, email, client).await {
+        Ok(cookies) => cookies,
         Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
     };
 
-    let updated_jar = jar.add(auth_cookie);
+    publish_event(state, WebhookEventKind::UserLoggedIn, email).await;
+
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
 
     (
         updated_jar,
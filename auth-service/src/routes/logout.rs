@@ -2,37 +2,87 @@ use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use axum_extra::extract::CookieJar;
 
 use crate::{
-    domain::AuthAPIError,
+    domain::{AuditEventKind, AuthAPIError, RefreshToken, SessionStoreError},
     store::AppState,
-    utils::{auth::validate_token, constants::JWT_COOKIE_NAME},
+    utils::{
+        audit::{record_outcome, AuditContext},
+        auth::AuthenticatedUser,
+        constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+    },
 };
 
 pub async fn logout(
-    state: State<AppState>,
+    State(state): State<AppState>,
+    audit: AuditContext,
+    user: Result<AuthenticatedUser, AuthAPIError>,
     jar: CookieJar,
 ) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
-    let cookie = match jar.get(JWT_COOKIE_NAME) {
-        None => return (jar, Err(AuthAPIError::MissingToken)),
-        Some(cookie) => cookie,
+    let (jar, result) = match user {
+        Ok(user) => end_session(&state, user, jar).await,
+        Err(e) => (jar, Err(e)),
     };
 
-    let token = cookie.value().to_owned();
-    match validate_token(&state.banned_tokens_store, &token).await {
-        Ok(_) => (),
-        Err(_) => return (jar, Err(AuthAPIError::InvalidToken)),
+    let (actor, result) = match result {
+        Ok(email) => (Some(email), Ok(StatusCode::OK)),
+        Err(e) => (None, Err(e)),
     };
+    record_outcome(
+        &state,
+        &audit,
+        AuditEventKind::Logout,
+        actor,
+        result.as_ref().err(),
+    )
+    .await;
 
-    match state
+    (jar, result)
+}
+
+// returns the email of the user who logged out
+async fn end_session(
+    state: &AppState,
+    user: AuthenticatedUser,
+    jar: CookieJar,
+) -> (CookieJar, Result<String, AuthAPIError>) {
+    if let Some(session_id) = user.session_id() {
+        match state
+            .session_store
+            .write()
+            .await
+            .revoke_session(&user.email, &session_id)
+            .await
+        {
+            Ok(_) | Err(SessionStoreError::SessionNotFound) => (),
+            Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+        }
+    }
+
+    // tokens without a session, like those from `/token`, can only be revoked one by one
+    if let Err(e) = state
         .banned_tokens_store
         .write()
         .await
-        .add_token(token)
+        .add_token(user.token)
         .await
     {
-        Err(e) => (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
-        Ok(_) => {
-            let jar = jar.remove(JWT_COOKIE_NAME);
-            (jar, Ok(StatusCode::OK))
+        return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+    }
+
+    if let Some(refresh_token) = jar
+        .get(REFRESH_TOKEN_COOKIE_NAME)
+        .and_then(|cookie| RefreshToken::parse(cookie.value().to_owned()).ok())
+    {
+        let mut refresh_token_store = state.refresh_token_store.write().await;
+
+        if let Ok(record) = refresh_token_store.get_token(&refresh_token).await {
+            if let Err(e) = refresh_token_store.revoke_family(&record.family_id).await {
+                return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+            }
         }
     }
+
+    let jar = jar
+        .remove(JWT_COOKIE_NAME)
+        .remove(REFRESH_TOKEN_COOKIE_NAME);
+    (jar, Ok(user.claims.sub))
 }
@@ -0,0 +1,174 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        AccountStatus, AuditEventKind, AuthAPIError, Email, MagicLinkToken,
+        MagicLinkTokenStoreError, SessionClient, UserStoreError,
+    },
+    routes::{complete_login, ensure_can_log_in, LoginResponse},
+    store::AppState,
+    utils::{
+        audit::{record_outcome, AuditContext},
+        constants::AUTH_SERVICE_URL,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: Secret<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkLoginRequest {
+    pub token: String,
+}
+
+#[derive(Serialize)]
+pub struct MagicLinkResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Request magic link", skip_all)]
+pub async fn request_magic_link(
+    State(state): State<AppState>,
+    Json(request): Json<MagicLinkRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(request.email) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let response = Json(MagicLinkResponse {
+        message: "If the account exists, a login link has been sent".to_owned(),
+    });
+
+    // accounts that can't log in get the same response as unknown ones
+    match state.user_store.read().await.get_user(&email).await {
+        Ok(user) if user.status == AccountStatus::Active => (),
+        Ok(_) | Err(UserStoreError::UserNotFound) => return Ok((StatusCode::OK, response)),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    let token = MagicLinkToken::default();
+
+    if let Err(e) = state
+        .magic_link_token_store
+        .write()
+        .await
+        .add_token(token.clone(), email.clone())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    let link = format!(
+        "{}/?magic_link_token={}",
+        AUTH_SERVICE_URL.as_str(),
+        token.as_ref()
+    );
+
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, "Your login link", &link)
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e));
+    }
+
+    Ok((StatusCode::OK, response))
+}
+
+#[tracing::instrument(name = "Magic link login", skip_all)]
+pub async fn magic_link_login(
+    State(state): State<AppState>,
+    client: SessionClient,
+    audit: AuditContext,
+    jar: CookieJar,
+    Json(request): Json<MagicLinkLoginRequest>,
+) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let (jar, email, result) = attempt_magic_link_login(&state, client, jar, request).await;
+    record_outcome(
+        &state,
+        &audit,
+        AuditEventKind::Login,
+        email.map(|email| email.as_ref().to_owned()),
+        result.as_ref().err(),
+    )
+    .await;
+
+    (jar, result)
+}
+
+// also returns whose link it was, once the token is known to be valid
+async fn attempt_magic_link_login(
+    state: &AppState,
+    client: SessionClient,
+    jar: CookieJar,
+    request: MagicLinkLoginRequest,
+) -> (
+    CookieJar,
+    Option<Email>,
+    Result<(StatusCode, Json<LoginResponse>), AuthAPIError>,
+) {
+    let token = match MagicLinkToken::parse(request.token) {
+        Ok(token) => token,
+        Err(_) => return (jar, None, Err(AuthAPIError::InvalidToken)),
+    };
+
+    let email = match state
+        .magic_link_token_store
+        .write()
+        .await
+        .consume_token(&token)
+        .await
+    {
+        Ok(email) => email,
+        Err(MagicLinkTokenStoreError::TokenNotFound) => {
+            return (jar, None, Err(AuthAPIError::InvalidToken))
+        }
+        Err(e) => return (jar, None, Err(AuthAPIError::UnexpectedError(e.into()))),
+    };
+
+    match state
+        .login_lockout_store
+        .read()
+        .await
+        .get_lock_ttl(&email)
+        .await
+    {
+        Ok(Some(seconds)) => return (jar, Some(email), Err(AuthAPIError::AccountLocked(seconds))),
+        Ok(None) => (),
+        Err(e) => {
+            return (
+                jar,
+                Some(email),
+                Err(AuthAPIError::UnexpectedError(e.into())),
+            )
+        }
+    }
+
+    let user = match state.user_store.read().await.get_user(&email).await {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => {
+            return (jar, Some(email), Err(AuthAPIError::InvalidToken))
+        }
+        Err(e) => {
+            return (
+                jar,
+                Some(email),
+                Err(AuthAPIError::UnexpectedError(e.into())),
+            )
+        }
+    };
+
+    if let Err(e) = ensure_can_log_in(user.status) {
+        return (jar, Some(email), Err(e));
+    }
+
+    // the link only replaces the password, a second factor is still required
+    let (jar, result) = complete_login(&user.email, user.requires_2fa, state, client, jar).await;
+    (jar, Some(email), result)
+}
@@ -1,11 +1,51 @@
+mod admin_users;
+mod api_keys;
+mod audit_events;
+mod change_password;
+mod delete_account;
+mod jwks;
 mod login;
 mod logout;
+mod magic_link;
+mod oauth;
+mod oidc;
+mod password_reset;
+mod recovery_codes;
+mod refresh_token;
+mod resend_2fa;
+mod roles;
+mod sessions;
 mod signup;
+mod totp;
+mod two_fa_settings;
+mod unlock_account;
 mod verify_2fa;
+mod verify_email;
 mod verify_token;
+mod webhooks;
 
+pub use admin_users::*;
+pub use api_keys::*;
+pub use audit_events::*;
+pub use change_password::*;
+pub use delete_account::*;
+pub use jwks::*;
 pub use login::*;
 pub use logout::*;
+pub use magic_link::*;
+pub use oauth::*;
+pub use oidc::*;
+pub use password_reset::*;
+pub use recovery_codes::*;
+pub use refresh_token::*;
+pub use resend_2fa::*;
+pub use roles::*;
+pub use sessions::*;
 pub use signup::*;
+pub use totp::*;
+pub use two_fa_settings::*;
+pub use unlock_account::*;
 pub use verify_2fa::*;
+pub use verify_email::*;
 pub use verify_token::*;
+pub use webhooks::*;
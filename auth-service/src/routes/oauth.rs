@@ -0,0 +1,423 @@
+use axum::{
+    extract::{Query, State},
+    http::{
+        header::{AUTHORIZATION, CACHE_CONTROL},
+        HeaderMap, StatusCode, Uri,
+    },
+    response::{IntoResponse, Redirect},
+    Form, Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use url::{form_urlencoded, Url};
+
+use crate::{
+    domain::{
+        AccountStatus, AuthorizationCode, AuthorizationCodeRecord, AuthorizationCodeStoreError,
+        ClientSecret, CodeChallenge, CodeVerifier, MachineClientStoreError, OAuthClientStoreError,
+        OAuthError, UserStoreError,
+    },
+    routes::ensure_can_log_in,
+    store::AppState,
+    utils::auth::{
+        generate_client_token, generate_id_token, generate_oauth_access_token, AuthenticatedUser,
+        TOKEN_TTL_SECONDS,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct AuthorizeRequest {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub state: Option<String>,
+    pub scope: Option<String>,
+    pub nonce: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: Option<String>,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub client_id: Option<String>,
+    pub code_verifier: Option<Secret<String>>,
+    pub client_secret: Option<Secret<String>>,
+    pub scope: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+#[tracing::instrument(name = "Authorize", skip_all)]
+pub async fn authorize(
+    State(state): State<AppState>,
+    user: Option<AuthenticatedUser>,
+    uri: Uri,
+    Query(request): Query<AuthorizeRequest>,
+) -> Result<Redirect, OAuthError> {
+    let client_id = match request.client_id {
+        Some(client_id) => client_id,
+        None => return Err(OAuthError::InvalidRequest("client_id is required")),
+    };
+
+    let client = match state
+        .oauth_client_store
+        .read()
+        .await
+        .get_client(&client_id)
+        .await
+    {
+        Ok(client) => client,
+        Err(OAuthClientStoreError::ClientNotFound) => return Err(OAuthError::InvalidClient),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    // never redirect to an unregistered uri, not even to report an error
+    let redirect_uri = match request.redirect_uri {
+        Some(uri) if client.allows_redirect_uri(&uri) => uri,
+        _ => {
+            return Err(OAuthError::InvalidRequest(
+                "redirect_uri is not registered for this client",
+            ))
+        }
+    };
+
+    let oauth_state = request.state.as_deref();
+
+    if request.response_type.as_deref() != Some("code") {
+        return client_redirect(
+            &redirect_uri,
+            &[
+                ("error", Some("unsupported_response_type")),
+                ("state", oauth_state),
+            ],
+        );
+    }
+
+    // PKCE is required for every client, and only with S256
+    let code_challenge = match (
+        request.code_challenge,
+        request.code_challenge_method.as_deref(),
+    ) {
+        (Some(challenge), Some("S256")) => match CodeChallenge::parse(challenge) {
+            Ok(challenge) => challenge,
+            Err(_) => {
+                return client_redirect(
+                    &redirect_uri,
+                    &[("error", Some("invalid_request")), ("state", oauth_state)],
+                )
+            }
+        },
+        _ => {
+            return client_redirect(
+                &redirect_uri,
+                &[("error", Some("invalid_request")), ("state", oauth_state)],
+            )
+        }
+    };
+
+    // there is no consent screen, registered clients are trusted as first-party apps
+    let (email, auth_time) = match user {
+        Some(user) => (user.email.clone(), signed_in_at(&state, &user).await?),
+        None => {
+            let return_to: String =
+                form_urlencoded::byte_serialize(uri.to_string().as_bytes()).collect();
+            return Ok(Redirect::to(&format!("/?return_to={}", return_to)));
+        }
+    };
+
+    let code = AuthorizationCode::default();
+    let record = AuthorizationCodeRecord {
+        email,
+        client_id: client.client_id,
+        redirect_uri: redirect_uri.clone(),
+        code_challenge,
+        scope: request.scope,
+        nonce: request.nonce,
+        auth_time,
+    };
+
+    if let Err(e) = state
+        .authorization_code_store
+        .write()
+        .await
+        .add_code(code.clone(), record)
+        .await
+    {
+        return Err(OAuthError::UnexpectedError(e.into()));
+    }
+
+    client_redirect(
+        &redirect_uri,
+        &[("code", Some(code.as_ref())), ("state", oauth_state)],
+    )
+}
+
+#[tracing::instrument(name = "Issue token", skip_all)]
+pub async fn issue_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(request): Form<TokenRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let token = match request.grant_type.as_deref() {
+        Some("authorization_code") => exchange_authorization_code(&state, request).await,
+        Some("client_credentials") => issue_client_token(&state, &headers, request).await,
+        _ => Err(OAuthError::UnsupportedGrantType),
+    };
+
+    token.map(|token| (StatusCode::OK, [(CACHE_CONTROL, "no-store")], Json(token)))
+}
+
+async fn exchange_authorization_code(
+    state: &AppState,
+    request: TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let (code, redirect_uri, client_id, code_verifier) = match (
+        request.code,
+        request.redirect_uri,
+        request.client_id,
+        request.code_verifier,
+    ) {
+        (Some(code), Some(redirect_uri), Some(client_id), Some(code_verifier)) => {
+            (code, redirect_uri, client_id, code_verifier)
+        }
+        _ => {
+            return Err(OAuthError::InvalidRequest(
+                "code, redirect_uri, client_id and code_verifier are required",
+            ))
+        }
+    };
+
+    match state
+        .oauth_client_store
+        .read()
+        .await
+        .get_client(&client_id)
+        .await
+    {
+        Ok(_) => (),
+        Err(OAuthClientStoreError::ClientNotFound) => return Err(OAuthError::InvalidClient),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    }
+
+    let code = match AuthorizationCode::parse(code) {
+        Ok(code) => code,
+        Err(_) => return Err(OAuthError::InvalidGrant),
+    };
+
+    let code_verifier = match CodeVerifier::parse(code_verifier) {
+        Ok(verifier) => verifier,
+        Err(_) => return Err(OAuthError::InvalidRequest("Invalid code_verifier")),
+    };
+
+    // consumed before the checks below, so a code can't be retried with other values
+    let record = match state
+        .authorization_code_store
+        .write()
+        .await
+        .consume_code(&code)
+        .await
+    {
+        Ok(record) => record,
+        Err(AuthorizationCodeStoreError::CodeNotFound) => return Err(OAuthError::InvalidGrant),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    if record.client_id != client_id
+        || record.redirect_uri != redirect_uri
+        || !record.code_challenge.verify(&code_verifier)
+    {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    // the account may have been deleted since the code was issued
+    let user = match state.user_store.read().await.get_user(&record.email).await {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => return Err(OAuthError::InvalidGrant),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    // or stopped being allowed to log in
+    if ensure_can_log_in(user.status).is_err() {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    let access_token = match generate_oauth_access_token(
+        &record.email,
+        user.token_version,
+        &record.client_id,
+        record.scope.clone(),
+    ) {
+        Ok(token) => token,
+        Err(e) => return Err(OAuthError::UnexpectedError(e)),
+    };
+
+    let id_token = if requests_openid(record.scope.as_deref()) {
+        match generate_id_token(
+            &record.email,
+            user.status != AccountStatus::PendingVerification,
+            &record.client_id,
+            record.nonce,
+            record.auth_time,
+        ) {
+            Ok(token) => Some(token),
+            Err(e) => return Err(OAuthError::UnexpectedError(e)),
+        }
+    } else {
+        None
+    };
+
+    Ok(TokenResponse {
+        access_token,
+        token_type: "Bearer".to_owned(),
+        expires_in: TOKEN_TTL_SECONDS,
+        id_token,
+        scope: record.scope,
+    })
+}
+
+async fn issue_client_token(
+    state: &AppState,
+    headers: &HeaderMap,
+    request: TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let (client_id, secret) = match client_authentication(headers, &request) {
+        Some(Ok(credentials)) => credentials,
+        Some(Err(e)) => return Err(e),
+        None => return Err(OAuthError::InvalidClient),
+    };
+
+    let secret = match ClientSecret::parse(secret) {
+        Ok(secret) => secret,
+        Err(_) => return Err(OAuthError::InvalidClient),
+    };
+
+    let client = match state
+        .machine_client_store
+        .read()
+        .await
+        .get_client(&client_id)
+        .await
+    {
+        Ok(client) => client,
+        Err(MachineClientStoreError::ClientNotFound) => return Err(OAuthError::InvalidClient),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    if !client.verify_secret(&secret) {
+        return Err(OAuthError::InvalidClient);
+    }
+
+    let scopes = match client.grant_scopes(request.scope.as_deref()) {
+        Some(scopes) => scopes,
+        None => return Err(OAuthError::InvalidScope),
+    };
+
+    let access_token = match generate_client_token(&client.client_id, &scopes) {
+        Ok(token) => token,
+        Err(e) => return Err(OAuthError::UnexpectedError(e)),
+    };
+
+    Ok(TokenResponse {
+        access_token,
+        token_type: "Bearer".to_owned(),
+        expires_in: TOKEN_TTL_SECONDS,
+        id_token: None,
+        scope: Some(scopes.join(" ")),
+    })
+}
+
+// HTTP basic or form parameters, but not both, per RFC 6749 section 2.3.1
+fn client_authentication(
+    headers: &HeaderMap,
+    request: &TokenRequest,
+) -> Option<Result<(String, Secret<String>), OAuthError>> {
+    let basic = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "));
+
+    match (basic, &request.client_id, &request.client_secret) {
+        (Some(_), _, Some(_)) => Some(Err(OAuthError::InvalidRequest(
+            "Only one client authentication method may be used",
+        ))),
+        (Some(basic), _, None) => {
+            Some(parse_basic_credentials(basic).ok_or(OAuthError::InvalidClient))
+        }
+        (None, Some(client_id), Some(secret)) => Some(Ok((client_id.clone(), secret.clone()))),
+        (None, _, _) => None,
+    }
+}
+
+// both parts are form encoded before being joined, so they may contain a colon
+fn parse_basic_credentials(value: &str) -> Option<(String, Secret<String>)> {
+    let decoded = String::from_utf8(STANDARD.decode(value).ok()?).ok()?;
+    let (client_id, secret) = decoded.split_once(':')?;
+
+    let decode = |value: &str| -> String {
+        form_urlencoded::parse(value.as_bytes())
+            .next()
+            .map(|(key, _)| key.into_owned())
+            .unwrap_or_default()
+    };
+
+    Some((decode(client_id), Secret::new(decode(secret))))
+}
+
+// when the user's session started, which unlike the token's `iat` survives refreshes
+async fn signed_in_at(state: &AppState, user: &AuthenticatedUser) -> Result<usize, OAuthError> {
+    let session_id = match user.session_id() {
+        Some(session_id) => session_id,
+        None => return Ok(user.claims.iat),
+    };
+
+    let session = match state
+        .session_store
+        .read()
+        .await
+        .get_session(&session_id)
+        .await
+    {
+        Ok(session) => session,
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    usize::try_from(session.created_at.timestamp())
+        .map_err(|e| OAuthError::UnexpectedError(e.into()))
+}
+
+// an id token is only issued when the client asked for OpenID Connect
+fn requests_openid(scope: Option<&str>) -> bool {
+    scope.is_some_and(|scope| scope.split(' ').any(|value| value == "openid"))
+}
+
+// appends to the registered uri, keeping any query it already has
+fn client_redirect(
+    redirect_uri: &str,
+    params: &[(&str, Option<&str>)],
+) -> Result<Redirect, OAuthError> {
+    let mut location = match Url::parse(redirect_uri) {
+        Ok(url) => url,
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    for (key, value) in params {
+        if let Some(value) = value {
+            location.query_pairs_mut().append_pair(key, value);
+        }
+    }
+
+    Ok(Redirect::to(location.as_str()))
+}
@@ -0,0 +1,117 @@
+use axum::{
+    extract::State,
+    http::{header::AUTHORIZATION, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AccountStatus, Email, OAuthError, UserStoreError},
+    store::AppState,
+    utils::{auth::validate_oauth_token, constants::AUTH_SERVICE_URL},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub response_types_supported: Vec<String>,
+    pub grant_types_supported: Vec<String>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub scopes_supported: Vec<String>,
+    pub claims_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub code_challenge_methods_supported: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserInfoResponse {
+    pub sub: String,
+    pub email: String,
+    pub email_verified: bool,
+}
+
+#[tracing::instrument(name = "OpenID configuration", skip_all)]
+pub async fn openid_configuration() -> impl IntoResponse {
+    let url = AUTH_SERVICE_URL.as_str();
+    let values = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+
+    Json(OpenIdConfiguration {
+        issuer: url.to_owned(),
+        authorization_endpoint: format!("{}/authorize", url),
+        token_endpoint: format!("{}/token", url),
+        userinfo_endpoint: format!("{}/userinfo", url),
+        jwks_uri: format!("{}/.well-known/jwks.json", url),
+        response_types_supported: values(&["code"]),
+        grant_types_supported: values(&["authorization_code", "client_credentials"]),
+        subject_types_supported: values(&["public"]),
+        id_token_signing_alg_values_supported: values(&["EdDSA"]),
+        scopes_supported: values(&["openid", "email"]),
+        claims_supported: values(&[
+            "iss",
+            "sub",
+            "aud",
+            "exp",
+            "iat",
+            "auth_time",
+            "nonce",
+            "email",
+            "email_verified",
+        ]),
+        // OpenID Connect clients are public and authenticate the code exchange with PKCE
+        // alone. Secrets are only checked for machine clients' client_credentials grant.
+        token_endpoint_auth_methods_supported: values(&["none"]),
+        code_challenge_methods_supported: values(&["S256"]),
+    })
+}
+
+#[tracing::instrument(name = "User info", skip_all)]
+pub async fn userinfo(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, OAuthError> {
+    let token = match headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(token) => token,
+        None => return Err(OAuthError::InvalidToken),
+    };
+
+    // only tokens issued to an OAuth client, the session's own token isn't accepted here
+    let claims = match validate_oauth_token(
+        &state.banned_tokens_store,
+        &state.session_store,
+        &state.user_store,
+        token,
+    )
+    .await
+    {
+        Ok(claims) => claims,
+        Err(_) => return Err(OAuthError::InvalidToken),
+    };
+
+    let email = match Email::parse(Secret::new(claims.sub)) {
+        Ok(email) => email,
+        Err(_) => return Err(OAuthError::InvalidToken),
+    };
+
+    let user = match state.user_store.read().await.get_user(&email).await {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => return Err(OAuthError::InvalidToken),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    Ok(Json(UserInfoResponse {
+        sub: user.email.as_ref().to_owned(),
+        email: user.email.as_ref().to_owned(),
+        email_verified: user.status != AccountStatus::PendingVerification,
+    }))
+}
@@ -0,0 +1,150 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        AccountStatus, AuthAPIError, Email, Password, PasswordResetToken,
+        PasswordResetTokenStoreError, UserStoreError,
+    },
+    store::AppState,
+    utils::{auth::end_all_sessions, constants::AUTH_SERVICE_URL},
+};
+
+#[derive(Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: Secret<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetConfirmRequest {
+    pub token: String,
+    pub password: Secret<String>,
+}
+
+#[derive(Serialize)]
+pub struct PasswordResetResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Request password reset", skip_all)]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    Json(request): Json<PasswordResetRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(request.email) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let response = Json(PasswordResetResponse {
+        message: "If the account exists, a password reset email has been sent".to_owned(),
+    });
+
+    // respond the same way for unknown emails so accounts can't be enumerated
+    match state.user_store.read().await.get_user(&email).await {
+        Ok(_) => (),
+        Err(UserStoreError::UserNotFound) => return Ok((StatusCode::OK, response)),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    send_password_reset_email(&state, &email).await?;
+
+    Ok((StatusCode::OK, response))
+}
+
+// also used when an admin forces a reset
+pub(crate) async fn send_password_reset_email(
+    state: &AppState,
+    email: &Email,
+) -> Result<(), AuthAPIError> {
+    let token = PasswordResetToken::default();
+
+    if let Err(e) = state
+        .password_reset_token_store
+        .write()
+        .await
+        .add_token(token.clone(), email.clone())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    let link = format!(
+        "{}/?password_reset_token={}",
+        AUTH_SERVICE_URL.as_str(),
+        token.as_ref()
+    );
+
+    match state
+        .email_client
+        .send_email(email, "Password reset", &link)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e)),
+    }
+}
+
+#[tracing::instrument(name = "Confirm password reset", skip_all)]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    Json(request): Json<PasswordResetConfirmRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let token = match PasswordResetToken::parse(request.token) {
+        Ok(token) => token,
+        Err(_) => return Err(AuthAPIError::InvalidToken),
+    };
+
+    let password = match Password::parse(request.password) {
+        Ok(password) => password,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let email = match state
+        .password_reset_token_store
+        .write()
+        .await
+        .consume_token(&token)
+        .await
+    {
+        Ok(email) => email,
+        Err(PasswordResetTokenStoreError::TokenNotFound) => return Err(AuthAPIError::InvalidToken),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    {
+        let mut user_store = state.user_store.write().await;
+
+        match user_store.update_password(&email, password).await {
+            Ok(_) => (),
+            Err(UserStoreError::UserNotFound) => return Err(AuthAPIError::InvalidToken),
+            Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+        }
+
+        let user = match user_store.get_user(&email).await {
+            Ok(user) => user,
+            Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+        };
+
+        if user.status == AccountStatus::PasswordResetRequired {
+            if let Err(e) = user_store
+                .update_account_status(&email, AccountStatus::Active)
+                .await
+            {
+                return Err(AuthAPIError::UnexpectedError(e.into()));
+            }
+        }
+    }
+
+    if let Err(e) = end_all_sessions(&state, &email).await {
+        return Err(AuthAPIError::UnexpectedError(e));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(PasswordResetResponse {
+            message: "Password updated successfully".to_owned(),
+        }),
+    ))
+}
@@ -0,0 +1,67 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Password},
+    store::AppState,
+    utils::{
+        auth::AuthenticatedUser,
+        totp::{generate_recovery_codes, totp_enabled},
+    },
+};
+
+#[derive(Deserialize)]
+pub struct RegenerateRecoveryCodesRequest {
+    pub password: Secret<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecoveryCodesResponse {
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[tracing::instrument(name = "Regenerate recovery codes", skip_all)]
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    Json(request): Json<RegenerateRecoveryCodesRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let password = match Password::parse(request.password) {
+        Ok(password) => password,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let requires_2fa = {
+        let user_store = state.user_store.read().await;
+
+        if user_store.verify_user(&email, &password).await.is_err() {
+            return Err(AuthAPIError::IncorrectCredentials);
+        }
+
+        match user_store.get_user(&email).await {
+            Ok(user) => user.requires_2fa,
+            Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+        }
+    };
+
+    let totp_enabled = match totp_enabled(&state.totp_secret_store, &email).await {
+        Ok(enabled) => enabled,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    };
+
+    if !requires_2fa && !totp_enabled {
+        return Err(AuthAPIError::TwoFANotEnabled);
+    }
+
+    let recovery_codes = match generate_recovery_codes(&state.recovery_code_store, &email).await {
+        Ok(codes) => codes,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(RecoveryCodesResponse { recovery_codes }),
+    ))
+}
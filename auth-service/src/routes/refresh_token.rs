@@ -0,0 +1,122 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    domain::{
+        AuthAPIError, RefreshToken, RefreshTokenStoreError, SessionStoreError, UserStoreError,
+    },
+    store::AppState,
+    utils::{
+        audit::{record_token_rejection, AuditContext},
+        auth::{generate_auth_cookie, generate_refresh_cookie},
+        constants::REFRESH_TOKEN_COOKIE_NAME,
+    },
+};
+
+#[tracing::instrument(name = "Refresh token", skip_all)]
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let cookie = match jar.get(REFRESH_TOKEN_COOKIE_NAME) {
+        None => return (jar, Err(AuthAPIError::MissingToken)),
+        Some(cookie) => cookie,
+    };
+
+    let refresh_token = match RefreshToken::parse(cookie.value().to_owned()) {
+        Ok(token) => token,
+        Err(_) => {
+            record_token_rejection(&state, &audit, None, "Malformed refresh token").await;
+            return (jar, Err(AuthAPIError::InvalidToken));
+        }
+    };
+
+    let record = {
+        let mut refresh_token_store = state.refresh_token_store.write().await;
+
+        match refresh_token_store.consume_token(&refresh_token).await {
+            Ok(record) => record,
+            Err(RefreshTokenStoreError::TokenReused(family_id)) => {
+                // a rotated token was presented again, so the whole family is compromised
+                tracing::warn!("Refresh token reuse detected, revoking token family");
+                if let Err(e) = refresh_token_store.revoke_family(&family_id).await {
+                    return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+                }
+                drop(refresh_token_store);
+                record_token_rejection(&state, &audit, None, "Refresh token reused").await;
+                let jar = jar.remove(REFRESH_TOKEN_COOKIE_NAME);
+                return (jar, Err(AuthAPIError::InvalidToken));
+            }
+            Err(RefreshTokenStoreError::UnexpectedError(e)) => {
+                return (jar, Err(AuthAPIError::UnexpectedError(e)))
+            }
+            Err(_) => {
+                drop(refresh_token_store);
+                record_token_rejection(&state, &audit, None, "Unknown refresh token").await;
+                return (jar, Err(AuthAPIError::InvalidToken));
+            }
+        }
+    };
+
+    let actor = Some(record.email.as_ref().to_owned());
+
+    // the family is the session, so a revoked session can't be refreshed back to life
+    match state
+        .session_store
+        .write()
+        .await
+        .touch_session(&record.family_id)
+        .await
+    {
+        Ok(_) => (),
+        Err(SessionStoreError::SessionNotFound) => {
+            if let Err(e) = state
+                .refresh_token_store
+                .write()
+                .await
+                .revoke_family(&record.family_id)
+                .await
+            {
+                return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+            }
+            record_token_rejection(&state, &audit, actor, "Session revoked").await;
+            let jar = jar.remove(REFRESH_TOKEN_COOKIE_NAME);
+            return (jar, Err(AuthAPIError::InvalidToken));
+        }
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+    }
+
+    let token_version = match state.user_store.read().await.get_user(&record.email).await {
+        Ok(user) => user.token_version,
+        Err(UserStoreError::UserNotFound) => {
+            record_token_rejection(&state, &audit, actor, "User no longer exists").await;
+            return (jar, Err(AuthAPIError::InvalidToken));
+        }
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+    };
+
+    let auth_cookie = match generate_auth_cookie(
+        &state.role_store,
+        &record.email,
+        token_version,
+        &record.family_id,
+    )
+    .await
+    {
+        Ok(cookie) => cookie,
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
+    };
+
+    let refresh_cookie =
+        match generate_refresh_cookie(&state.refresh_token_store, &record.email, record.family_id)
+            .await
+        {
+            Ok(cookie) => cookie,
+            Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
+        };
+
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    (updated_jar, Ok(StatusCode::OK))
+}
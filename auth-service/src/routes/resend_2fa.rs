@@ -0,0 +1,97 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Email, LoginAttemptId, TwoFACode, TwoFACodeStoreError},
+    store::AppState,
+    utils::{
+        constants::{MAX_TWO_FA_RESENDS, TWO_FA_RESEND_COOLDOWN_SECONDS},
+        totp::totp_enabled,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct Resend2FARequest {
+    pub email: String,
+    #[serde(rename = "loginAttemptId")]
+    pub login_attempt_id: String,
+}
+
+#[derive(Serialize)]
+pub struct Resend2FAResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Resend 2FA code", skip_all)]
+pub async fn resend_2fa(
+    State(state): State<AppState>,
+    Json(request): Json<Resend2FARequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(Secret::new(request.email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+    let login_attempt_id = match LoginAttemptId::parse(request.login_attempt_id) {
+        Ok(id) => id,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let mut two_fa_code_store = state.two_fa_code_store.write().await;
+
+    match two_fa_code_store.get_code(&email).await {
+        Ok((current_id, _)) if current_id == login_attempt_id => (),
+        Ok(_) | Err(TwoFACodeStoreError::LoginAttemptIdNotFound) => {
+            return Err(AuthAPIError::IncorrectCredentials)
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    // codes for an authenticator app aren't emailed, so there's nothing to resend
+    match totp_enabled(&state.totp_secret_store, &email).await {
+        Ok(false) => (),
+        Ok(true) => return Err(AuthAPIError::TotpAlreadyEnabled),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    }
+
+    let resends = match two_fa_code_store
+        .record_resend(&login_attempt_id, TWO_FA_RESEND_COOLDOWN_SECONDS)
+        .await
+    {
+        Ok(resends) => resends,
+        Err(TwoFACodeStoreError::ResendCooldown(seconds)) => {
+            return Err(AuthAPIError::TooManyRequests(seconds))
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    if resends > MAX_TWO_FA_RESENDS {
+        return Err(AuthAPIError::TooManyResends);
+    }
+
+    // the login attempt id is kept, so failed guesses still count against it
+    let two_fa_code = TwoFACode::default();
+    if let Err(e) = two_fa_code_store
+        .add_code(email.clone(), login_attempt_id, two_fa_code.clone())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    drop(two_fa_code_store);
+
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, "2FA auth code", two_fa_code.as_ref())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(Resend2FAResponse {
+            message: "2FA code resent".to_owned(),
+        }),
+    ))
+}
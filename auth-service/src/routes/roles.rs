@@ -0,0 +1,166 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Email, Role, RoleStoreError, UserAccess, UserStoreError},
+    store::AppState,
+    utils::permission::{AssignRoles, RequirePermission},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct RoleResponse {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+}
+
+impl From<Role> for RoleResponse {
+    fn from(role: Role) -> Self {
+        Self {
+            name: role.name,
+            description: role.description,
+            permissions: role.permissions,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserRolesResponse {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+impl From<UserAccess> for UserRolesResponse {
+    fn from(access: UserAccess) -> Self {
+        Self {
+            roles: access.roles,
+            permissions: access.permissions,
+        }
+    }
+}
+
+#[tracing::instrument(name = "List roles", skip_all)]
+pub async fn list_roles(
+    _: RequirePermission<AssignRoles>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let roles = match state.role_store.read().await.list_roles().await {
+        Ok(roles) => roles,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    let roles: Vec<RoleResponse> = roles.into_iter().map(RoleResponse::from).collect();
+
+    Ok((StatusCode::OK, Json(roles)))
+}
+
+#[tracing::instrument(name = "Get user roles", skip_all)]
+pub async fn get_user_roles(
+    _: RequirePermission<AssignRoles>,
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match existing_user(&state, email).await {
+        Ok(email) => email,
+        Err(e) => return Err(e),
+    };
+
+    match state.role_store.read().await.get_user_access(&email).await {
+        Ok(access) => Ok((StatusCode::OK, Json(UserRolesResponse::from(access)))),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+#[tracing::instrument(name = "Assign role", skip_all)]
+pub async fn assign_role(
+    _: RequirePermission<AssignRoles>,
+    State(state): State<AppState>,
+    Path((email, role)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match existing_user(&state, email).await {
+        Ok(email) => email,
+        Err(e) => return Err(e),
+    };
+
+    match state
+        .role_store
+        .write()
+        .await
+        .assign_role(&email, &role)
+        .await
+    {
+        Ok(_) => (),
+        Err(RoleStoreError::RoleNotFound) => return Err(AuthAPIError::RoleNotFound),
+        Err(RoleStoreError::UserNotFound) => return Err(AuthAPIError::UserNotFound),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    expire_access_tokens(&state, &email).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[tracing::instrument(name = "Unassign role", skip_all)]
+pub async fn unassign_role(
+    _: RequirePermission<AssignRoles>,
+    State(state): State<AppState>,
+    Path((email, role)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match existing_user(&state, email).await {
+        Ok(email) => email,
+        Err(e) => return Err(e),
+    };
+
+    let removed = match state
+        .role_store
+        .write()
+        .await
+        .unassign_role(&email, &role)
+        .await
+    {
+        Ok(removed) => removed,
+        Err(RoleStoreError::RoleNotFound) => return Err(AuthAPIError::RoleNotFound),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    if removed {
+        expire_access_tokens(&state, &email).await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// tokens carry a snapshot of the roles, so the user has to refresh to pick up the change.
+// Their sessions stay open
+async fn expire_access_tokens(state: &AppState, email: &Email) -> Result<(), AuthAPIError> {
+    match state
+        .user_store
+        .write()
+        .await
+        .bump_token_version(email)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(UserStoreError::UserNotFound) => Err(AuthAPIError::UserNotFound),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+async fn existing_user(state: &AppState, email: String) -> Result<Email, AuthAPIError> {
+    let email = match Email::parse(Secret::new(email)) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid email address")),
+    };
+
+    match state.user_store.read().await.get_user(&email).await {
+        Ok(_) => Ok(email),
+        Err(UserStoreError::UserNotFound) => Err(AuthAPIError::UserNotFound),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Session, SessionId, SessionStoreError},
+    store::AppState,
+    utils::{
+        auth::{end_all_sessions, AuthenticatedUser},
+        constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionResponse {
+    pub id: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: DateTime<Utc>,
+    pub ip: Option<String>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    // whether this is the session making the request
+    pub current: bool,
+}
+
+impl SessionResponse {
+    fn new(session: Session, current: Option<&SessionId>) -> Self {
+        Self {
+            current: current == Some(&session.id),
+            id: session.id.as_ref().to_owned(),
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            ip: session.client.ip,
+            user_agent: session.client.user_agent,
+        }
+    }
+}
+
+#[tracing::instrument(name = "List sessions", skip_all)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let current = user.session_id();
+    let email = user.email;
+
+    let sessions = match state.session_store.read().await.list_sessions(&email).await {
+        Ok(sessions) => sessions,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    let sessions: Vec<SessionResponse> = sessions
+        .into_iter()
+        .map(|session| SessionResponse::new(session, current.as_ref()))
+        .collect();
+
+    Ok((StatusCode::OK, Json(sessions)))
+}
+
+#[tracing::instrument(name = "Revoke session", skip_all)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    jar: CookieJar,
+    Path(id): Path<String>,
+) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let current = user.session_id();
+    let email = user.email;
+
+    let session_id = match SessionId::parse(id) {
+        Ok(id) => id,
+        Err(_) => return (jar, Err(AuthAPIError::SessionNotFound)),
+    };
+
+    match state
+        .session_store
+        .write()
+        .await
+        .revoke_session(&email, &session_id)
+        .await
+    {
+        Ok(_) => (),
+        Err(SessionStoreError::SessionNotFound) => {
+            return (jar, Err(AuthAPIError::SessionNotFound))
+        }
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+    }
+
+    if let Err(e) = state
+        .refresh_token_store
+        .write()
+        .await
+        .revoke_family(&session_id)
+        .await
+    {
+        return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+    }
+
+    let jar = if current == Some(session_id) {
+        jar.remove(JWT_COOKIE_NAME)
+            .remove(REFRESH_TOKEN_COOKIE_NAME)
+    } else {
+        jar
+    };
+
+    (jar, Ok(StatusCode::NO_CONTENT))
+}
+
+// "log out everywhere", this session included
+#[tracing::instrument(name = "Revoke all sessions", skip_all)]
+pub async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    jar: CookieJar,
+) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    if let Err(e) = end_all_sessions(&state, &email).await {
+        return (jar, Err(AuthAPIError::UnexpectedError(e)));
+    }
+
+    let jar = jar
+        .remove(JWT_COOKIE_NAME)
+        .remove(REFRESH_TOKEN_COOKIE_NAME);
+
+    (jar, Ok(StatusCode::NO_CONTENT))
+}
@@ -1,17 +1,43 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use secrecy::Secret;
+use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{AuthAPIError, Email, Password, User},
+    domain::{AuditEventKind, AuthAPIError, Email, Password, User, WebhookEventKind},
+    routes::send_verification_email,
+    utils::{
+        audit::{record_outcome, AuditContext},
+        totp::generate_recovery_codes,
+        webhooks::publish_event,
+    },
     AppState,
 };
 
 #[tracing::instrument(name = "Signup", skip_all)]
 pub async fn signup(
-    state: State<AppState>,
+    State(state): State<AppState>,
+    audit: AuditContext,
     Json(request): Json<SignupRequest>,
 ) -> Result<impl IntoResponse, AuthAPIError> {
+    let actor = request.email.expose_secret().clone();
+
+    let result = create_account(&state, request).await;
+    record_outcome(
+        &state,
+        &audit,
+        AuditEventKind::Signup,
+        Some(actor),
+        result.as_ref().err(),
+    )
+    .await;
+
+    result
+}
+
+async fn create_account(
+    state: &AppState,
+    request: SignupRequest,
+) -> Result<(StatusCode, Json<SignupResponse>), AuthAPIError> {
     let SignupRequest {
         email,
         password,
@@ -35,18 +61,35 @@ pub async fn signup(
         Ok(_) => return Err(AuthAPIError::UserAlreadyExists),
     };
 
-    let result = user_store.add_user(user).await;
+    let email = user.email.clone();
 
-    match result {
-        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
-        Ok(_) => {
-            let response = Json(SignupResponse {
-                message: "User Created Successfully!".to_string(),
-            });
+    if let Err(e) = user_store.add_user(user).await {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
 
-            Ok((StatusCode::CREATED, response))
-        }
+    drop(user_store);
+
+    if let Err(e) = send_verification_email(state, &email).await {
+        return Err(AuthAPIError::UnexpectedError(e));
     }
+
+    let recovery_codes = if requires_2fa {
+        match generate_recovery_codes(&state.recovery_code_store, &email).await {
+            Ok(codes) => Some(codes),
+            Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+        }
+    } else {
+        None
+    };
+
+    publish_event(state, WebhookEventKind::UserSignedUp, &email).await;
+
+    let response = Json(SignupResponse {
+        message: "User Created Successfully!".to_string(),
+        recovery_codes,
+    });
+
+    Ok((StatusCode::CREATED, response))
 }
 
 #[derive(Deserialize)]
@@ -57,7 +100,13 @@ pub struct SignupRequest {
     pub requires_2fa: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct SignupResponse {
     pub message: String,
+    #[serde(
+        rename = "recoveryCodes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recovery_codes: Option<Vec<String>>,
 }
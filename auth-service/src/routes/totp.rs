@@ -0,0 +1,132 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, TotpSecret, TotpSecretStoreError, TwoFACode, WebhookEventKind},
+    store::AppState,
+    utils::{
+        auth::AuthenticatedUser,
+        totp::{consume_code, generate_recovery_codes, otpauth_uri},
+        webhooks::publish_event,
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct EnrollTotpResponse {
+    pub secret: String,
+    #[serde(rename = "otpauthUri")]
+    pub otpauth_uri: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConfirmTotpResponse {
+    pub message: String,
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[tracing::instrument(name = "Enroll TOTP", skip_all)]
+pub async fn enroll_totp(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    // replacing a confirmed secret has to go through disabling it first
+    match state
+        .totp_secret_store
+        .read()
+        .await
+        .get_secret(&email)
+        .await
+    {
+        Ok(record) if record.confirmed => return Err(AuthAPIError::TotpAlreadyEnabled),
+        Ok(_) | Err(TotpSecretStoreError::SecretNotFound) => (),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    let secret = TotpSecret::default();
+
+    let uri = match otpauth_uri(&secret, &email) {
+        Ok(uri) => uri,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    };
+
+    if let Err(e) = state
+        .totp_secret_store
+        .write()
+        .await
+        .set_pending_secret(email, secret.clone())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(EnrollTotpResponse {
+            secret: secret.as_ref().expose_secret().to_owned(),
+            otpauth_uri: uri,
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "Confirm TOTP", skip_all)]
+pub async fn confirm_totp(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    Json(request): Json<ConfirmTotpRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let code = match TwoFACode::parse(request.code) {
+        Ok(code) => code,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let record = match state
+        .totp_secret_store
+        .read()
+        .await
+        .get_secret(&email)
+        .await
+    {
+        Ok(record) if record.confirmed => return Err(AuthAPIError::TotpAlreadyEnabled),
+        Ok(record) => record,
+        Err(TotpSecretStoreError::SecretNotFound) => return Err(AuthAPIError::InvalidCredentials),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    match consume_code(&state.totp_secret_store, &email, &record.secret, &code).await {
+        Ok(true) => (),
+        Ok(false) => return Err(AuthAPIError::IncorrectCredentials),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    }
+
+    if let Err(e) = state
+        .totp_secret_store
+        .write()
+        .await
+        .confirm_secret(&email)
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    let recovery_codes = match generate_recovery_codes(&state.recovery_code_store, &email).await {
+        Ok(codes) => codes,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    };
+
+    publish_event(&state, WebhookEventKind::TwoFAEnabled, &email).await;
+
+    Ok((
+        StatusCode::OK,
+        Json(ConfirmTotpResponse {
+            message: "Authenticator app enabled".to_owned(),
+            recovery_codes,
+        }),
+    ))
+}
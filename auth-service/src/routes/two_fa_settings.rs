@@ -0,0 +1,236 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        AuthAPIError, Email, LoginAttemptId, Password, TotpSecretStoreError, TwoFACode,
+        TwoFACodeStoreError, WebhookEventKind,
+    },
+    store::AppState,
+    utils::{
+        auth::AuthenticatedUser,
+        totp::{generate_recovery_codes, totp_enabled},
+        webhooks::publish_event,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct Enable2FARequest {
+    #[serde(rename = "2FACode")]
+    pub two_fa_code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Enable2FAResponse {
+    pub message: String,
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Disable2FARequest {
+    pub password: Secret<String>,
+}
+
+#[derive(Serialize)]
+pub struct TwoFASettingsResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Request enable 2FA", skip_all)]
+pub async fn request_enable_2fa(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    if two_fa_enabled(&state, &email).await? {
+        return Err(AuthAPIError::TwoFAAlreadyEnabled);
+    }
+
+    // kept alongside an attempt id nobody is given, so the code can't complete a login
+    let two_fa_code = TwoFACode::default();
+
+    if let Err(e) = state
+        .two_fa_code_store
+        .write()
+        .await
+        .add_code(
+            email.clone(),
+            LoginAttemptId::default(),
+            two_fa_code.clone(),
+        )
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, "2FA enable code", two_fa_code.as_ref())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(TwoFASettingsResponse {
+            message: "A code has been sent to your email".to_owned(),
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "Enable 2FA", skip_all)]
+pub async fn enable_2fa(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    Json(request): Json<Enable2FARequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let two_fa_code = match TwoFACode::parse(request.two_fa_code) {
+        Ok(code) => code,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    if two_fa_enabled(&state, &email).await? {
+        return Err(AuthAPIError::TwoFAAlreadyEnabled);
+    }
+
+    {
+        let mut two_fa_code_store = state.two_fa_code_store.write().await;
+
+        let (login_attempt_id, expected_code) = match two_fa_code_store.get_code(&email).await {
+            Ok(tuple) => tuple,
+            Err(TwoFACodeStoreError::LoginAttemptIdNotFound) => {
+                return Err(AuthAPIError::IncorrectCredentials)
+            }
+            Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+        };
+
+        if expected_code != two_fa_code {
+            if let Err(e) = two_fa_code_store
+                .record_failed_attempt(&email, &login_attempt_id)
+                .await
+            {
+                return Err(AuthAPIError::UnexpectedError(e.into()));
+            }
+
+            return Err(AuthAPIError::IncorrectCredentials);
+        }
+
+        if let Err(e) = two_fa_code_store.remove_code(&email).await {
+            return Err(AuthAPIError::UnexpectedError(e.into()));
+        }
+    }
+
+    if let Err(e) = state
+        .user_store
+        .write()
+        .await
+        .update_requires_2fa(&email, true)
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    let recovery_codes = match generate_recovery_codes(&state.recovery_code_store, &email).await {
+        Ok(codes) => codes,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e)),
+    };
+
+    publish_event(&state, WebhookEventKind::TwoFAEnabled, &email).await;
+
+    Ok((
+        StatusCode::OK,
+        Json(Enable2FAResponse {
+            message: "2FA enabled".to_owned(),
+            recovery_codes,
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "Disable 2FA", skip_all)]
+pub async fn disable_2fa(
+    State(state): State<AppState>,
+    AuthenticatedUser { email, .. }: AuthenticatedUser,
+    Json(request): Json<Disable2FARequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let password = match Password::parse(request.password) {
+        Ok(password) => password,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    if state
+        .user_store
+        .read()
+        .await
+        .verify_user(&email, &password)
+        .await
+        .is_err()
+    {
+        return Err(AuthAPIError::IncorrectCredentials);
+    }
+
+    if !two_fa_enabled(&state, &email).await? {
+        return Err(AuthAPIError::TwoFANotEnabled);
+    }
+
+    remove_second_factors(&state, &email).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TwoFASettingsResponse {
+            message: "2FA disabled".to_owned(),
+        }),
+    ))
+}
+
+// also used when an admin turns off 2FA for a user who lost their authenticator
+pub(crate) async fn remove_second_factors(
+    state: &AppState,
+    email: &Email,
+) -> Result<(), AuthAPIError> {
+    if let Err(e) = state
+        .user_store
+        .write()
+        .await
+        .update_requires_2fa(email, false)
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    // an authenticator app would otherwise keep asking for codes at login
+    match state
+        .totp_secret_store
+        .write()
+        .await
+        .remove_secret(email)
+        .await
+    {
+        Ok(_) | Err(TotpSecretStoreError::SecretNotFound) => (),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    match state
+        .recovery_code_store
+        .write()
+        .await
+        .replace_codes(email, &[])
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+async fn two_fa_enabled(state: &AppState, email: &Email) -> Result<bool, AuthAPIError> {
+    let requires_2fa = match state.user_store.read().await.get_user(email).await {
+        Ok(user) => user.requires_2fa,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    match totp_enabled(&state.totp_secret_store, email).await {
+        Ok(enabled) => Ok(requires_2fa || enabled),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e)),
+    }
+}
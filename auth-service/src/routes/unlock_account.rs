@@ -0,0 +1,41 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AccountUnlockToken, AuthAPIError, LoginLockoutStoreError},
+    store::AppState,
+};
+
+#[derive(Deserialize)]
+pub struct UnlockAccountRequest {
+    pub token: String,
+}
+
+#[derive(Serialize)]
+pub struct UnlockAccountResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Unlock account", skip_all)]
+pub async fn unlock_account(
+    State(state): State<AppState>,
+    Json(request): Json<UnlockAccountRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let token = match AccountUnlockToken::parse(request.token) {
+        Ok(token) => token,
+        Err(_) => return Err(AuthAPIError::InvalidToken),
+    };
+
+    match state.login_lockout_store.write().await.unlock(&token).await {
+        Ok(_) => (),
+        Err(LoginLockoutStoreError::UnlockTokenNotFound) => return Err(AuthAPIError::InvalidToken),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(UnlockAccountResponse {
+            message: "Account unlocked".to_owned(),
+        }),
+    ))
+}
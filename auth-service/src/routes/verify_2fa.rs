@@ -1,12 +1,25 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
 use axum_extra::extract::CookieJar;
 use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{AuthAPIError, Email, LoginAttemptId, TwoFACode},
+    domain::{
+        AuditEventKind, AuthAPIError, Email, LoginAttemptId, RecoveryCode, RecoveryCodeStoreError,
+        SessionClient, TotpSecretStoreError, TwoFACode, WebhookEventKind,
+    },
     store::AppState,
-    utils::auth::generate_auth_cookie,
+    utils::{
+        audit::{record_outcome, AuditContext},
+        auth::start_session,
+        totp::consume_code,
+        webhooks::publish_event,
+    },
 };
 
 #[derive(Deserialize)]
@@ -25,9 +38,32 @@ pub struct Verify2FAResponse {
 
 pub async fn verify_2fa(
     State(state): State<AppState>,
+    client: SessionClient,
+    audit: AuditContext,
     jar: CookieJar,
     Json(request): Json<Verify2FARequest>,
 ) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
+    let actor = request.email.clone();
+
+    let (jar, result) = check_second_factor(&state, client, jar, request).await;
+    record_outcome(
+        &state,
+        &audit,
+        AuditEventKind::TwoFAVerification,
+        Some(actor),
+        result.as_ref().err(),
+    )
+    .await;
+
+    (jar, result)
+}
+
+async fn check_second_factor(
+    state: &AppState,
+    client: SessionClient,
+    jar: CookieJar,
+    request: Verify2FARequest,
+) -> (CookieJar, Result<Response, AuthAPIError>) {
     let email = match Email::parse(Secret::new(request.email)) {
         Ok(email) => email,
         Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
@@ -38,9 +74,13 @@ pub async fn verify_2fa(
         Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
     };
 
-    let two_fa_code = match TwoFACode::parse(request.two_fa_code.clone()) {
-        Ok(code) => code,
-        Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
+    // a recovery code can be given in place of the 2FA code
+    let second_factor = match TwoFACode::parse(request.two_fa_code.clone()) {
+        Ok(code) => SecondFactor::Code(code),
+        Err(_) => match RecoveryCode::parse(Secret::new(request.two_fa_code)) {
+            Ok(code) => SecondFactor::RecoveryCode(code),
+            Err(_) => return (jar, Err(AuthAPIError::InvalidCredentials)),
+        },
     };
 
     let mut two_fa_code_store = state.two_fa_code_store.write().await;
@@ -50,9 +90,39 @@ pub async fn verify_2fa(
         Err(_) => return (jar, Err(AuthAPIError::IncorrectCredentials)),
     };
 
-    if code_tuple.0.as_ref().to_string() != login_attempt_id.as_ref().to_string()
-        || code_tuple.1.as_ref().to_string() != two_fa_code.as_ref().to_string()
-    {
+    if code_tuple.0.as_ref() != login_attempt_id.as_ref() {
+        return (jar, Err(AuthAPIError::IncorrectCredentials));
+    }
+
+    let code_is_valid = match second_factor {
+        SecondFactor::Code(two_fa_code) => {
+            match verify_two_fa_code(state, &email, &two_fa_code, &code_tuple.1).await {
+                Ok(valid) => valid,
+                Err(e) => return (jar, Err(e)),
+            }
+        }
+        SecondFactor::RecoveryCode(recovery_code) => match state
+            .recovery_code_store
+            .write()
+            .await
+            .consume_code(&email, &recovery_code)
+            .await
+        {
+            Ok(_) => true,
+            Err(RecoveryCodeStoreError::CodeNotFound) => false,
+            Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+        },
+    };
+
+    if !code_is_valid {
+        // the code is dropped after too many wrong guesses, forcing a fresh login
+        if let Err(e) = two_fa_code_store
+            .record_failed_attempt(&email, &login_attempt_id)
+            .await
+        {
+            return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
+        }
+
         return (jar, Err(AuthAPIError::IncorrectCredentials));
     }
 
@@ -60,12 +130,40 @@ pub async fn verify_2fa(
         return (jar, Err(AuthAPIError::UnexpectedError(e.into())));
     }
 
-    let auth_cookie = match generate_auth_cookie(&email) {
-        Ok(cookie) => cookie,
-        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e.into()))),
+    let (auth_cookie, refresh_cookie) = match start_session(state, &email, client).await {
+        Ok(cookies) => cookies,
+        Err(e) => return (jar, Err(AuthAPIError::UnexpectedError(e))),
     };
 
-    let updated_jar = jar.add(auth_cookie);
+    publish_event(state, WebhookEventKind::UserLoggedIn, &email).await;
+
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
 
     (updated_jar, Ok(StatusCode::OK.into_response()))
 }
+
+enum SecondFactor {
+    Code(TwoFACode),
+    RecoveryCode(RecoveryCode),
+}
+
+// checks the authenticator app when one is confirmed, otherwise the emailed code
+async fn verify_two_fa_code(
+    state: &AppState,
+    email: &Email,
+    two_fa_code: &TwoFACode,
+    emailed_code: &TwoFACode,
+) -> Result<bool, AuthAPIError> {
+    let totp_secret = match state.totp_secret_store.read().await.get_secret(email).await {
+        Ok(record) if record.confirmed => Some(record.secret),
+        Ok(_) | Err(TotpSecretStoreError::SecretNotFound) => None,
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    match totp_secret {
+        Some(secret) => consume_code(&state.totp_secret_store, email, &secret, two_fa_code)
+            .await
+            .map_err(AuthAPIError::UnexpectedError),
+        None => Ok(emailed_code.as_ref() == two_fa_code.as_ref()),
+    }
+}
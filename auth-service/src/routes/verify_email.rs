@@ -0,0 +1,131 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use color_eyre::eyre::Result;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        AccountStatus, AuthAPIError, Email, EmailVerificationToken,
+        EmailVerificationTokenStoreError, UserStoreError,
+    },
+    store::AppState,
+    utils::constants::AUTH_SERVICE_URL,
+};
+
+#[derive(Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+pub struct ResendVerificationEmailRequest {
+    pub email: Secret<String>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyEmailResponse {
+    pub message: String,
+}
+
+#[tracing::instrument(name = "Verify email", skip_all)]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(request): Json<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let token = match EmailVerificationToken::parse(request.token) {
+        Ok(token) => token,
+        Err(_) => return Err(AuthAPIError::InvalidToken),
+    };
+
+    let email = match state
+        .email_verification_token_store
+        .write()
+        .await
+        .consume_token(&token)
+        .await
+    {
+        Ok(email) => email,
+        Err(EmailVerificationTokenStoreError::TokenNotFound) => {
+            return Err(AuthAPIError::InvalidToken)
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    let mut user_store = state.user_store.write().await;
+
+    let user = match user_store.get_user(&email).await {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => return Err(AuthAPIError::InvalidToken),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    // a stale link must not re-enable an account an admin has since disabled
+    if user.status == AccountStatus::PendingVerification {
+        if let Err(e) = user_store
+            .update_account_status(&email, AccountStatus::Active)
+            .await
+        {
+            return Err(AuthAPIError::UnexpectedError(e.into()));
+        }
+    }
+
+    drop(user_store);
+
+    Ok((
+        StatusCode::OK,
+        Json(VerifyEmailResponse {
+            message: "Email verified successfully".to_owned(),
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "Resend verification email", skip_all)]
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    Json(request): Json<ResendVerificationEmailRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let email = match Email::parse(request.email) {
+        Ok(email) => email,
+        Err(_) => return Err(AuthAPIError::InvalidCredentials),
+    };
+
+    let response = Json(VerifyEmailResponse {
+        message: "If the account is awaiting verification, a new link has been sent".to_owned(),
+    });
+
+    // verified, disabled and unknown accounts get the same response
+    match state.user_store.read().await.get_user(&email).await {
+        Ok(user) if user.status == AccountStatus::PendingVerification => (),
+        Ok(_) | Err(UserStoreError::UserNotFound) => return Ok((StatusCode::OK, response)),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    if let Err(e) = send_verification_email(&state, &email).await {
+        return Err(AuthAPIError::UnexpectedError(e));
+    }
+
+    Ok((StatusCode::OK, response))
+}
+
+#[tracing::instrument(name = "Send verification email", skip_all)]
+pub(crate) async fn send_verification_email(state: &AppState, email: &Email) -> Result<()> {
+    let token = EmailVerificationToken::default();
+
+    state
+        .email_verification_token_store
+        .write()
+        .await
+        .add_token(token.clone(), email.clone())
+        .await?;
+
+    let link = format!(
+        "{}/?email_verification_token={}",
+        AUTH_SERVICE_URL.as_str(),
+        token.as_ref()
+    );
+
+    state
+        .email_client
+        .send_email(email, "Verify your email address", &link)
+        .await
+}
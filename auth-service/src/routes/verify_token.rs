@@ -1,27 +1,142 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use serde::Deserialize;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
 
-use crate::{store::AppState, utils::auth::validate_token};
+use crate::{
+    domain::{AccountStatus, ApiKey, ApiKeyStoreError, UserStoreError, API_KEY_PREFIX},
+    store::AppState,
+    utils::{
+        audit::{record_token_rejection, AuditContext},
+        auth::{validate_any_token, TokenKind},
+    },
+};
 
 #[derive(Deserialize)]
 pub struct VerifyTokenRequest {
     pub token: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    User,
+    Client,
+    #[serde(rename = "oauth")]
+    OAuth,
+    ApiKey,
+}
+
+impl From<TokenKind> for CredentialKind {
+    fn from(kind: TokenKind) -> Self {
+        match kind {
+            TokenKind::User => Self::User,
+            TokenKind::Client => Self::Client,
+            TokenKind::OAuth => Self::OAuth,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VerifyTokenResponse {
+    pub kind: CredentialKind,
+    // a user's email, or a machine client's id
+    pub sub: String,
+    // the OAuth client a user's token was issued to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+}
+
 pub async fn verify_token(
-    state: State<AppState>,
+    State(state): State<AppState>,
+    audit: AuditContext,
     Json(request): Json<VerifyTokenRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let VerifyTokenRequest { token } = request;
     if token.is_empty() {
-        return StatusCode::UNPROCESSABLE_ENTITY;
+        return StatusCode::UNPROCESSABLE_ENTITY.into_response();
+    }
+
+    if token.starts_with(API_KEY_PREFIX) {
+        return verify_api_key(&state, &audit, token).await;
+    }
+
+    match validate_any_token(
+        &state.banned_tokens_store,
+        &state.session_store,
+        &state.user_store,
+        &token,
+    )
+    .await
+    {
+        Ok(claims) => Json(VerifyTokenResponse {
+            kind: claims.kind.into(),
+            sub: claims.sub,
+            aud: claims.aud,
+            scope: claims.scope,
+            roles: claims.roles,
+            permissions: claims.permissions,
+        })
+        .into_response(),
+        Err(e) => {
+            record_token_rejection(&state, &audit, None, &e.to_string()).await;
+            StatusCode::UNAUTHORIZED.into_response()
+        }
     }
-    println!("{}", token);
+}
 
-    let banned_store = &state.banned_tokens_store;
+async fn verify_api_key(state: &AppState, audit: &AuditContext, key: String) -> Response {
+    let key = match ApiKey::parse(Secret::new(key)) {
+        Ok(key) => key,
+        Err(_) => {
+            record_token_rejection(state, audit, None, "Malformed API key").await;
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    };
 
-    match validate_token(banned_store, &token).await {
-        Ok(_) => StatusCode::OK,
-        Err(_) => StatusCode::UNAUTHORIZED,
+    let record = match state.api_key_store.read().await.get_key(&key).await {
+        Ok(record) => record,
+        Err(ApiKeyStoreError::KeyNotFound) => {
+            record_token_rejection(state, audit, None, "Unknown API key").await;
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    if record.is_expired() {
+        let actor = Some(record.email.as_ref().to_owned());
+        record_token_rejection(state, audit, actor, "Expired API key").await;
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    // a key only works while its owner could log in
+    match state.user_store.read().await.get_user(&record.email).await {
+        Ok(user) if user.status == AccountStatus::Active => (),
+        Ok(_) | Err(UserStoreError::UserNotFound) => {
+            let actor = Some(record.email.as_ref().to_owned());
+            record_token_rejection(state, audit, actor, "API key owner is not active").await;
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
     }
+
+    Json(VerifyTokenResponse {
+        kind: CredentialKind::ApiKey,
+        sub: record.email.as_ref().to_owned(),
+        aud: None,
+        scope: Some(record.scopes.join(" ")),
+        roles: Vec::new(),
+        permissions: Vec::new(),
+    })
+    .into_response()
 }
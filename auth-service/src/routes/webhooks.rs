@@ -0,0 +1,254 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        AuthAPIError, WebhookDelivery, WebhookEventKind, WebhookStoreError, WebhookSubscription,
+    },
+    store::AppState,
+    utils::{
+        constants::WEBHOOK_ALLOW_PRIVATE_HOSTS,
+        permission::{ManageWebhooks, RequirePermission},
+    },
+};
+
+const MAX_LISTED_DELIVERIES: u32 = 50;
+
+#[derive(Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookSubscription> for WebhookResponse {
+    fn from(subscription: WebhookSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            url: subscription.url,
+            events: subscription
+                .events
+                .iter()
+                .map(|kind| kind.as_ref().to_owned())
+                .collect(),
+            created_at: subscription.created_at,
+        }
+    }
+}
+
+// the only response that ever contains the signing secret
+#[derive(Serialize, Deserialize)]
+pub struct CreateWebhookResponse {
+    pub secret: String,
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WebhookDeliveryResponse {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status: String,
+    pub attempts: u32,
+    pub payload: String,
+    #[serde(rename = "nextAttemptAt")]
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "deliveredAt")]
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            kind: delivery.event.as_ref().to_owned(),
+            status: delivery.status.as_ref().to_owned(),
+            attempts: delivery.attempts,
+            payload: delivery.payload,
+            next_attempt_at: delivery.next_attempt_at,
+            last_error: delivery.last_error,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+#[tracing::instrument(name = "Create webhook", skip_all)]
+pub async fn create_webhook(
+    _: RequirePermission<ManageWebhooks>,
+    State(state): State<AppState>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let events = match request
+        .events
+        .iter()
+        .map(|kind| WebhookEventKind::parse(kind))
+        .collect()
+    {
+        Ok(events) => events,
+        Err(_) => return Err(AuthAPIError::BadRequest("Unknown event type")),
+    };
+
+    let subscription =
+        match WebhookSubscription::new(&request.url, events, *WEBHOOK_ALLOW_PRIVATE_HOSTS) {
+            Ok(subscription) => subscription,
+            Err(_) => return Err(AuthAPIError::BadRequest("Invalid webhook URL or events")),
+        };
+
+    let secret = subscription.secret.as_ref().expose_secret().clone();
+
+    if let Err(e) = state
+        .webhook_store
+        .write()
+        .await
+        .add_subscription(subscription.clone())
+        .await
+    {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateWebhookResponse {
+            secret,
+            webhook: subscription.into(),
+        }),
+    ))
+}
+
+#[tracing::instrument(name = "List webhooks", skip_all)]
+pub async fn list_webhooks(
+    _: RequirePermission<ManageWebhooks>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    match state.webhook_store.read().await.list_subscriptions().await {
+        Ok(subscriptions) => Ok((
+            StatusCode::OK,
+            Json(
+                subscriptions
+                    .into_iter()
+                    .map(WebhookResponse::from)
+                    .collect::<Vec<_>>(),
+            ),
+        )),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+#[tracing::instrument(name = "Delete webhook", skip_all)]
+pub async fn delete_webhook(
+    _: RequirePermission<ManageWebhooks>,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid webhook id")),
+    };
+
+    match state
+        .webhook_store
+        .write()
+        .await
+        .delete_subscription(&id)
+        .await
+    {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(WebhookStoreError::SubscriptionNotFound) => Err(AuthAPIError::WebhookNotFound),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+#[tracing::instrument(name = "List webhook deliveries", skip_all)]
+pub async fn list_webhook_deliveries(
+    _: RequirePermission<ManageWebhooks>,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return Err(AuthAPIError::BadRequest("Invalid webhook id")),
+    };
+
+    let webhook_store = state.webhook_store.read().await;
+
+    match webhook_store.get_subscription(&id).await {
+        Ok(_) => (),
+        Err(WebhookStoreError::SubscriptionNotFound) => return Err(AuthAPIError::WebhookNotFound),
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+
+    match webhook_store
+        .list_deliveries(&id, MAX_LISTED_DELIVERIES)
+        .await
+    {
+        Ok(deliveries) => Ok((
+            StatusCode::OK,
+            Json(
+                deliveries
+                    .into_iter()
+                    .map(WebhookDeliveryResponse::from)
+                    .collect::<Vec<_>>(),
+            ),
+        )),
+        Err(e) => Err(AuthAPIError::UnexpectedError(e.into())),
+    }
+}
+
+// queues the delivery to be sent again right away, whatever happened to it before
+#[tracing::instrument(name = "Redeliver webhook", skip_all)]
+pub async fn redeliver_webhook(
+    _: RequirePermission<ManageWebhooks>,
+    State(state): State<AppState>,
+    Path((id, delivery_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    let (id, delivery_id) = match (Uuid::parse_str(&id), Uuid::parse_str(&delivery_id)) {
+        (Ok(id), Ok(delivery_id)) => (id, delivery_id),
+        (Err(_), _) => return Err(AuthAPIError::BadRequest("Invalid webhook id")),
+        (_, Err(_)) => return Err(AuthAPIError::BadRequest("Invalid webhook delivery id")),
+    };
+
+    let mut webhook_store = state.webhook_store.write().await;
+
+    let mut delivery = match webhook_store.get_delivery(&delivery_id).await {
+        Ok(delivery) if delivery.subscription_id == id => delivery,
+        Ok(_) | Err(WebhookStoreError::DeliveryNotFound) => {
+            return Err(AuthAPIError::WebhookDeliveryNotFound)
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(e.into())),
+    };
+
+    delivery.schedule_redelivery(Utc::now());
+
+    if let Err(e) = webhook_store.update_delivery(&delivery).await {
+        return Err(AuthAPIError::UnexpectedError(e.into()));
+    }
+
+    state.webhook_wakeup.notify_one();
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(WebhookDeliveryResponse::from(delivery)),
+    ))
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    data_stores::{ApiKey, ApiKeyRecord, ApiKeyStore, ApiKeyStoreError},
+    Email,
+};
+
+// keyed by the key's hash, like the Postgres store
+#[derive(Default)]
+pub struct HashmapApiKeyStore {
+    pub keys: HashMap<String, ApiKeyRecord>,
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for HashmapApiKeyStore {
+    async fn add_key(
+        &mut self,
+        key: &ApiKey,
+        record: ApiKeyRecord,
+    ) -> Result<(), ApiKeyStoreError> {
+        self.keys.insert(key.hash(), record);
+        Ok(())
+    }
+
+    async fn get_key(&self, key: &ApiKey) -> Result<ApiKeyRecord, ApiKeyStoreError> {
+        self.keys
+            .get(&key.hash())
+            .cloned()
+            .ok_or(ApiKeyStoreError::KeyNotFound)
+    }
+
+    async fn list_keys(&self, email: &Email) -> Result<Vec<ApiKeyRecord>, ApiKeyStoreError> {
+        let mut keys: Vec<ApiKeyRecord> = self
+            .keys
+            .values()
+            .filter(|record| &record.email == email)
+            .cloned()
+            .collect();
+        keys.sort_by_key(|record| record.created_at);
+        Ok(keys)
+    }
+
+    async fn revoke_key(&mut self, email: &Email, id: Uuid) -> Result<(), ApiKeyStoreError> {
+        let before = self.keys.len();
+        self.keys
+            .retain(|_, record| !(record.id == id && &record.email == email));
+
+        if self.keys.len() == before {
+            return Err(ApiKeyStoreError::KeyNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_all_keys(&mut self, email: &Email) -> Result<(), ApiKeyStoreError> {
+        self.keys.retain(|_, record| &record.email != email);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    fn email(address: &str) -> Email {
+        Email::parse(Secret::new(address.to_owned())).unwrap()
+    }
+
+    fn record(address: &str) -> ApiKeyRecord {
+        ApiKeyRecord::new(
+            email(address),
+            "ci".to_owned(),
+            vec!["deploy".to_owned()],
+            30,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_key() {
+        let mut store = HashmapApiKeyStore::default();
+        let key = ApiKey::default();
+        let record = record("email@email.com");
+
+        store.add_key(&key, record.clone()).await.unwrap();
+
+        assert_eq!(store.get_key(&key).await, Ok(record));
+        assert_eq!(
+            store.get_key(&ApiKey::default()).await,
+            Err(ApiKeyStoreError::KeyNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_only_owner_can_revoke_key() {
+        let mut store = HashmapApiKeyStore::default();
+        let key = ApiKey::default();
+        let record = record("email@email.com");
+
+        store.add_key(&key, record.clone()).await.unwrap();
+
+        assert_eq!(
+            store.revoke_key(&email("other@email.com"), record.id).await,
+            Err(ApiKeyStoreError::KeyNotFound)
+        );
+        assert_eq!(
+            store.list_keys(&email("email@email.com")).await,
+            Ok(vec![record.clone()])
+        );
+
+        store
+            .revoke_key(&email("email@email.com"), record.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get_key(&key).await,
+            Err(ApiKeyStoreError::KeyNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_keys_keeps_other_users_keys() {
+        let mut store = HashmapApiKeyStore::default();
+        let other = record("other@email.com");
+
+        store
+            .add_key(&ApiKey::default(), record("email@email.com"))
+            .await
+            .unwrap();
+        store
+            .add_key(&ApiKey::default(), record("email@email.com"))
+            .await
+            .unwrap();
+        store
+            .add_key(&ApiKey::default(), other.clone())
+            .await
+            .unwrap();
+
+        store
+            .revoke_all_keys(&email("email@email.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(store.list_keys(&email("email@email.com")).await, Ok(vec![]));
+        assert_eq!(
+            store.list_keys(&email("other@email.com")).await,
+            Ok(vec![other])
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::cmp::Reverse;
+
+use crate::domain::{
+    data_stores::{AuditLogStore, AuditLogStoreError},
+    AuditEvent, AuditPage, AuditQuery,
+};
+
+#[derive(Default)]
+pub struct HashmapAuditLogStore {
+    pub events: Vec<AuditEvent>,
+}
+
+#[async_trait::async_trait]
+impl AuditLogStore for HashmapAuditLogStore {
+    async fn add_event(&mut self, event: AuditEvent) -> Result<(), AuditLogStoreError> {
+        self.events.push(event);
+        Ok(())
+    }
+
+    async fn list_events(&self, query: &AuditQuery) -> Result<AuditPage, AuditLogStoreError> {
+        let mut events: Vec<AuditEvent> = self
+            .events
+            .iter()
+            .filter(|event| query.matches(event))
+            .cloned()
+            .collect();
+        events.sort_by_key(|event| Reverse(event.occurred_at));
+
+        let total = events.len() as u64;
+        let events = events
+            .into_iter()
+            .skip(query.offset() as usize)
+            .take(query.per_page as usize)
+            .collect();
+
+        Ok(AuditPage { events, total })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::domain::{AuditEventKind, AuditFilter, AuditOutcome};
+
+    use super::*;
+
+    fn event(kind: AuditEventKind, minutes_ago: i64) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            occurred_at: Utc::now() - Duration::minutes(minutes_ago),
+            kind,
+            outcome: AuditOutcome::Success,
+            actor: Some("ok@email.com".to_owned()),
+            ip: None,
+            user_agent: None,
+            request_id: None,
+            reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_events_newest_first_and_paged() {
+        let mut store = HashmapAuditLogStore::default();
+        store
+            .add_event(event(AuditEventKind::Signup, 3))
+            .await
+            .unwrap();
+        store
+            .add_event(event(AuditEventKind::Logout, 1))
+            .await
+            .unwrap();
+        store
+            .add_event(event(AuditEventKind::Login, 2))
+            .await
+            .unwrap();
+
+        let query = AuditQuery::new(AuditFilter::default(), 1, 2).unwrap();
+        let page = store.list_events(&query).await.unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            page.events.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![AuditEventKind::Logout, AuditEventKind::Login]
+        );
+
+        let query = AuditQuery::new(AuditFilter::default(), 2, 2).unwrap();
+        let page = store.list_events(&query).await.unwrap();
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].kind, AuditEventKind::Signup);
+    }
+
+    #[tokio::test]
+    async fn test_list_events_filters() {
+        let mut store = HashmapAuditLogStore::default();
+        store
+            .add_event(event(AuditEventKind::Signup, 3))
+            .await
+            .unwrap();
+        store
+            .add_event(event(AuditEventKind::Login, 2))
+            .await
+            .unwrap();
+
+        let filter = AuditFilter {
+            kind: Some(AuditEventKind::Login),
+            ..AuditFilter::default()
+        };
+        let query = AuditQuery::new(filter, 1, 20).unwrap();
+        let page = store.list_events(&query).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.events[0].kind, AuditEventKind::Login);
+    }
+}
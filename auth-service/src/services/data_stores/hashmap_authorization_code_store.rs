@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use crate::domain::data_stores::{
+    AuthorizationCode, AuthorizationCodeRecord, AuthorizationCodeStore, AuthorizationCodeStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapAuthorizationCodeStore {
+    pub codes: HashMap<String, AuthorizationCodeRecord>,
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeStore for HashmapAuthorizationCodeStore {
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        record: AuthorizationCodeRecord,
+    ) -> Result<(), AuthorizationCodeStoreError> {
+        self.codes.insert(code.as_ref().to_owned(), record);
+        Ok(())
+    }
+
+    async fn consume_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationCodeRecord, AuthorizationCodeStoreError> {
+        self.codes
+            .remove(code.as_ref())
+            .ok_or(AuthorizationCodeStoreError::CodeNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CodeChallenge, Email};
+    use secrecy::Secret;
+
+    #[tokio::test]
+    async fn test_code_is_single_use() {
+        let mut store = HashmapAuthorizationCodeStore::default();
+        let code = AuthorizationCode::default();
+        let record = AuthorizationCodeRecord {
+            email: Email::parse(Secret::new("email@email.com".to_owned())).unwrap(),
+            client_id: "client".to_owned(),
+            redirect_uri: "https://app.example.com/callback".to_owned(),
+            code_challenge: CodeChallenge::parse(
+                "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM".to_owned(),
+            )
+            .unwrap(),
+            scope: Some("openid".to_owned()),
+            nonce: Some("nonce".to_owned()),
+            auth_time: 1_700_000_000,
+        };
+
+        store.add_code(code.clone(), record.clone()).await.unwrap();
+
+        assert_eq!(store.consume_code(&code).await, Ok(record));
+        assert_eq!(
+            store.consume_code(&code).await,
+            Err(AuthorizationCodeStoreError::CodeNotFound)
+        );
+    }
+}
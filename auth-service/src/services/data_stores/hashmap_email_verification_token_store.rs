@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{
+        EmailVerificationToken, EmailVerificationTokenStore, EmailVerificationTokenStoreError,
+    },
+    Email,
+};
+
+#[derive(Default)]
+pub struct HashmapEmailVerificationTokenStore {
+    pub tokens: HashMap<String, Email>,
+}
+
+#[async_trait::async_trait]
+impl EmailVerificationTokenStore for HashmapEmailVerificationTokenStore {
+    async fn add_token(
+        &mut self,
+        token: EmailVerificationToken,
+        email: Email,
+    ) -> Result<(), EmailVerificationTokenStoreError> {
+        self.tokens.insert(token.as_ref().to_owned(), email);
+        Ok(())
+    }
+
+    async fn consume_token(
+        &mut self,
+        token: &EmailVerificationToken,
+    ) -> Result<Email, EmailVerificationTokenStoreError> {
+        self.tokens
+            .remove(token.as_ref())
+            .ok_or(EmailVerificationTokenStoreError::TokenNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    #[tokio::test]
+    async fn test_consume_token() {
+        let mut store = HashmapEmailVerificationTokenStore::default();
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let token = EmailVerificationToken::default();
+
+        store
+            .add_token(token.clone(), email.clone())
+            .await
+            .expect("Failed to add token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(result.expect("Failed to consume token"), email);
+    }
+
+    #[tokio::test]
+    async fn test_token_is_single_use() {
+        let mut store = HashmapEmailVerificationTokenStore::default();
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let token = EmailVerificationToken::default();
+
+        store
+            .add_token(token.clone(), email)
+            .await
+            .expect("Failed to add token");
+        store
+            .consume_token(&token)
+            .await
+            .expect("Failed to consume token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(
+            result.unwrap_err(),
+            EmailVerificationTokenStoreError::TokenNotFound
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+
+use crate::domain::{
+    data_stores::{AccountUnlockToken, LoginLockoutStore, LoginLockoutStoreError},
+    Email,
+};
+
+// failure windows and lockout history never expire here, only locks do
+#[derive(Default)]
+pub struct HashmapLoginLockoutStore {
+    pub failures: HashMap<Email, u32>,
+    pub lockouts: HashMap<Email, u32>,
+    // unix timestamp the lock ends at
+    pub locked_until: HashMap<Email, i64>,
+    pub unlock_tokens: HashMap<String, Email>,
+}
+
+#[async_trait::async_trait]
+impl LoginLockoutStore for HashmapLoginLockoutStore {
+    async fn record_failure(
+        &mut self,
+        email: &Email,
+        _window_seconds: u64,
+    ) -> Result<u32, LoginLockoutStoreError> {
+        let failures = self.failures.entry(email.clone()).or_default();
+        *failures += 1;
+        Ok(*failures)
+    }
+
+    async fn get_lockout_count(&self, email: &Email) -> Result<u32, LoginLockoutStoreError> {
+        Ok(self.lockouts.get(email).copied().unwrap_or_default())
+    }
+
+    async fn lock(
+        &mut self,
+        email: &Email,
+        unlock_token: AccountUnlockToken,
+        lock_seconds: u64,
+    ) -> Result<(), LoginLockoutStoreError> {
+        let lock_seconds: i64 = lock_seconds.try_into().unwrap_or(i64::MAX);
+
+        self.locked_until.insert(
+            email.clone(),
+            Utc::now().timestamp().saturating_add(lock_seconds),
+        );
+        self.unlock_tokens
+            .insert(unlock_token.as_ref().to_owned(), email.clone());
+        *self.lockouts.entry(email.clone()).or_default() += 1;
+        self.failures.remove(email);
+        Ok(())
+    }
+
+    async fn get_lock_ttl(&self, email: &Email) -> Result<Option<u64>, LoginLockoutStoreError> {
+        let now = Utc::now().timestamp();
+
+        Ok(self
+            .locked_until
+            .get(email)
+            .filter(|locked_until| **locked_until > now)
+            .map(|locked_until| (locked_until - now) as u64))
+    }
+
+    async fn unlock(
+        &mut self,
+        unlock_token: &AccountUnlockToken,
+    ) -> Result<Email, LoginLockoutStoreError> {
+        let email = self
+            .unlock_tokens
+            .remove(unlock_token.as_ref())
+            .ok_or(LoginLockoutStoreError::UnlockTokenNotFound)?;
+
+        self.reset(&email).await?;
+
+        Ok(email)
+    }
+
+    async fn reset(&mut self, email: &Email) -> Result<(), LoginLockoutStoreError> {
+        self.failures.remove(email);
+        self.lockouts.remove(email);
+        self.locked_until.remove(email);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    fn email() -> Email {
+        Email::parse(Secret::new("email@email.com".to_owned())).expect("Failed to create email")
+    }
+
+    #[tokio::test]
+    async fn test_lock_and_unlock() {
+        let mut store = HashmapLoginLockoutStore::default();
+        let token = AccountUnlockToken::default();
+
+        assert_eq!(store.record_failure(&email(), 60).await, Ok(1));
+        assert_eq!(store.record_failure(&email(), 60).await, Ok(2));
+        assert_eq!(store.get_lock_ttl(&email()).await, Ok(None));
+
+        store.lock(&email(), token.clone(), 60).await.unwrap();
+
+        assert!(store.get_lock_ttl(&email()).await.unwrap().is_some());
+        assert_eq!(store.get_lockout_count(&email()).await, Ok(1));
+        // locking starts a new window
+        assert_eq!(store.record_failure(&email(), 60).await, Ok(1));
+
+        assert_eq!(store.unlock(&token).await, Ok(email()));
+        assert_eq!(store.get_lock_ttl(&email()).await, Ok(None));
+        assert_eq!(store.get_lockout_count(&email()).await, Ok(0));
+        assert_eq!(
+            store.unlock(&token).await,
+            Err(LoginLockoutStoreError::UnlockTokenNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_lock() {
+        let mut store = HashmapLoginLockoutStore::default();
+
+        store
+            .lock(&email(), AccountUnlockToken::default(), 0)
+            .await
+            .unwrap();
+
+        assert_eq!(store.get_lock_ttl(&email()).await, Ok(None));
+        assert_eq!(store.get_lockout_count(&email()).await, Ok(1));
+    }
+}
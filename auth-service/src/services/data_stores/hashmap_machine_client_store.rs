@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{MachineClientStore, MachineClientStoreError},
+    MachineClient,
+};
+
+#[derive(Default)]
+pub struct HashmapMachineClientStore {
+    pub clients: HashMap<String, MachineClient>,
+}
+
+#[async_trait::async_trait]
+impl MachineClientStore for HashmapMachineClientStore {
+    async fn add_client(&mut self, client: MachineClient) -> Result<(), MachineClientStoreError> {
+        if self.clients.contains_key(&client.client_id) {
+            return Err(MachineClientStoreError::ClientAlreadyExists);
+        }
+
+        self.clients.insert(client.client_id.clone(), client);
+        Ok(())
+    }
+
+    async fn get_client(&self, client_id: &str) -> Result<MachineClient, MachineClientStoreError> {
+        self.clients
+            .get(client_id)
+            .cloned()
+            .ok_or(MachineClientStoreError::ClientNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> MachineClient {
+        MachineClient::parse(
+            "service".to_owned(),
+            "Service".to_owned(),
+            "hash".to_owned(),
+            vec!["users:read".to_owned()],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_client() {
+        let mut store = HashmapMachineClientStore::default();
+
+        store.add_client(client()).await.unwrap();
+
+        assert_eq!(store.get_client("service").await, Ok(client()));
+        assert_eq!(
+            store.get_client("other").await,
+            Err(MachineClientStoreError::ClientNotFound)
+        );
+        assert_eq!(
+            store.add_client(client()).await,
+            Err(MachineClientStoreError::ClientAlreadyExists)
+        );
+    }
+}
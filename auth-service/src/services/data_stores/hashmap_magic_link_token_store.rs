@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{MagicLinkToken, MagicLinkTokenStore, MagicLinkTokenStoreError},
+    Email,
+};
+
+#[derive(Default)]
+pub struct HashmapMagicLinkTokenStore {
+    pub tokens: HashMap<String, Email>,
+}
+
+#[async_trait::async_trait]
+impl MagicLinkTokenStore for HashmapMagicLinkTokenStore {
+    async fn add_token(
+        &mut self,
+        token: MagicLinkToken,
+        email: Email,
+    ) -> Result<(), MagicLinkTokenStoreError> {
+        self.tokens.insert(token.as_ref().to_owned(), email);
+        Ok(())
+    }
+
+    async fn consume_token(
+        &mut self,
+        token: &MagicLinkToken,
+    ) -> Result<Email, MagicLinkTokenStoreError> {
+        self.tokens
+            .remove(token.as_ref())
+            .ok_or(MagicLinkTokenStoreError::TokenNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    #[tokio::test]
+    async fn test_consume_token() {
+        let mut store = HashmapMagicLinkTokenStore::default();
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let token = MagicLinkToken::default();
+
+        store
+            .add_token(token.clone(), email.clone())
+            .await
+            .expect("Failed to add token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(result.expect("Failed to consume token"), email);
+    }
+
+    #[tokio::test]
+    async fn test_token_is_single_use() {
+        let mut store = HashmapMagicLinkTokenStore::default();
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let token = MagicLinkToken::default();
+
+        store
+            .add_token(token.clone(), email)
+            .await
+            .expect("Failed to add token");
+        store
+            .consume_token(&token)
+            .await
+            .expect("Failed to consume token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(result.unwrap_err(), MagicLinkTokenStoreError::TokenNotFound);
+    }
+}
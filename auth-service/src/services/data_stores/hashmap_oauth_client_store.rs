@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{OAuthClientStore, OAuthClientStoreError},
+    OAuthClient,
+};
+
+#[derive(Default)]
+pub struct HashmapOAuthClientStore {
+    pub clients: HashMap<String, OAuthClient>,
+}
+
+#[async_trait::async_trait]
+impl OAuthClientStore for HashmapOAuthClientStore {
+    async fn add_client(&mut self, client: OAuthClient) -> Result<(), OAuthClientStoreError> {
+        if self.clients.contains_key(&client.client_id) {
+            return Err(OAuthClientStoreError::ClientAlreadyExists);
+        }
+
+        self.clients.insert(client.client_id.clone(), client);
+        Ok(())
+    }
+
+    async fn get_client(&self, client_id: &str) -> Result<OAuthClient, OAuthClientStoreError> {
+        self.clients
+            .get(client_id)
+            .cloned()
+            .ok_or(OAuthClientStoreError::ClientNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> OAuthClient {
+        OAuthClient::parse(
+            "client".to_owned(),
+            "Client".to_owned(),
+            vec!["https://app.example.com/callback".to_owned()],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_client() {
+        let mut store = HashmapOAuthClientStore::default();
+
+        store.add_client(client()).await.unwrap();
+
+        assert_eq!(store.get_client("client").await, Ok(client()));
+        assert_eq!(
+            store.get_client("other").await,
+            Err(OAuthClientStoreError::ClientNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_existing_client() {
+        let mut store = HashmapOAuthClientStore::default();
+
+        store.add_client(client()).await.unwrap();
+
+        assert_eq!(
+            store.add_client(client()).await,
+            Err(OAuthClientStoreError::ClientAlreadyExists)
+        );
+    }
+}
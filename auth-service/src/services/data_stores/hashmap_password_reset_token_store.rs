@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{PasswordResetToken, PasswordResetTokenStore, PasswordResetTokenStoreError},
+    Email,
+};
+
+#[derive(Default)]
+pub struct HashmapPasswordResetTokenStore {
+    pub tokens: HashMap<String, Email>,
+}
+
+#[async_trait::async_trait]
+impl PasswordResetTokenStore for HashmapPasswordResetTokenStore {
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        email: Email,
+    ) -> Result<(), PasswordResetTokenStoreError> {
+        self.tokens.insert(token.as_ref().to_owned(), email);
+        Ok(())
+    }
+
+    async fn consume_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<Email, PasswordResetTokenStoreError> {
+        self.tokens
+            .remove(token.as_ref())
+            .ok_or(PasswordResetTokenStoreError::TokenNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    #[tokio::test]
+    async fn test_consume_token() {
+        let mut store = HashmapPasswordResetTokenStore::default();
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let token = PasswordResetToken::default();
+
+        store
+            .add_token(token.clone(), email.clone())
+            .await
+            .expect("Failed to add token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(result.expect("Failed to consume token"), email);
+    }
+
+    #[tokio::test]
+    async fn test_token_is_single_use() {
+        let mut store = HashmapPasswordResetTokenStore::default();
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let token = PasswordResetToken::default();
+
+        store
+            .add_token(token.clone(), email)
+            .await
+            .expect("Failed to add token");
+        store
+            .consume_token(&token)
+            .await
+            .expect("Failed to consume token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(
+            result.unwrap_err(),
+            PasswordResetTokenStoreError::TokenNotFound
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+
+use crate::domain::data_stores::{RateLimitStore, RateLimitStoreError};
+
+#[derive(Default)]
+pub struct HashmapRateLimitStore {
+    // count and the unix timestamp the window ends at
+    pub windows: HashMap<String, (u32, i64)>,
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for HashmapRateLimitStore {
+    async fn increment(
+        &mut self,
+        key: &str,
+        window_seconds: u64,
+    ) -> Result<(u32, u64), RateLimitStoreError> {
+        let now = Utc::now().timestamp();
+        let window_seconds: i64 = window_seconds.try_into().unwrap_or(i64::MAX);
+
+        let (count, window_end) = self
+            .windows
+            .entry(key.to_owned())
+            .and_modify(|(count, window_end)| {
+                if *window_end <= now {
+                    *count = 0;
+                    *window_end = now.saturating_add(window_seconds);
+                }
+            })
+            .or_insert((0, now.saturating_add(window_seconds)));
+        *count += 1;
+
+        Ok((*count, (*window_end - now) as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_increment_counts_within_window() {
+        let mut store = HashmapRateLimitStore::default();
+
+        assert_eq!(store.increment("key", 60).await, Ok((1, 60)));
+        assert_eq!(store.increment("key", 60).await.unwrap().0, 2);
+        assert_eq!(store.increment("other", 60).await.unwrap().0, 1);
+    }
+
+    #[tokio::test]
+    async fn test_increment_resets_after_window() {
+        let mut store = HashmapRateLimitStore::default();
+
+        store.increment("key", 0).await.unwrap();
+        store.increment("key", 0).await.unwrap();
+
+        assert_eq!(store.increment("key", 60).await, Ok((1, 60)));
+    }
+}
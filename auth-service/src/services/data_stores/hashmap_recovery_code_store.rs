@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{RecoveryCode, RecoveryCodeStore, RecoveryCodeStoreError},
+    Email,
+};
+
+#[derive(Default)]
+pub struct HashmapRecoveryCodeStore {
+    pub code_hashes: HashMap<Email, Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl RecoveryCodeStore for HashmapRecoveryCodeStore {
+    async fn replace_codes(
+        &mut self,
+        email: &Email,
+        codes: &[RecoveryCode],
+    ) -> Result<(), RecoveryCodeStoreError> {
+        let code_hashes = codes
+            .iter()
+            .map(RecoveryCode::hash)
+            .collect::<Result<_, _>>()
+            .map_err(RecoveryCodeStoreError::UnexpectedError)?;
+
+        self.code_hashes.insert(email.clone(), code_hashes);
+        Ok(())
+    }
+
+    async fn consume_code(
+        &mut self,
+        email: &Email,
+        code: &RecoveryCode,
+    ) -> Result<(), RecoveryCodeStoreError> {
+        let code_hashes = self
+            .code_hashes
+            .get_mut(email)
+            .ok_or(RecoveryCodeStoreError::CodeNotFound)?;
+
+        match code_hashes
+            .iter()
+            .position(|code_hash| code.matches(code_hash))
+        {
+            Some(index) => {
+                code_hashes.remove(index);
+                Ok(())
+            }
+            None => Err(RecoveryCodeStoreError::CodeNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::{ExposeSecret, Secret};
+    use std::slice;
+
+    fn email() -> Email {
+        Email::parse(Secret::new("email@email.com".to_owned())).expect("Failed to create email")
+    }
+
+    #[tokio::test]
+    async fn test_consume_code_is_single_use() {
+        let mut store = HashmapRecoveryCodeStore::default();
+        let code = RecoveryCode::default();
+
+        store
+            .replace_codes(&email(), slice::from_ref(&code))
+            .await
+            .expect("Failed to store codes");
+
+        assert_eq!(store.consume_code(&email(), &code).await, Ok(()));
+        assert_eq!(
+            store.consume_code(&email(), &code).await,
+            Err(RecoveryCodeStoreError::CodeNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_codes_invalidates_old_codes() {
+        let mut store = HashmapRecoveryCodeStore::default();
+        let old_code = RecoveryCode::default();
+        let new_code = RecoveryCode::default();
+
+        store
+            .replace_codes(&email(), slice::from_ref(&old_code))
+            .await
+            .unwrap();
+        store
+            .replace_codes(&email(), slice::from_ref(&new_code))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.consume_code(&email(), &old_code).await,
+            Err(RecoveryCodeStoreError::CodeNotFound)
+        );
+        assert_eq!(store.consume_code(&email(), &new_code).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_codes_are_stored_hashed() {
+        let mut store = HashmapRecoveryCodeStore::default();
+        let code = RecoveryCode::default();
+
+        store
+            .replace_codes(&email(), slice::from_ref(&code))
+            .await
+            .unwrap();
+
+        let stored = store.code_hashes.get(&email()).unwrap();
+        assert!(!stored.contains(code.as_ref().expose_secret()));
+    }
+
+    #[tokio::test]
+    async fn test_code_hashes_are_salted() {
+        let mut store = HashmapRecoveryCodeStore::default();
+        let code = RecoveryCode::default();
+
+        store
+            .replace_codes(&email(), &[code.clone(), code.clone()])
+            .await
+            .unwrap();
+
+        let stored = store.code_hashes.get(&email()).unwrap();
+        assert_ne!(stored[0], stored[1]);
+        assert!(stored.iter().all(|code_hash| code.matches(code_hash)));
+    }
+}
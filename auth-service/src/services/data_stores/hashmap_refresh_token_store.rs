@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::{
+    data_stores::{
+        RefreshToken, RefreshTokenFamilyId, RefreshTokenRecord, RefreshTokenStore,
+        RefreshTokenStoreError,
+    },
+    Email,
+};
+
+#[derive(Default)]
+pub struct HashmapRefreshTokenStore {
+    pub tokens: HashMap<String, RefreshTokenRecord>,
+    pub used_tokens: HashSet<String>,
+    pub revoked_families: HashSet<String>,
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for HashmapRefreshTokenStore {
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        record: RefreshTokenRecord,
+    ) -> Result<(), RefreshTokenStoreError> {
+        self.tokens.insert(token.as_ref().to_owned(), record);
+        Ok(())
+    }
+
+    async fn get_token(
+        &self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenRecord, RefreshTokenStoreError> {
+        match self.tokens.get(token.as_ref()) {
+            Some(record) => Ok(record.clone()),
+            None => Err(RefreshTokenStoreError::TokenNotFound),
+        }
+    }
+
+    async fn consume_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenRecord, RefreshTokenStoreError> {
+        let record = self.get_token(token).await?;
+
+        if self.revoked_families.contains(record.family_id.as_ref()) {
+            return Err(RefreshTokenStoreError::TokenRevoked);
+        }
+
+        if !self.used_tokens.insert(token.as_ref().to_owned()) {
+            return Err(RefreshTokenStoreError::TokenReused(record.family_id));
+        }
+
+        Ok(record)
+    }
+
+    async fn revoke_family(
+        &mut self,
+        family_id: &RefreshTokenFamilyId,
+    ) -> Result<(), RefreshTokenStoreError> {
+        self.revoked_families.insert(family_id.as_ref().to_owned());
+        Ok(())
+    }
+
+    async fn revoke_all_tokens(&mut self, email: &Email) -> Result<(), RefreshTokenStoreError> {
+        let families = self
+            .tokens
+            .values()
+            .filter(|record| &record.email == email)
+            .map(|record| record.family_id.as_ref().to_owned());
+        self.revoked_families.extend(families);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    fn record() -> RefreshTokenRecord {
+        RefreshTokenRecord {
+            email: Email::parse(Secret::new("email@email.com".to_owned()))
+                .expect("Failed to create email"),
+            family_id: RefreshTokenFamilyId::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consume_token() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        let record = record();
+
+        store
+            .add_token(token.clone(), record.clone())
+            .await
+            .expect("Failed to add token");
+
+        let consumed = store
+            .consume_token(&token)
+            .await
+            .expect("Failed to consume token");
+        assert_eq!(consumed, record);
+    }
+
+    #[tokio::test]
+    async fn test_consume_token_twice_is_reuse() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+
+        store
+            .add_token(token.clone(), record())
+            .await
+            .expect("Failed to add token");
+        store
+            .consume_token(&token)
+            .await
+            .expect("Failed to consume token");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(
+            result.unwrap_err(),
+            RefreshTokenStoreError::TokenReused(RefreshTokenFamilyId::default())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoked_family_rejects_unused_token() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        let record = record();
+
+        store
+            .add_token(token.clone(), record.clone())
+            .await
+            .expect("Failed to add token");
+        store
+            .revoke_family(&record.family_id)
+            .await
+            .expect("Failed to revoke family");
+
+        let result = store.consume_token(&token).await;
+        assert_eq!(result.unwrap_err(), RefreshTokenStoreError::TokenRevoked);
+    }
+
+    #[tokio::test]
+    async fn test_token_not_found() {
+        let mut store = HashmapRefreshTokenStore::default();
+
+        let result = store.consume_token(&RefreshToken::default()).await;
+        assert_eq!(result.unwrap_err(), RefreshTokenStoreError::TokenNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_tokens_only_affects_user() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let token = RefreshToken::default();
+        let other_token = RefreshToken::default();
+        let record = record();
+        let other_record = RefreshTokenRecord {
+            email: Email::parse(Secret::new("other@email.com".to_owned())).unwrap(),
+            family_id: RefreshTokenFamilyId::default(),
+        };
+
+        store
+            .add_token(token.clone(), record.clone())
+            .await
+            .unwrap();
+        store
+            .add_token(other_token.clone(), other_record)
+            .await
+            .unwrap();
+
+        store.revoke_all_tokens(&record.email).await.unwrap();
+
+        assert_eq!(
+            store.consume_token(&token).await.unwrap_err(),
+            RefreshTokenStoreError::TokenRevoked
+        );
+        assert!(store.consume_token(&other_token).await.is_ok());
+    }
+}
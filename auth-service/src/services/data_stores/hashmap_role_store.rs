@@ -0,0 +1,135 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::{
+    data_stores::{RoleStore, RoleStoreError},
+    Email, Role, UserAccess,
+};
+
+#[derive(Default)]
+pub struct HashmapRoleStore {
+    pub roles: HashMap<String, Role>,
+    pub user_roles: HashMap<Email, HashSet<String>>,
+}
+
+#[async_trait::async_trait]
+impl RoleStore for HashmapRoleStore {
+    async fn list_roles(&self) -> Result<Vec<Role>, RoleStoreError> {
+        let mut roles: Vec<Role> = self.roles.values().cloned().collect();
+        roles.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(roles)
+    }
+
+    async fn get_user_access(&self, email: &Email) -> Result<UserAccess, RoleStoreError> {
+        let roles: Vec<String> = self
+            .user_roles
+            .get(email)
+            .map(|roles| roles.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let permissions = roles
+            .iter()
+            .filter_map(|role| self.roles.get(role))
+            .flat_map(|role| role.permissions.iter().cloned())
+            .collect();
+
+        Ok(UserAccess::new(roles, permissions))
+    }
+
+    async fn assign_role(&mut self, email: &Email, role: &str) -> Result<(), RoleStoreError> {
+        if !self.roles.contains_key(role) {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+
+        self.user_roles
+            .entry(email.clone())
+            .or_default()
+            .insert(role.to_owned());
+        Ok(())
+    }
+
+    async fn unassign_role(&mut self, email: &Email, role: &str) -> Result<bool, RoleStoreError> {
+        if !self.roles.contains_key(role) {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+
+        Ok(self
+            .user_roles
+            .get_mut(email)
+            .is_some_and(|roles| roles.remove(role)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    fn store() -> HashmapRoleStore {
+        let mut store = HashmapRoleStore::default();
+        for (name, permissions) in [
+            ("admin", vec!["roles:assign", "users:read"]),
+            ("support", vec!["users:read"]),
+        ] {
+            store.roles.insert(
+                name.to_owned(),
+                Role {
+                    name: name.to_owned(),
+                    description: String::new(),
+                    permissions: permissions.into_iter().map(str::to_owned).collect(),
+                },
+            );
+        }
+        store
+    }
+
+    fn email() -> Email {
+        Email::parse(Secret::new("email@email.com".to_owned())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_user_access_combines_roles() {
+        let mut store = store();
+
+        store.assign_role(&email(), "admin").await.unwrap();
+        store.assign_role(&email(), "support").await.unwrap();
+
+        assert_eq!(
+            store.get_user_access(&email()).await,
+            Ok(UserAccess::new(
+                vec!["admin".to_owned(), "support".to_owned()],
+                vec!["roles:assign".to_owned(), "users:read".to_owned()],
+            ))
+        );
+
+        assert!(store.unassign_role(&email(), "admin").await.unwrap());
+
+        assert_eq!(
+            store.get_user_access(&email()).await.unwrap().permissions,
+            vec!["users:read"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assign_unknown_role() {
+        let mut store = store();
+
+        assert_eq!(
+            store.assign_role(&email(), "owner").await,
+            Err(RoleStoreError::RoleNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unassign_role() {
+        let mut store = store();
+
+        store.assign_role(&email(), "support").await.unwrap();
+
+        assert_eq!(store.unassign_role(&email(), "support").await, Ok(true));
+        assert_eq!(store.unassign_role(&email(), "support").await, Ok(false));
+        assert_eq!(
+            store.unassign_role(&email(), "owner").await,
+            Err(RoleStoreError::RoleNotFound)
+        );
+    }
+}
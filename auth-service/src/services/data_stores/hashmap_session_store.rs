@@ -0,0 +1,144 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use chrono::Utc;
+
+use crate::domain::{
+    data_stores::{SessionStore, SessionStoreError},
+    Email, Session, SessionId,
+};
+
+#[derive(Default)]
+pub struct HashmapSessionStore {
+    pub sessions: HashMap<String, Session>,
+}
+
+#[async_trait::async_trait]
+impl SessionStore for HashmapSessionStore {
+    async fn add_session(&mut self, session: Session) -> Result<(), SessionStoreError> {
+        self.sessions
+            .insert(session.id.as_ref().to_owned(), session);
+        Ok(())
+    }
+
+    async fn get_session(&self, id: &SessionId) -> Result<Session, SessionStoreError> {
+        match self.sessions.get(id.as_ref()) {
+            Some(session) => Ok(session.clone()),
+            None => Err(SessionStoreError::SessionNotFound),
+        }
+    }
+
+    async fn touch_session(&mut self, id: &SessionId) -> Result<(), SessionStoreError> {
+        match self.sessions.get_mut(id.as_ref()) {
+            Some(session) => {
+                session.last_used_at = Utc::now();
+                Ok(())
+            }
+            None => Err(SessionStoreError::SessionNotFound),
+        }
+    }
+
+    async fn list_sessions(&self, email: &Email) -> Result<Vec<Session>, SessionStoreError> {
+        let mut sessions: Vec<Session> = self
+            .sessions
+            .values()
+            .filter(|session| &session.email == email)
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.created_at));
+        Ok(sessions)
+    }
+
+    async fn revoke_session(
+        &mut self,
+        email: &Email,
+        id: &SessionId,
+    ) -> Result<(), SessionStoreError> {
+        match self.sessions.get(id.as_ref()) {
+            Some(session) if &session.email == email => {
+                self.sessions.remove(id.as_ref());
+                Ok(())
+            }
+            _ => Err(SessionStoreError::SessionNotFound),
+        }
+    }
+
+    async fn revoke_all_sessions(&mut self, email: &Email) -> Result<(), SessionStoreError> {
+        self.sessions.retain(|_, session| &session.email != email);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use crate::domain::SessionClient;
+
+    use super::*;
+
+    fn email(email: &str) -> Email {
+        Email::parse(Secret::new(email.to_owned())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_only_for_owner() {
+        let mut store = HashmapSessionStore::default();
+        let session = Session::new(email("owner@email.com"), SessionClient::default());
+        let id = session.id.clone();
+        store.add_session(session).await.unwrap();
+
+        assert_eq!(
+            store.revoke_session(&email("other@email.com"), &id).await,
+            Err(SessionStoreError::SessionNotFound)
+        );
+        assert!(store.get_session(&id).await.is_ok());
+
+        store
+            .revoke_session(&email("owner@email.com"), &id)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get_session(&id).await,
+            Err(SessionStoreError::SessionNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_sessions() {
+        let mut store = HashmapSessionStore::default();
+        for owner in ["owner@email.com", "owner@email.com", "other@email.com"] {
+            store
+                .add_session(Session::new(email(owner), SessionClient::default()))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            store
+                .list_sessions(&email("owner@email.com"))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        store
+            .revoke_all_sessions(&email("owner@email.com"))
+            .await
+            .unwrap();
+
+        assert!(store
+            .list_sessions(&email("owner@email.com"))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store
+                .list_sessions(&email("other@email.com"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
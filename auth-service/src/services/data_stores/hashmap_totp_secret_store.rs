@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    data_stores::{TotpRecord, TotpSecret, TotpSecretStore, TotpSecretStoreError},
+    Email,
+};
+
+#[derive(Default)]
+pub struct HashmapTotpSecretStore {
+    pub secrets: HashMap<Email, (TotpRecord, Option<u64>)>,
+}
+
+#[async_trait::async_trait]
+impl TotpSecretStore for HashmapTotpSecretStore {
+    async fn set_pending_secret(
+        &mut self,
+        email: Email,
+        secret: TotpSecret,
+    ) -> Result<(), TotpSecretStoreError> {
+        let record = TotpRecord {
+            secret,
+            confirmed: false,
+        };
+        self.secrets.insert(email, (record, None));
+        Ok(())
+    }
+
+    async fn get_secret(&self, email: &Email) -> Result<TotpRecord, TotpSecretStoreError> {
+        match self.secrets.get(email) {
+            Some((record, _)) => Ok(record.clone()),
+            None => Err(TotpSecretStoreError::SecretNotFound),
+        }
+    }
+
+    async fn confirm_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        match self.secrets.get_mut(email) {
+            Some((record, _)) => {
+                record.confirmed = true;
+                Ok(())
+            }
+            None => Err(TotpSecretStoreError::SecretNotFound),
+        }
+    }
+
+    async fn record_used_step(
+        &mut self,
+        email: &Email,
+        step: u64,
+    ) -> Result<(), TotpSecretStoreError> {
+        match self.secrets.get_mut(email) {
+            Some((_, Some(last_used_step))) if *last_used_step >= step => {
+                Err(TotpSecretStoreError::StepAlreadyUsed)
+            }
+            Some((_, last_used_step)) => {
+                *last_used_step = Some(step);
+                Ok(())
+            }
+            None => Err(TotpSecretStoreError::SecretNotFound),
+        }
+    }
+
+    async fn remove_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        match self.secrets.remove(email) {
+            Some(_) => Ok(()),
+            None => Err(TotpSecretStoreError::SecretNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::Secret;
+
+    fn email() -> Email {
+        Email::parse(Secret::new("email@email.com".to_owned())).expect("Failed to create email")
+    }
+
+    #[tokio::test]
+    async fn test_confirm_secret() {
+        let mut store = HashmapTotpSecretStore::default();
+        let secret = TotpSecret::default();
+
+        store
+            .set_pending_secret(email(), secret.clone())
+            .await
+            .expect("Failed to set secret");
+        assert!(!store.get_secret(&email()).await.unwrap().confirmed);
+
+        store
+            .confirm_secret(&email())
+            .await
+            .expect("Failed to confirm secret");
+
+        let record = store.get_secret(&email()).await.unwrap();
+        assert!(record.confirmed);
+        assert_eq!(record.secret, secret);
+    }
+
+    #[tokio::test]
+    async fn test_record_used_step_rejects_replay() {
+        let mut store = HashmapTotpSecretStore::default();
+
+        store
+            .set_pending_secret(email(), TotpSecret::default())
+            .await
+            .unwrap();
+
+        assert_eq!(store.record_used_step(&email(), 10).await, Ok(()));
+        assert_eq!(
+            store.record_used_step(&email(), 10).await,
+            Err(TotpSecretStoreError::StepAlreadyUsed)
+        );
+        assert_eq!(
+            store.record_used_step(&email(), 9).await,
+            Err(TotpSecretStoreError::StepAlreadyUsed)
+        );
+        assert_eq!(store.record_used_step(&email(), 11).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_remove_secret() {
+        let mut store = HashmapTotpSecretStore::default();
+
+        store
+            .set_pending_secret(email(), TotpSecret::default())
+            .await
+            .unwrap();
+
+        assert_eq!(store.remove_secret(&email()).await, Ok(()));
+        assert_eq!(
+            store.get_secret(&email()).await,
+            Err(TotpSecretStoreError::SecretNotFound)
+        );
+        assert_eq!(
+            store.remove_secret(&email()).await,
+            Err(TotpSecretStoreError::SecretNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_secret_not_found() {
+        let store = HashmapTotpSecretStore::default();
+
+        assert_eq!(
+            store.get_secret(&email()).await,
+            Err(TotpSecretStoreError::SecretNotFound)
+        );
+    }
+}
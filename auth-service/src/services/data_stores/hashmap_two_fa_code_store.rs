@@ -1,13 +1,22 @@
 use std::collections::HashMap;
 
-use crate::domain::{
-    data_stores::{LoginAttemptId, TwoFACode, TwoFACodeStore, TwoFACodeStoreError},
-    email::Email,
+use chrono::Utc;
+
+use crate::{
+    domain::{
+        data_stores::{LoginAttemptId, TwoFACode, TwoFACodeStore, TwoFACodeStoreError},
+        email::Email,
+    },
+    utils::constants::MAX_TWO_FA_ATTEMPTS,
 };
 
 #[derive(Default)]
 pub struct HashmapTwoFACodeStore {
     pub codes: HashMap<Email, (LoginAttemptId, TwoFACode)>,
+    // keyed by login attempt id
+    pub failed_attempts: HashMap<String, u32>,
+    // resend count and the unix time its cooldown ends, keyed by login attempt id
+    pub resends: HashMap<String, (u32, i64)>,
 }
 
 #[async_trait::async_trait]
@@ -22,7 +31,7 @@ impl TwoFACodeStore for HashmapTwoFACodeStore {
         Ok(())
     }
     async fn remove_code(&mut self, email: &Email) -> Result<(), TwoFACodeStoreError> {
-        match self.codes.remove(&email) {
+        match self.codes.remove(email) {
             Some(_) => Ok(()),
             None => Err(TwoFACodeStoreError::LoginAttemptIdNotFound),
         }
@@ -36,6 +45,51 @@ impl TwoFACodeStore for HashmapTwoFACodeStore {
             None => Err(TwoFACodeStoreError::LoginAttemptIdNotFound),
         }
     }
+    async fn record_failed_attempt(
+        &mut self,
+        email: &Email,
+        login_attempt_id: &LoginAttemptId,
+    ) -> Result<u32, TwoFACodeStoreError> {
+        let failed_attempts = self
+            .failed_attempts
+            .entry(login_attempt_id.as_ref().to_owned())
+            .or_default();
+        *failed_attempts += 1;
+        let failed_attempts = *failed_attempts;
+
+        if failed_attempts >= MAX_TWO_FA_ATTEMPTS
+            && self
+                .codes
+                .get(email)
+                .is_some_and(|(current_id, _)| current_id == login_attempt_id)
+        {
+            self.codes.remove(email);
+        }
+
+        Ok(failed_attempts)
+    }
+    async fn record_resend(
+        &mut self,
+        login_attempt_id: &LoginAttemptId,
+        cooldown_seconds: u64,
+    ) -> Result<u32, TwoFACodeStoreError> {
+        let now = Utc::now().timestamp();
+        let (resends, cooldown_ends) = self
+            .resends
+            .entry(login_attempt_id.as_ref().to_owned())
+            .or_default();
+
+        if *cooldown_ends > now {
+            return Err(TwoFACodeStoreError::ResendCooldown(
+                (*cooldown_ends - now) as u64,
+            ));
+        }
+
+        *resends += 1;
+        *cooldown_ends = now + cooldown_seconds as i64;
+
+        Ok(*resends)
+    }
 }
 
 #[cfg(test)]
@@ -49,9 +103,7 @@ mod tests {
     async fn test_add_method() {
         let email = Email::parse(Secret::new("email@email.com".to_owned()))
             .expect("Failed to create email");
-        let mut store = HashmapTwoFACodeStore {
-            codes: HashMap::new(),
-        };
+        let mut store = HashmapTwoFACodeStore::default();
         let login_attempt_id =
             LoginAttemptId::parse(Uuid::new_v4().to_string()).expect("Failed to parse uuid");
         let two_fa_code = TwoFACode::parse("123456".to_owned()).expect("Failed to parse code");
@@ -60,16 +112,14 @@ mod tests {
             .add_code(email.clone(), login_attempt_id.clone(), two_fa_code.clone())
             .await;
         assert!(inserted.is_ok());
-        assert_eq!(store.codes.is_empty(), false);
+        assert!(!store.codes.is_empty());
     }
 
     #[tokio::test]
     async fn test_get_code() {
         let email = Email::parse(Secret::new("email@email.com".to_owned()))
             .expect("Failed to create email");
-        let mut store = HashmapTwoFACodeStore {
-            codes: HashMap::new(),
-        };
+        let mut store = HashmapTwoFACodeStore::default();
         let login_attempt_id =
             LoginAttemptId::parse(Uuid::new_v4().to_string()).expect("Failed to parse uuid");
         let two_fa_code = TwoFACode::parse("123456".to_owned()).expect("Failed to parse code");
@@ -78,7 +128,7 @@ mod tests {
             .add_code(email.clone(), login_attempt_id.clone(), two_fa_code.clone())
             .await;
         assert!(inserted.is_ok());
-        assert_eq!(store.codes.is_empty(), false);
+        assert!(!store.codes.is_empty());
 
         let code = store.get_code(&email).await.expect("Failed to get code");
         let (id, code) = code;
@@ -93,9 +143,7 @@ mod tests {
     async fn test_remove_method() {
         let email = Email::parse(Secret::new("email@email.com".to_owned()))
             .expect("Failed to create email");
-        let mut store = HashmapTwoFACodeStore {
-            codes: HashMap::new(),
-        };
+        let mut store = HashmapTwoFACodeStore::default();
         let login_attempt_id = LoginAttemptId::default();
         let two_fa_code = TwoFACode::default();
 
@@ -103,21 +151,105 @@ mod tests {
             .add_code(email.clone(), login_attempt_id.clone(), two_fa_code.clone())
             .await;
         assert!(inserted.is_ok());
-        assert_eq!(store.codes.is_empty(), false);
+        assert!(!store.codes.is_empty());
 
         let code = store.remove_code(&email).await;
         assert!(code.is_ok());
         assert!(store.codes.is_empty())
     }
 
+    #[tokio::test]
+    async fn test_code_removed_after_max_failed_attempts() {
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let mut store = HashmapTwoFACodeStore::default();
+        let login_attempt_id = LoginAttemptId::default();
+
+        store
+            .add_code(
+                email.clone(),
+                login_attempt_id.clone(),
+                TwoFACode::default(),
+            )
+            .await
+            .expect("Failed to add code");
+
+        for attempt in 1..MAX_TWO_FA_ATTEMPTS {
+            assert_eq!(
+                store.record_failed_attempt(&email, &login_attempt_id).await,
+                Ok(attempt)
+            );
+            assert!(store.get_code(&email).await.is_ok());
+        }
+
+        assert_eq!(
+            store.record_failed_attempt(&email, &login_attempt_id).await,
+            Ok(MAX_TWO_FA_ATTEMPTS)
+        );
+        assert_eq!(
+            store.get_code(&email).await,
+            Err(TwoFACodeStoreError::LoginAttemptIdNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempts_leave_newer_login_attempt() {
+        let email = Email::parse(Secret::new("email@email.com".to_owned()))
+            .expect("Failed to create email");
+        let mut store = HashmapTwoFACodeStore::default();
+        let old_attempt_id = LoginAttemptId::default();
+        let new_attempt_id = LoginAttemptId::default();
+
+        store
+            .add_code(email.clone(), new_attempt_id.clone(), TwoFACode::default())
+            .await
+            .expect("Failed to add code");
+
+        for _ in 0..MAX_TWO_FA_ATTEMPTS {
+            store
+                .record_failed_attempt(&email, &old_attempt_id)
+                .await
+                .unwrap();
+        }
+
+        let (id, _) = store.get_code(&email).await.expect("Code was removed");
+        assert_eq!(id, new_attempt_id);
+    }
+
+    #[tokio::test]
+    async fn test_resend_cooldown() {
+        let mut store = HashmapTwoFACodeStore::default();
+        let login_attempt_id = LoginAttemptId::default();
+
+        assert_eq!(store.record_resend(&login_attempt_id, 30).await, Ok(1));
+        assert_eq!(
+            store.record_resend(&login_attempt_id, 30).await,
+            Err(TwoFACodeStoreError::ResendCooldown(30))
+        );
+
+        // other login attempts have their own cooldown
+        assert_eq!(
+            store.record_resend(&LoginAttemptId::default(), 30).await,
+            Ok(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resends_are_counted() {
+        let mut store = HashmapTwoFACodeStore::default();
+        let login_attempt_id = LoginAttemptId::default();
+
+        for resend in 1..=3 {
+            assert_eq!(store.record_resend(&login_attempt_id, 0).await, Ok(resend));
+        }
+    }
+
     #[tokio::test]
     async fn test_code_not_found() {
         let email = Email::parse(Secret::new("email@email.com".to_owned()))
             .expect("Failed to create email");
 
-        let mut store = HashmapTwoFACodeStore {
-            codes: HashMap::new(),
-        };
+        let mut store = HashmapTwoFACodeStore::default();
 
         let result = store.remove_code(&email).await;
 
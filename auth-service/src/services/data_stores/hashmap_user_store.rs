@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::domain::{Email, Password, User, UserStore, UserStoreError};
+use crate::domain::{
+    AccountStatus, Email, Password, User, UserPage, UserQuery, UserStore, UserStoreError,
+};
 
 #[derive(Default)]
 pub struct HashmapUserStore {
@@ -10,7 +12,7 @@ pub struct HashmapUserStore {
 #[async_trait::async_trait]
 impl UserStore for HashmapUserStore {
     async fn add_user(&mut self, user: User) -> Result<(), UserStoreError> {
-        if let Some(_) = self.users.get(&user.email) {
+        if self.users.contains_key(&user.email) {
             Err(UserStoreError::UserAlreadyExists)
         } else {
             self.users.insert(user.email.clone(), user);
@@ -38,6 +40,89 @@ impl UserStore for HashmapUserStore {
             None => Err(UserStoreError::UserNotFound),
         }
     }
+
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError> {
+        match self.users.get_mut(email) {
+            Some(user) => {
+                user.password = password;
+                Ok(())
+            }
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn update_account_status(
+        &mut self,
+        email: &Email,
+        status: AccountStatus,
+    ) -> Result<(), UserStoreError> {
+        match self.users.get_mut(email) {
+            Some(user) => {
+                user.status = status;
+                Ok(())
+            }
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn update_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        match self.users.get_mut(email) {
+            Some(user) => {
+                user.requires_2fa = requires_2fa;
+                Ok(())
+            }
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn bump_token_version(&mut self, email: &Email) -> Result<u32, UserStoreError> {
+        match self.users.get_mut(email) {
+            Some(user) => {
+                user.token_version += 1;
+                Ok(user.token_version)
+            }
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn delete_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        match self.users.remove(email) {
+            Some(_) => Ok(()),
+            None => Err(UserStoreError::UserNotFound),
+        }
+    }
+
+    async fn list_users(&self, query: &UserQuery) -> Result<UserPage, UserStoreError> {
+        let search = query.email.as_deref().map(str::to_lowercase);
+
+        let mut users: Vec<User> = self
+            .users
+            .values()
+            .filter(|user| match &search {
+                Some(search) => user.email.as_ref().to_lowercase().contains(search),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        users.sort_by(|a, b| a.email.as_ref().cmp(b.email.as_ref()));
+
+        let total = users.len() as u64;
+        let users = users
+            .into_iter()
+            .skip(query.offset() as usize)
+            .take(query.per_page as usize)
+            .collect();
+
+        Ok(UserPage { users, total })
+    }
 }
 
 #[cfg(test)]
@@ -58,6 +143,8 @@ mod tests {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
 
         let result = store.add_user(user).await;
@@ -74,6 +161,8 @@ mod tests {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
         let inserted_user_result = store.add_user(user);
 
@@ -84,6 +173,8 @@ mod tests {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
 
         let found_user = store.get_user(&user.email);
@@ -92,6 +183,8 @@ mod tests {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
 
         assert_eq!(found_user.await.unwrap().email, user.email)
@@ -107,14 +200,18 @@ mod tests {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
 
-        let _inserted_user_result = store.add_user(user).await.unwrap();
+        store.add_user(user).await.unwrap();
 
         let user = User {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
 
         let found_user = store.get_user(&user.email);
@@ -122,6 +219,8 @@ mod tests {
             email: Email::parse(Secret::new("ok@email.com".to_owned())).unwrap(),
             password: Password::parse(Secret::new("longenough".to_owned())).unwrap(),
             requires_2fa: false,
+            status: AccountStatus::Active,
+            token_version: 0,
         };
         let found_user = found_user.await.unwrap();
 
@@ -132,4 +231,166 @@ mod tests {
 
         assert_eq!(results.await.unwrap(), ());
     }
+
+    #[tokio::test]
+    async fn update_password() {
+        let mut store = HashmapUserStore::default();
+        let email = Email::parse(Secret::new("ok@email.com".to_owned())).unwrap();
+        let old_password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+        let new_password = Password::parse(Secret::new("evenlongerpassword".to_owned())).unwrap();
+
+        store
+            .add_user(User::new(email.clone(), old_password.clone(), false))
+            .await
+            .unwrap();
+
+        store
+            .update_password(&email, new_password.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.verify_user(&email, &old_password).await,
+            Err(UserStoreError::InvalidCredentials)
+        );
+        assert_eq!(store.verify_user(&email, &new_password).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn update_password_user_not_found() {
+        let mut store = HashmapUserStore::default();
+        let email = Email::parse(Secret::new("ok@email.com".to_owned())).unwrap();
+        let password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+
+        assert_eq!(
+            store.update_password(&email, password).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn update_account_status() {
+        let mut store = HashmapUserStore::default();
+        let email = Email::parse(Secret::new("ok@email.com".to_owned())).unwrap();
+        let password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+
+        store
+            .add_user(User::new(email.clone(), password, false))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_user(&email).await.unwrap().status,
+            AccountStatus::PendingVerification
+        );
+
+        store
+            .update_account_status(&email, AccountStatus::Active)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_user(&email).await.unwrap().status,
+            AccountStatus::Active
+        );
+    }
+
+    #[tokio::test]
+    async fn update_requires_2fa() {
+        let mut store = HashmapUserStore::default();
+        let email = Email::parse(Secret::new("ok@email.com".to_owned())).unwrap();
+        let password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+
+        store
+            .add_user(User::new(email.clone(), password, false))
+            .await
+            .unwrap();
+
+        store.update_requires_2fa(&email, true).await.unwrap();
+        assert!(store.get_user(&email).await.unwrap().requires_2fa);
+
+        store.update_requires_2fa(&email, false).await.unwrap();
+        assert!(!store.get_user(&email).await.unwrap().requires_2fa);
+
+        let unknown = Email::parse(Secret::new("unknown@email.com".to_owned())).unwrap();
+        assert_eq!(
+            store.update_requires_2fa(&unknown, true).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn bump_token_version() {
+        let mut store = HashmapUserStore::default();
+        let email = Email::parse(Secret::new("ok@email.com".to_owned())).unwrap();
+        let password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+
+        store
+            .add_user(User::new(email.clone(), password, false))
+            .await
+            .unwrap();
+        assert_eq!(store.get_user(&email).await.unwrap().token_version, 0);
+
+        assert_eq!(store.bump_token_version(&email).await, Ok(1));
+        assert_eq!(store.bump_token_version(&email).await, Ok(2));
+        assert_eq!(store.get_user(&email).await.unwrap().token_version, 2);
+
+        let unknown = Email::parse(Secret::new("unknown@email.com".to_owned())).unwrap();
+        assert_eq!(
+            store.bump_token_version(&unknown).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_user() {
+        let mut store = HashmapUserStore::default();
+        let email = Email::parse(Secret::new("ok@email.com".to_owned())).unwrap();
+        let password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+
+        store
+            .add_user(User::new(email.clone(), password, false))
+            .await
+            .unwrap();
+
+        assert_eq!(store.delete_user(&email).await, Ok(()));
+        assert_eq!(
+            store.get_user(&email).await.err(),
+            Some(UserStoreError::UserNotFound)
+        );
+        assert_eq!(
+            store.delete_user(&email).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn list_users() {
+        let mut store = HashmapUserStore::default();
+        let password = Password::parse(Secret::new("longenough".to_owned())).unwrap();
+
+        for email in ["carol@email.com", "alice@email.com", "bob@other.com"] {
+            let email = Email::parse(Secret::new(email.to_owned())).unwrap();
+            store
+                .add_user(User::new(email, password.clone(), false))
+                .await
+                .unwrap();
+        }
+
+        let page = store
+            .list_users(&UserQuery::new(None, 1, 2).unwrap())
+            .await
+            .unwrap();
+        let emails: Vec<&str> = page.users.iter().map(|user| user.email.as_ref()).collect();
+        assert_eq!(emails, vec!["alice@email.com", "bob@other.com"]);
+        assert_eq!(page.total, 3);
+
+        let page = store
+            .list_users(&UserQuery::new(Some("EMAIL.com".to_owned()), 2, 1).unwrap())
+            .await
+            .unwrap();
+        let emails: Vec<&str> = page.users.iter().map(|user| user.email.as_ref()).collect();
+        assert_eq!(emails, vec!["carol@email.com"]);
+        assert_eq!(page.total, 2);
+    }
 }
@@ -0,0 +1,197 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::domain::{
+    data_stores::{WebhookStore, WebhookStoreError},
+    DeliveryStatus, WebhookDelivery, WebhookSubscription,
+};
+
+#[derive(Default)]
+pub struct HashmapWebhookStore {
+    pub subscriptions: HashMap<Uuid, WebhookSubscription>,
+    pub deliveries: HashMap<Uuid, WebhookDelivery>,
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for HashmapWebhookStore {
+    async fn add_subscription(
+        &mut self,
+        subscription: WebhookSubscription,
+    ) -> Result<(), WebhookStoreError> {
+        self.subscriptions.insert(subscription.id, subscription);
+        Ok(())
+    }
+
+    async fn get_subscription(&self, id: &Uuid) -> Result<WebhookSubscription, WebhookStoreError> {
+        match self.subscriptions.get(id) {
+            Some(subscription) => Ok(subscription.clone()),
+            None => Err(WebhookStoreError::SubscriptionNotFound),
+        }
+    }
+
+    async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscription>, WebhookStoreError> {
+        let mut subscriptions: Vec<WebhookSubscription> =
+            self.subscriptions.values().cloned().collect();
+        subscriptions.sort_by_key(|subscription| subscription.created_at);
+        Ok(subscriptions)
+    }
+
+    async fn delete_subscription(&mut self, id: &Uuid) -> Result<(), WebhookStoreError> {
+        match self.subscriptions.remove(id) {
+            Some(_) => {
+                self.deliveries
+                    .retain(|_, delivery| &delivery.subscription_id != id);
+                Ok(())
+            }
+            None => Err(WebhookStoreError::SubscriptionNotFound),
+        }
+    }
+
+    async fn add_delivery(&mut self, delivery: WebhookDelivery) -> Result<(), WebhookStoreError> {
+        if !self.subscriptions.contains_key(&delivery.subscription_id) {
+            return Err(WebhookStoreError::SubscriptionNotFound);
+        }
+
+        self.deliveries.insert(delivery.id, delivery);
+        Ok(())
+    }
+
+    async fn get_delivery(&self, id: &Uuid) -> Result<WebhookDelivery, WebhookStoreError> {
+        match self.deliveries.get(id) {
+            Some(delivery) => Ok(delivery.clone()),
+            None => Err(WebhookStoreError::DeliveryNotFound),
+        }
+    }
+
+    async fn list_deliveries(
+        &self,
+        subscription_id: &Uuid,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        let mut deliveries: Vec<WebhookDelivery> = self
+            .deliveries
+            .values()
+            .filter(|delivery| &delivery.subscription_id == subscription_id)
+            .cloned()
+            .collect();
+        deliveries.sort_by_key(|delivery| Reverse(delivery.created_at));
+        deliveries.truncate(limit as usize);
+        Ok(deliveries)
+    }
+
+    async fn claim_due_deliveries(
+        &mut self,
+        limit: u32,
+        lease: Duration,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        let now = Utc::now();
+
+        let mut due: Vec<&mut WebhookDelivery> = self
+            .deliveries
+            .values_mut()
+            .filter(|delivery| {
+                delivery.status == DeliveryStatus::Pending && delivery.next_attempt_at <= now
+            })
+            .collect();
+        due.sort_by_key(|delivery| delivery.next_attempt_at);
+
+        Ok(due
+            .into_iter()
+            .take(limit as usize)
+            .map(|delivery| {
+                delivery.next_attempt_at = now + lease;
+                delivery.clone()
+            })
+            .collect())
+    }
+
+    async fn update_delivery(
+        &mut self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookStoreError> {
+        match self.deliveries.get_mut(&delivery.id) {
+            Some(stored) => {
+                *stored = delivery.clone();
+                Ok(())
+            }
+            None => Err(WebhookStoreError::DeliveryNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::WebhookEventKind;
+
+    use super::*;
+
+    fn subscription() -> WebhookSubscription {
+        WebhookSubscription::new(
+            "https://example.com/hooks",
+            vec![WebhookEventKind::UserSignedUp],
+            false,
+        )
+        .unwrap()
+    }
+
+    fn delivery(subscription_id: Uuid) -> WebhookDelivery {
+        WebhookDelivery::new(
+            subscription_id,
+            WebhookEventKind::UserSignedUp,
+            "{}".to_owned(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_claimed_deliveries_are_leased() {
+        let mut store = HashmapWebhookStore::default();
+        let subscription = subscription();
+        let subscription_id = subscription.id;
+        store.add_subscription(subscription).await.unwrap();
+        store.add_delivery(delivery(subscription_id)).await.unwrap();
+
+        let claimed = store
+            .claim_due_deliveries(10, Duration::minutes(5))
+            .await
+            .unwrap();
+        assert_eq!(claimed.len(), 1);
+
+        let claimed_again = store
+            .claim_due_deliveries(10, Duration::minutes(5))
+            .await
+            .unwrap();
+        assert!(claimed_again.is_empty());
+
+        let mut delivered = claimed[0].clone();
+        delivered.record_success(Utc::now());
+        store.update_delivery(&delivered).await.unwrap();
+
+        let stored = store.get_delivery(&delivered.id).await.unwrap();
+        assert_eq!(stored.status, DeliveryStatus::Delivered);
+        assert_eq!(stored.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_subscription_drops_its_deliveries() {
+        let mut store = HashmapWebhookStore::default();
+        let subscription = subscription();
+        let subscription_id = subscription.id;
+        store.add_subscription(subscription).await.unwrap();
+        let delivery = delivery(subscription_id);
+        let delivery_id = delivery.id;
+        store.add_delivery(delivery).await.unwrap();
+
+        store.delete_subscription(&subscription_id).await.unwrap();
+
+        assert_eq!(
+            store.get_delivery(&delivery_id).await,
+            Err(WebhookStoreError::DeliveryNotFound)
+        );
+        assert_eq!(
+            store.delete_subscription(&subscription_id).await,
+            Err(WebhookStoreError::SubscriptionNotFound)
+        );
+    }
+}
@@ -1,13 +1,77 @@
+mod hashmap_api_key_store;
+mod hashmap_audit_log_store;
+mod hashmap_authorization_code_store;
+mod hashmap_email_verification_token_store;
+mod hashmap_login_lockout_store;
+mod hashmap_machine_client_store;
+mod hashmap_magic_link_token_store;
+mod hashmap_oauth_client_store;
+mod hashmap_password_reset_token_store;
+mod hashmap_rate_limit_store;
+mod hashmap_recovery_code_store;
+mod hashmap_refresh_token_store;
+mod hashmap_role_store;
+mod hashmap_session_store;
+mod hashmap_totp_secret_store;
 mod hashmap_two_fa_code_store;
 // mod hashmap_banned_token_store;
 mod hashmap_user_store;
+mod hashmap_webhook_store;
+mod postgres_api_key_store;
+mod postgres_audit_log_store;
+mod postgres_machine_client_store;
+mod postgres_oauth_client_store;
+mod postgres_recovery_code_store;
+mod postgres_role_store;
+mod postgres_totp_secret_store;
 mod postgres_user_store;
+mod postgres_webhook_store;
+mod redis_authorization_code_store;
 mod redis_banned_token_store;
+mod redis_email_verification_token_store;
+mod redis_login_lockout_store;
+mod redis_magic_link_token_store;
+mod redis_password_reset_token_store;
+mod redis_rate_limit_store;
+mod redis_refresh_token_store;
+mod redis_session_store;
 mod redis_two_fa_code_store;
 
+pub use hashmap_api_key_store::*;
+pub use hashmap_audit_log_store::*;
+pub use hashmap_authorization_code_store::*;
+pub use hashmap_email_verification_token_store::*;
+pub use hashmap_login_lockout_store::*;
+pub use hashmap_machine_client_store::*;
+pub use hashmap_magic_link_token_store::*;
+pub use hashmap_oauth_client_store::*;
+pub use hashmap_password_reset_token_store::*;
+pub use hashmap_rate_limit_store::*;
+pub use hashmap_recovery_code_store::*;
+pub use hashmap_refresh_token_store::*;
+pub use hashmap_role_store::*;
+pub use hashmap_session_store::*;
+pub use hashmap_totp_secret_store::*;
 pub use hashmap_two_fa_code_store::*;
 // pub use hashmap_banned_token_store::*;
 pub use hashmap_user_store::*;
+pub use hashmap_webhook_store::*;
+pub use postgres_api_key_store::*;
+pub use postgres_audit_log_store::*;
+pub use postgres_machine_client_store::*;
+pub use postgres_oauth_client_store::*;
+pub use postgres_recovery_code_store::*;
+pub use postgres_role_store::*;
+pub use postgres_totp_secret_store::*;
 pub use postgres_user_store::*;
+pub use postgres_webhook_store::*;
+pub use redis_authorization_code_store::*;
 pub use redis_banned_token_store::*;
+pub use redis_email_verification_token_store::*;
+pub use redis_login_lockout_store::*;
+pub use redis_magic_link_token_store::*;
+pub use redis_password_reset_token_store::*;
+pub use redis_rate_limit_store::*;
+pub use redis_refresh_token_store::*;
+pub use redis_session_store::*;
 pub use redis_two_fa_code_store::*;
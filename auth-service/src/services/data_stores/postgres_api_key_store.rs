@@ -0,0 +1,138 @@
+use secrecy::Secret;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::{
+    data_stores::{ApiKey, ApiKeyRecord, ApiKeyStore, ApiKeyStoreError},
+    Email,
+};
+
+pub struct PostgresApiKeyStore {
+    pool: PgPool,
+}
+
+impl PostgresApiKeyStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for PostgresApiKeyStore {
+    #[tracing::instrument(name = "Adding API key to PostgreSQL", skip_all)]
+    async fn add_key(
+        &mut self,
+        key: &ApiKey,
+        record: ApiKeyRecord,
+    ) -> Result<(), ApiKeyStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO api_keys (id, email, name, key_hash, scopes, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            record.id,
+            record.email.as_ref(),
+            record.name,
+            key.hash(),
+            &record.scopes,
+            record.created_at,
+            record.expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving API key from PostgreSQL", skip_all)]
+    async fn get_key(&self, key: &ApiKey) -> Result<ApiKeyRecord, ApiKeyStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, email, name, scopes, created_at, expires_at
+            FROM api_keys
+            WHERE key_hash = $1
+            "#,
+            key.hash()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?
+        .ok_or(ApiKeyStoreError::KeyNotFound)?;
+
+        Ok(ApiKeyRecord {
+            id: row.id,
+            email: Email::parse(Secret::new(row.email))
+                .map_err(ApiKeyStoreError::UnexpectedError)?,
+            name: row.name,
+            scopes: row.scopes,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        })
+    }
+
+    #[tracing::instrument(name = "Listing API keys from PostgreSQL", skip_all)]
+    async fn list_keys(&self, email: &Email) -> Result<Vec<ApiKeyRecord>, ApiKeyStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, name, scopes, created_at, expires_at
+            FROM api_keys
+            WHERE email = $1
+            ORDER BY created_at
+            "#,
+            email.as_ref()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ApiKeyRecord {
+                id: row.id,
+                email: email.clone(),
+                name: row.name,
+                scopes: row.scopes,
+                created_at: row.created_at,
+                expires_at: row.expires_at,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "Revoking API key in PostgreSQL", skip_all)]
+    async fn revoke_key(&mut self, email: &Email, id: Uuid) -> Result<(), ApiKeyStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM api_keys
+            WHERE id = $1 AND email = $2
+            "#,
+            id,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiKeyStoreError::KeyNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Revoking all API keys in PostgreSQL", skip_all)]
+    async fn revoke_all_keys(&mut self, email: &Email) -> Result<(), ApiKeyStoreError> {
+        sqlx::query!(
+            r#"
+            DELETE FROM api_keys
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+}
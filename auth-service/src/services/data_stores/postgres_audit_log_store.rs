@@ -0,0 +1,119 @@
+use sqlx::PgPool;
+
+use crate::domain::{
+    data_stores::{AuditLogStore, AuditLogStoreError},
+    AuditEvent, AuditEventKind, AuditOutcome, AuditPage, AuditQuery,
+};
+
+pub struct PostgresAuditLogStore {
+    pool: PgPool,
+}
+
+impl PostgresAuditLogStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLogStore for PostgresAuditLogStore {
+    #[tracing::instrument(name = "Adding audit event to PostgreSQL", skip_all)]
+    async fn add_event(&mut self, event: AuditEvent) -> Result<(), AuditLogStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_events
+                (id, occurred_at, event_type, outcome, actor, ip, user_agent, request_id, reason)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            event.id,
+            event.occurred_at,
+            event.kind.as_ref(),
+            event.outcome.as_ref(),
+            event.actor,
+            event.ip,
+            event.user_agent,
+            event.request_id,
+            event.reason
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| AuditLogStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Listing audit events from PostgreSQL", skip_all)]
+    async fn list_events(&self, query: &AuditQuery) -> Result<AuditPage, AuditLogStoreError> {
+        let filter = &query.filter;
+        let kind = filter.kind.as_ref().map(AsRef::as_ref);
+        let outcome = filter.outcome.as_ref().map(AsRef::as_ref);
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "total!"
+            FROM audit_events
+            WHERE ($1::TEXT IS NULL OR actor = $1)
+                AND ($2::TEXT IS NULL OR event_type = $2)
+                AND ($3::TEXT IS NULL OR outcome = $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR occurred_at >= $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR occurred_at <= $5)
+            "#,
+            filter.actor,
+            kind,
+            outcome,
+            filter.since,
+            filter.until
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| AuditLogStoreError::UnexpectedError(e.into()))?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, occurred_at, event_type, outcome, actor, ip, user_agent, request_id, reason
+            FROM audit_events
+            WHERE ($1::TEXT IS NULL OR actor = $1)
+                AND ($2::TEXT IS NULL OR event_type = $2)
+                AND ($3::TEXT IS NULL OR outcome = $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR occurred_at >= $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR occurred_at <= $5)
+            ORDER BY occurred_at DESC, id
+            LIMIT $6 OFFSET $7
+            "#,
+            filter.actor,
+            kind,
+            outcome,
+            filter.since,
+            filter.until,
+            i64::from(query.per_page),
+            query.offset() as i64
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuditLogStoreError::UnexpectedError(e.into()))?;
+
+        let events = rows
+            .into_iter()
+            .map(|row| {
+                Ok(AuditEvent {
+                    id: row.id,
+                    occurred_at: row.occurred_at,
+                    kind: AuditEventKind::parse(&row.event_type)
+                        .map_err(AuditLogStoreError::UnexpectedError)?,
+                    outcome: AuditOutcome::parse(&row.outcome)
+                        .map_err(AuditLogStoreError::UnexpectedError)?,
+                    actor: row.actor,
+                    ip: row.ip,
+                    user_agent: row.user_agent,
+                    request_id: row.request_id,
+                    reason: row.reason,
+                })
+            })
+            .collect::<Result<Vec<AuditEvent>, AuditLogStoreError>>()?;
+
+        Ok(AuditPage {
+            events,
+            total: total as u64,
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use sqlx::PgPool;
+
+use crate::domain::{
+    data_stores::{MachineClientStore, MachineClientStoreError},
+    MachineClient,
+};
+
+pub struct PostgresMachineClientStore {
+    pool: PgPool,
+}
+
+impl PostgresMachineClientStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl MachineClientStore for PostgresMachineClientStore {
+    #[tracing::instrument(name = "Adding machine client to PostgreSQL", skip_all)]
+    async fn add_client(&mut self, client: MachineClient) -> Result<(), MachineClientStoreError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO machine_clients (client_id, name, client_secret_hash, scopes)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (client_id) DO NOTHING
+            "#,
+            client.client_id,
+            client.name,
+            client.secret_hash,
+            &client.scopes
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MachineClientStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(MachineClientStoreError::ClientAlreadyExists);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving machine client from PostgreSQL", skip_all)]
+    async fn get_client(&self, client_id: &str) -> Result<MachineClient, MachineClientStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT client_id, name, client_secret_hash, scopes
+            FROM machine_clients
+            WHERE client_id = $1
+            "#,
+            client_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| MachineClientStoreError::UnexpectedError(e.into()))?
+        .ok_or(MachineClientStoreError::ClientNotFound)?;
+
+        MachineClient::parse(row.client_id, row.name, row.client_secret_hash, row.scopes)
+            .map_err(MachineClientStoreError::UnexpectedError)
+    }
+}
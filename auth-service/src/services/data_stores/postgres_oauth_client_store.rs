@@ -0,0 +1,61 @@
+use sqlx::PgPool;
+
+use crate::domain::{
+    data_stores::{OAuthClientStore, OAuthClientStoreError},
+    OAuthClient,
+};
+
+pub struct PostgresOAuthClientStore {
+    pool: PgPool,
+}
+
+impl PostgresOAuthClientStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthClientStore for PostgresOAuthClientStore {
+    #[tracing::instrument(name = "Adding OAuth client to PostgreSQL", skip_all)]
+    async fn add_client(&mut self, client: OAuthClient) -> Result<(), OAuthClientStoreError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO oauth_clients (client_id, name, redirect_uris)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (client_id) DO NOTHING
+            "#,
+            client.client_id,
+            client.name,
+            &client.redirect_uris
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthClientStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthClientStoreError::ClientAlreadyExists);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving OAuth client from PostgreSQL", skip_all)]
+    async fn get_client(&self, client_id: &str) -> Result<OAuthClient, OAuthClientStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT client_id, name, redirect_uris
+            FROM oauth_clients
+            WHERE client_id = $1
+            "#,
+            client_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| OAuthClientStoreError::UnexpectedError(e.into()))?
+        .ok_or(OAuthClientStoreError::ClientNotFound)?;
+
+        OAuthClient::parse(row.client_id, row.name, row.redirect_uris)
+            .map_err(OAuthClientStoreError::UnexpectedError)
+    }
+}
@@ -0,0 +1,141 @@
+use color_eyre::eyre::Result;
+use sqlx::PgPool;
+
+use crate::domain::{
+    data_stores::{RecoveryCode, RecoveryCodeStore, RecoveryCodeStoreError},
+    Email,
+};
+
+pub struct PostgresRecoveryCodeStore {
+    pool: PgPool,
+}
+
+impl PostgresRecoveryCodeStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl RecoveryCodeStore for PostgresRecoveryCodeStore {
+    #[tracing::instrument(name = "Replacing recovery codes in PostgreSQL", skip_all)]
+    async fn replace_codes(
+        &mut self,
+        email: &Email,
+        codes: &[RecoveryCode],
+    ) -> Result<(), RecoveryCodeStoreError> {
+        let code_hashes = compute_code_hashes(codes.to_vec())
+            .await
+            .map_err(RecoveryCodeStoreError::UnexpectedError)?;
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| RecoveryCodeStoreError::UnexpectedError(e.into()))?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM recovery_codes
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| RecoveryCodeStoreError::UnexpectedError(e.into()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO recovery_codes (email, code_hash)
+            SELECT $1, code_hash FROM UNNEST($2::TEXT[]) AS code_hash
+            "#,
+            email.as_ref(),
+            &code_hashes
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| RecoveryCodeStoreError::UnexpectedError(e.into()))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| RecoveryCodeStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Consuming recovery code in PostgreSQL", skip_all)]
+    async fn consume_code(
+        &mut self,
+        email: &Email,
+        code: &RecoveryCode,
+    ) -> Result<(), RecoveryCodeStoreError> {
+        let code_hashes = sqlx::query_scalar!(
+            r#"
+            SELECT code_hash
+            FROM recovery_codes
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RecoveryCodeStoreError::UnexpectedError(e.into()))?;
+
+        let code_hash = find_matching_hash(code.clone(), code_hashes)
+            .await
+            .map_err(RecoveryCodeStoreError::UnexpectedError)?
+            .ok_or(RecoveryCodeStoreError::CodeNotFound)?;
+
+        // deleting the row is the redemption, so two requests can't both use one code
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM recovery_codes
+            WHERE email = $1 AND code_hash = $2
+            "#,
+            email.as_ref(),
+            code_hash
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RecoveryCodeStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RecoveryCodeStoreError::CodeNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[tracing::instrument(name = "Computing recovery code hashes", skip_all)]
+async fn compute_code_hashes(codes: Vec<RecoveryCode>) -> Result<Vec<String>> {
+    let current_span = tracing::Span::current();
+
+    let result = tokio::task::spawn_blocking(move || {
+        current_span.in_scope(|| codes.iter().map(RecoveryCode::hash).collect())
+    })
+    .await;
+
+    result?
+}
+
+#[tracing::instrument(name = "Matching recovery code hash", skip_all)]
+async fn find_matching_hash(
+    code: RecoveryCode,
+    code_hashes: Vec<String>,
+) -> Result<Option<String>> {
+    let current_span = tracing::Span::current();
+
+    let result = tokio::task::spawn_blocking(move || {
+        current_span.in_scope(|| {
+            code_hashes
+                .into_iter()
+                .find(|code_hash| code.matches(code_hash))
+        })
+    })
+    .await;
+
+    Ok(result?)
+}
@@ -0,0 +1,128 @@
+use sqlx::PgPool;
+
+use crate::domain::{
+    data_stores::{RoleStore, RoleStoreError},
+    Email, Role, UserAccess,
+};
+
+pub struct PostgresRoleStore {
+    pool: PgPool,
+}
+
+impl PostgresRoleStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl RoleStore for PostgresRoleStore {
+    #[tracing::instrument(name = "Listing roles from PostgreSQL", skip_all)]
+    async fn list_roles(&self) -> Result<Vec<Role>, RoleStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                roles.name,
+                roles.description,
+                COALESCE(
+                    ARRAY_AGG(role_permissions.permission ORDER BY role_permissions.permission)
+                        FILTER (WHERE role_permissions.permission IS NOT NULL),
+                    '{}'
+                ) AS "permissions!"
+            FROM roles
+            LEFT JOIN role_permissions ON role_permissions.role = roles.name
+            GROUP BY roles.name
+            ORDER BY roles.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Role {
+                name: row.name,
+                description: row.description,
+                permissions: row.permissions,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "Retrieving user access from PostgreSQL", skip_all)]
+    async fn get_user_access(&self, email: &Email) -> Result<UserAccess, RoleStoreError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT user_roles.role, role_permissions.permission AS "permission?"
+            FROM user_roles
+            LEFT JOIN role_permissions ON role_permissions.role = user_roles.role
+            WHERE user_roles.email = $1
+            "#,
+            email.as_ref()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))?;
+
+        let (roles, permissions): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .map(|row| (row.role, row.permission))
+            .unzip();
+
+        Ok(UserAccess::new(
+            roles,
+            permissions.into_iter().flatten().collect(),
+        ))
+    }
+
+    #[tracing::instrument(name = "Assigning role in PostgreSQL", skip_all)]
+    async fn assign_role(&mut self, email: &Email, role: &str) -> Result<(), RoleStoreError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_roles (email, role)
+            VALUES ($1, $2)
+            ON CONFLICT (email, role) DO NOTHING
+            "#,
+            email.as_ref(),
+            role
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => match e.constraint() {
+                Some("user_roles_role_fkey") => Err(RoleStoreError::RoleNotFound),
+                _ => Err(RoleStoreError::UserNotFound),
+            },
+            Err(e) => Err(RoleStoreError::UnexpectedError(e.into())),
+        }
+    }
+
+    #[tracing::instrument(name = "Unassigning role in PostgreSQL", skip_all)]
+    async fn unassign_role(&mut self, email: &Email, role: &str) -> Result<bool, RoleStoreError> {
+        let row = sqlx::query!(
+            r#"
+            WITH removed AS (
+                DELETE FROM user_roles
+                WHERE email = $1 AND role = $2
+                RETURNING role
+            )
+            SELECT
+                EXISTS(SELECT 1 FROM roles WHERE name = $2) AS "role_exists!",
+                EXISTS(SELECT 1 FROM removed) AS "removed!"
+            "#,
+            email.as_ref(),
+            role
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))?;
+
+        if !row.role_exists {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+
+        Ok(row.removed)
+    }
+}
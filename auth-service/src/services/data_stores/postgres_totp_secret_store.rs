@@ -0,0 +1,154 @@
+use color_eyre::eyre::Context;
+use sqlx::PgPool;
+
+use crate::{
+    domain::{
+        data_stores::{TotpRecord, TotpSecret, TotpSecretStore, TotpSecretStoreError},
+        Email,
+    },
+    utils::encryption::TOTP_SECRET_CIPHER,
+};
+
+pub struct PostgresTotpSecretStore {
+    pool: PgPool,
+}
+
+impl PostgresTotpSecretStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl TotpSecretStore for PostgresTotpSecretStore {
+    #[tracing::instrument(name = "Storing pending TOTP secret in PostgreSQL", skip_all)]
+    async fn set_pending_secret(
+        &mut self,
+        email: Email,
+        secret: TotpSecret,
+    ) -> Result<(), TotpSecretStoreError> {
+        let encrypted_secret = TOTP_SECRET_CIPHER
+            .encrypt(secret.as_ref())
+            .map_err(TotpSecretStoreError::UnexpectedError)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO totp_secrets (email, encrypted_secret, confirmed, last_used_step)
+            VALUES ($1, $2, FALSE, NULL)
+            ON CONFLICT (email) DO UPDATE
+            SET encrypted_secret = EXCLUDED.encrypted_secret,
+                confirmed = FALSE,
+                last_used_step = NULL
+            "#,
+            email.as_ref(),
+            encrypted_secret
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving TOTP secret from PostgreSQL", skip_all)]
+    async fn get_secret(&self, email: &Email) -> Result<TotpRecord, TotpSecretStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT encrypted_secret, confirmed
+            FROM totp_secrets
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?
+        .ok_or(TotpSecretStoreError::SecretNotFound)?;
+
+        let secret = TOTP_SECRET_CIPHER
+            .decrypt(&row.encrypted_secret)
+            .and_then(TotpSecret::parse)
+            .map_err(TotpSecretStoreError::UnexpectedError)?;
+
+        Ok(TotpRecord {
+            secret,
+            confirmed: row.confirmed,
+        })
+    }
+
+    #[tracing::instrument(name = "Confirming TOTP secret in PostgreSQL", skip_all)]
+    async fn confirm_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE totp_secrets
+            SET confirmed = TRUE
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(TotpSecretStoreError::SecretNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Recording used TOTP step in PostgreSQL", skip_all)]
+    async fn record_used_step(
+        &mut self,
+        email: &Email,
+        step: u64,
+    ) -> Result<(), TotpSecretStoreError> {
+        let step: i64 = step
+            .try_into()
+            .wrap_err("Failed to cast TOTP step to i64")
+            .map_err(TotpSecretStoreError::UnexpectedError)?;
+
+        // the condition makes check-and-set a single statement, so concurrent
+        // requests can't both use the same code
+        let result = sqlx::query!(
+            r#"
+            UPDATE totp_secrets
+            SET last_used_step = $1
+            WHERE email = $2 AND (last_used_step IS NULL OR last_used_step < $1)
+            "#,
+            step,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            // distinguish a replayed code from a missing secret
+            self.get_secret(email).await?;
+            return Err(TotpSecretStoreError::StepAlreadyUsed);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Removing TOTP secret from PostgreSQL", skip_all)]
+    async fn remove_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM totp_secrets
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(TotpSecretStoreError::SecretNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use sqlx::PgPool;
 
 use crate::domain::{
     data_stores::{UserStore, UserStoreError},
-    Email, Password, User,
+    AccountStatus, Email, Password, User, UserPage, UserQuery,
 };
 
 pub struct PostgresUserStore {
@@ -32,12 +32,13 @@ impl UserStore for PostgresUserStore {
 
         sqlx::query!(
             r#"
-            INSERT INTO users (email, password_hash, requires_2fa)
-            VALUES ($1, $2, $3)
+            INSERT INTO users (email, password_hash, requires_2fa, account_status)
+            VALUES ($1, $2, $3, $4)
         "#,
             &user.email.as_ref().to_string(),
             &hashed_password.expose_secret(),
-            &user.requires_2fa
+            &user.requires_2fa,
+            user.status.as_ref()
         )
         .execute(&self.pool)
         .await
@@ -50,8 +51,8 @@ impl UserStore for PostgresUserStore {
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError> {
         sqlx::query!(
             r#"
-            SELECT email, password_hash, requires_2fa 
-            FROM users 
+            SELECT email, password_hash, requires_2fa, account_status, token_version
+            FROM users
             WHERE email = $1;
             "#,
             email.as_ref()
@@ -61,11 +62,13 @@ impl UserStore for PostgresUserStore {
         .map_err(|e| UserStoreError::UnexpectedError(e.into()))?
         .map(|row| {
             Ok(User {
-                email: Email::parse(row.email.into())
-                    .map_err(|e| UserStoreError::UnexpectedError(e.into()))?,
+                email: Email::parse(row.email.into()).map_err(UserStoreError::UnexpectedError)?,
                 password: Password::parse(Secret::new(row.password_hash))
                     .map_err(UserStoreError::UnexpectedError)?,
                 requires_2fa: row.requires_2fa,
+                status: AccountStatus::parse(&row.account_status)
+                    .map_err(UserStoreError::UnexpectedError)?,
+                token_version: row.token_version as u32,
             })
         })
         .ok_or(UserStoreError::UserNotFound)?
@@ -85,6 +88,191 @@ impl UserStore for PostgresUserStore {
         .map_err(|_| UserStoreError::InvalidCredentials)?;
         Ok(())
     }
+
+    #[tracing::instrument(name = "Updating user password in PostgreSQL", skip_all)]
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError> {
+        let hashed_password = compute_password_hash(password.as_ref().to_owned())
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET password_hash = $1
+            WHERE email = $2
+            "#,
+            &hashed_password.expose_secret(),
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Updating account status in PostgreSQL", skip_all)]
+    async fn update_account_status(
+        &mut self,
+        email: &Email,
+        status: AccountStatus,
+    ) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET account_status = $1
+            WHERE email = $2
+            "#,
+            status.as_ref(),
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Updating 2FA requirement in PostgreSQL", skip_all)]
+    async fn update_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET requires_2fa = $1
+            WHERE email = $2
+            "#,
+            requires_2fa,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Bumping token version in PostgreSQL", skip_all)]
+    async fn bump_token_version(&mut self, email: &Email) -> Result<u32, UserStoreError> {
+        let token_version = sqlx::query_scalar!(
+            r#"
+            UPDATE users
+            SET token_version = token_version + 1
+            WHERE email = $1
+            RETURNING token_version
+            "#,
+            email.as_ref()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        Ok(token_version as u32)
+    }
+
+    #[tracing::instrument(name = "Deleting user from PostgreSQL", skip_all)]
+    async fn delete_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM users
+            WHERE email = $1
+            "#,
+            email.as_ref()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Listing users from PostgreSQL", skip_all)]
+    async fn list_users(&self, query: &UserQuery) -> Result<UserPage, UserStoreError> {
+        let pattern = query.email.as_deref().map(email_search_pattern);
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "total!"
+            FROM users
+            WHERE $1::TEXT IS NULL OR email ILIKE $1
+            "#,
+            pattern
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT email, password_hash, requires_2fa, account_status, token_version
+            FROM users
+            WHERE $1::TEXT IS NULL OR email ILIKE $1
+            ORDER BY email
+            LIMIT $2 OFFSET $3
+            "#,
+            pattern,
+            i64::from(query.per_page),
+            query.offset() as i64
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        let users = rows
+            .into_iter()
+            .map(|row| {
+                Ok(User {
+                    email: Email::parse(row.email.into())
+                        .map_err(UserStoreError::UnexpectedError)?,
+                    password: Password::parse(Secret::new(row.password_hash))
+                        .map_err(UserStoreError::UnexpectedError)?,
+                    requires_2fa: row.requires_2fa,
+                    status: AccountStatus::parse(&row.account_status)
+                        .map_err(UserStoreError::UnexpectedError)?,
+                    token_version: row.token_version as u32,
+                })
+            })
+            .collect::<Result<Vec<User>, UserStoreError>>()?;
+
+        Ok(UserPage {
+            users,
+            total: total as u64,
+        })
+    }
+}
+
+// matches emails containing `search`, with LIKE wildcards in it taken literally
+fn email_search_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{}%", escaped)
 }
 
 #[tracing::instrument(name = "Validating password hash", skip_all)]
@@ -96,7 +284,7 @@ async fn verify_password_hash(
     let result = tokio::task::spawn_blocking(move || {
         current_span.in_scope(|| {
             let expected_password_hash: PasswordHash<'_> =
-                PasswordHash::new(&expected_password_hash.expose_secret())?;
+                PasswordHash::new(expected_password_hash.expose_secret())?;
 
             Argon2::default()
                 .verify_password(
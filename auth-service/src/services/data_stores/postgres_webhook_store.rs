@@ -0,0 +1,328 @@
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::eyre::{Context, Result};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        data_stores::{WebhookStore, WebhookStoreError},
+        DeliveryStatus, WebhookDelivery, WebhookEventKind, WebhookSecret, WebhookSubscription,
+    },
+    utils::encryption::WEBHOOK_SECRET_CIPHER,
+};
+
+pub struct PostgresWebhookStore {
+    pool: PgPool,
+}
+
+impl PostgresWebhookStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct SubscriptionRow {
+    id: Uuid,
+    url: String,
+    encrypted_secret: String,
+    events: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<SubscriptionRow> for WebhookSubscription {
+    type Error = WebhookStoreError;
+
+    fn try_from(row: SubscriptionRow) -> Result<Self, Self::Error> {
+        let secret = WEBHOOK_SECRET_CIPHER
+            .decrypt(&row.encrypted_secret)
+            .and_then(WebhookSecret::parse)
+            .map_err(WebhookStoreError::UnexpectedError)?;
+
+        let events = row
+            .events
+            .iter()
+            .map(|kind| WebhookEventKind::parse(kind))
+            .collect::<Result<Vec<_>>>()
+            .map_err(WebhookStoreError::UnexpectedError)?;
+
+        Ok(Self {
+            id: row.id,
+            url: row.url,
+            secret,
+            events,
+            created_at: row.created_at,
+        })
+    }
+}
+
+struct DeliveryRow {
+    id: Uuid,
+    subscription_id: Uuid,
+    event_type: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    next_attempt_at: DateTime<Utc>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<DeliveryRow> for WebhookDelivery {
+    type Error = WebhookStoreError;
+
+    fn try_from(row: DeliveryRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            subscription_id: row.subscription_id,
+            event: WebhookEventKind::parse(&row.event_type)
+                .map_err(WebhookStoreError::UnexpectedError)?,
+            payload: row.payload,
+            status: DeliveryStatus::parse(&row.status)
+                .map_err(WebhookStoreError::UnexpectedError)?,
+            attempts: row.attempts as u32,
+            next_attempt_at: row.next_attempt_at,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            delivered_at: row.delivered_at,
+        })
+    }
+}
+
+fn attempts_to_i32(attempts: u32) -> Result<i32, WebhookStoreError> {
+    attempts
+        .try_into()
+        .wrap_err("Failed to cast delivery attempts to i32")
+        .map_err(WebhookStoreError::UnexpectedError)
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for PostgresWebhookStore {
+    #[tracing::instrument(name = "Adding webhook subscription to PostgreSQL", skip_all)]
+    async fn add_subscription(
+        &mut self,
+        subscription: WebhookSubscription,
+    ) -> Result<(), WebhookStoreError> {
+        let encrypted_secret = WEBHOOK_SECRET_CIPHER
+            .encrypt(subscription.secret.as_ref())
+            .map_err(WebhookStoreError::UnexpectedError)?;
+
+        let events: Vec<String> = subscription
+            .events
+            .iter()
+            .map(|kind| kind.as_ref().to_owned())
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_subscriptions (id, url, encrypted_secret, events, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            subscription.id,
+            subscription.url,
+            encrypted_secret,
+            &events,
+            subscription.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving webhook subscription from PostgreSQL", skip_all)]
+    async fn get_subscription(&self, id: &Uuid) -> Result<WebhookSubscription, WebhookStoreError> {
+        sqlx::query_as!(
+            SubscriptionRow,
+            r#"
+            SELECT id, url, encrypted_secret, events, created_at
+            FROM webhook_subscriptions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?
+        .ok_or(WebhookStoreError::SubscriptionNotFound)?
+        .try_into()
+    }
+
+    #[tracing::instrument(name = "Listing webhook subscriptions from PostgreSQL", skip_all)]
+    async fn list_subscriptions(&self) -> Result<Vec<WebhookSubscription>, WebhookStoreError> {
+        sqlx::query_as!(
+            SubscriptionRow,
+            r#"
+            SELECT id, url, encrypted_secret, events, created_at
+            FROM webhook_subscriptions
+            ORDER BY created_at, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(WebhookSubscription::try_from)
+        .collect()
+    }
+
+    #[tracing::instrument(name = "Deleting webhook subscription from PostgreSQL", skip_all)]
+    async fn delete_subscription(&mut self, id: &Uuid) -> Result<(), WebhookStoreError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM webhook_subscriptions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebhookStoreError::SubscriptionNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Queueing webhook delivery in PostgreSQL", skip_all)]
+    async fn add_delivery(&mut self, delivery: WebhookDelivery) -> Result<(), WebhookStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries
+                (id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+                 last_error, created_at, delivered_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            delivery.id,
+            delivery.subscription_id,
+            delivery.event.as_ref(),
+            delivery.payload,
+            delivery.status.as_ref(),
+            attempts_to_i32(delivery.attempts)?,
+            delivery.next_attempt_at,
+            delivery.last_error,
+            delivery.created_at,
+            delivery.delivered_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving webhook delivery from PostgreSQL", skip_all)]
+    async fn get_delivery(&self, id: &Uuid) -> Result<WebhookDelivery, WebhookStoreError> {
+        sqlx::query_as!(
+            DeliveryRow,
+            r#"
+            SELECT id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+                last_error, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?
+        .ok_or(WebhookStoreError::DeliveryNotFound)?
+        .try_into()
+    }
+
+    #[tracing::instrument(name = "Listing webhook deliveries from PostgreSQL", skip_all)]
+    async fn list_deliveries(
+        &self,
+        subscription_id: &Uuid,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        sqlx::query_as!(
+            DeliveryRow,
+            r#"
+            SELECT id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+                last_error, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE subscription_id = $1
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+            subscription_id,
+            i64::from(limit)
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(WebhookDelivery::try_from)
+        .collect()
+    }
+
+    #[tracing::instrument(name = "Claiming due webhook deliveries in PostgreSQL", skip_all)]
+    async fn claim_due_deliveries(
+        &mut self,
+        limit: u32,
+        lease: Duration,
+    ) -> Result<Vec<WebhookDelivery>, WebhookStoreError> {
+        let now = Utc::now();
+
+        // SKIP LOCKED lets several instances work through the queue without waiting on each other
+        let rows = sqlx::query_as!(
+            DeliveryRow,
+            r#"
+            UPDATE webhook_deliveries
+            SET next_attempt_at = $2
+            WHERE id IN (
+                SELECT id
+                FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= $1
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, subscription_id, event_type, payload, status, attempts,
+                next_attempt_at, last_error, created_at, delivered_at
+            "#,
+            now,
+            now + lease,
+            i64::from(limit)
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        rows.into_iter().map(WebhookDelivery::try_from).collect()
+    }
+
+    #[tracing::instrument(name = "Updating webhook delivery in PostgreSQL", skip_all)]
+    async fn update_delivery(
+        &mut self,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = $3, next_attempt_at = $4, last_error = $5,
+                delivered_at = $6
+            WHERE id = $1
+            "#,
+            delivery.id,
+            delivery.status.as_ref(),
+            attempts_to_i32(delivery.attempts)?,
+            delivery.next_attempt_at,
+            delivery.last_error,
+            delivery.delivered_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebhookStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebhookStoreError::DeliveryNotFound);
+        }
+
+        Ok(())
+    }
+}
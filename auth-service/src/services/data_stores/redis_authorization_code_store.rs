@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{
+            AuthorizationCode, AuthorizationCodeRecord, AuthorizationCodeStore,
+            AuthorizationCodeStoreError,
+        },
+        CodeChallenge, Email,
+    },
+    utils::constants::AUTHORIZATION_CODE_TTL_SECONDS,
+};
+
+pub struct RedisAuthorizationCodeStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisAuthorizationCodeStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeStore for RedisAuthorizationCodeStore {
+    #[tracing::instrument(name = "add_authorization_code", skip_all)]
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        record: AuthorizationCodeRecord,
+    ) -> Result<(), AuthorizationCodeStoreError> {
+        let value = serde_json::to_string(&StoredRecord {
+            email: record.email.as_ref().to_owned(),
+            client_id: record.client_id,
+            redirect_uri: record.redirect_uri,
+            code_challenge: record.code_challenge.as_ref().to_owned(),
+            scope: record.scope,
+            nonce: record.nonce,
+            auth_time: record.auth_time,
+        })
+        .wrap_err("Failed to json stringify authorization code record")
+        .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_key(&code), value, AUTHORIZATION_CODE_TTL_SECONDS)
+            .wrap_err("Failed to set authorization code in redis")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "consume_authorization_code", skip_all)]
+    async fn consume_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationCodeRecord, AuthorizationCodeStoreError> {
+        let value: Option<String> = self
+            .conn
+            .write()
+            .await
+            .get_del(get_key(code))
+            .wrap_err("Failed to get authorization code from redis")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+
+        let value = value.ok_or(AuthorizationCodeStoreError::CodeNotFound)?;
+
+        let record: StoredRecord = serde_json::from_str(&value)
+            .wrap_err("Failed to parse authorization code record")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+
+        Ok(AuthorizationCodeRecord {
+            email: Email::parse(Secret::new(record.email))
+                .map_err(AuthorizationCodeStoreError::UnexpectedError)?,
+            client_id: record.client_id,
+            redirect_uri: record.redirect_uri,
+            code_challenge: CodeChallenge::parse(record.code_challenge)
+                .map_err(AuthorizationCodeStoreError::UnexpectedError)?,
+            scope: record.scope,
+            nonce: record.nonce,
+            auth_time: record.auth_time,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRecord {
+    email: String,
+    client_id: String,
+    redirect_uri: String,
+    code_challenge: String,
+    scope: Option<String>,
+    nonce: Option<String>,
+    auth_time: usize,
+}
+
+const AUTHORIZATION_CODE_PREFIX: &str = "authorization_code:";
+
+fn get_key(code: &AuthorizationCode) -> String {
+    format!("{}{}", AUTHORIZATION_CODE_PREFIX, code.as_ref())
+}
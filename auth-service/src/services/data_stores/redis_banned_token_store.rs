@@ -48,7 +48,7 @@ impl BannedTokenStore for RedisBannedTokenStore {
             .conn
             .write()
             .await
-            .exists(&get_key(token))
+            .exists(get_key(token))
             .wrap_err("Failed to get token from redis")
             .map_err(BannedTokenStoreError::UnexpectedError)?)
     }
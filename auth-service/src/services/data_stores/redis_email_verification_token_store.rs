@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::Secret;
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{
+            EmailVerificationToken, EmailVerificationTokenStore, EmailVerificationTokenStoreError,
+        },
+        Email,
+    },
+    utils::constants::EMAIL_VERIFICATION_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisEmailVerificationTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisEmailVerificationTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailVerificationTokenStore for RedisEmailVerificationTokenStore {
+    #[tracing::instrument(name = "add_email_verification_token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: EmailVerificationToken,
+        email: Email,
+    ) -> Result<(), EmailVerificationTokenStoreError> {
+        let ttl: u64 = EMAIL_VERIFICATION_TOKEN_TTL_SECONDS
+            .try_into()
+            .wrap_err("Failed to cast EMAIL_VERIFICATION_TOKEN_TTL_SECONDS to u64")
+            .map_err(EmailVerificationTokenStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_key(&token), email.as_ref(), ttl)
+            .wrap_err("Failed to set email verification token in redis")
+            .map_err(EmailVerificationTokenStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "consume_email_verification_token", skip_all)]
+    async fn consume_token(
+        &mut self,
+        token: &EmailVerificationToken,
+    ) -> Result<Email, EmailVerificationTokenStoreError> {
+        let email: Option<String> = self
+            .conn
+            .write()
+            .await
+            .get_del(get_key(token))
+            .wrap_err("Failed to get email verification token from redis")
+            .map_err(EmailVerificationTokenStoreError::UnexpectedError)?;
+
+        let email = email.ok_or(EmailVerificationTokenStoreError::TokenNotFound)?;
+
+        Email::parse(Secret::new(email)).map_err(EmailVerificationTokenStoreError::UnexpectedError)
+    }
+}
+
+const EMAIL_VERIFICATION_TOKEN_PREFIX: &str = "email_verification_token:";
+
+fn get_key(token: &EmailVerificationToken) -> String {
+    format!("{}{}", EMAIL_VERIFICATION_TOKEN_PREFIX, token.as_ref())
+}
@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::Secret;
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{AccountUnlockToken, LoginLockoutStore, LoginLockoutStoreError},
+        Email,
+    },
+    utils::constants::LOGIN_LOCKOUT_HISTORY_TTL_SECONDS,
+};
+
+pub struct RedisLoginLockoutStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisLoginLockoutStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl LoginLockoutStore for RedisLoginLockoutStore {
+    #[tracing::instrument(name = "record_login_failure", skip_all)]
+    async fn record_failure(
+        &mut self,
+        email: &Email,
+        window_seconds: u64,
+    ) -> Result<u32, LoginLockoutStoreError> {
+        let key = get_failures_key(email);
+        let mut conn = self.conn.write().await;
+
+        let failures: u32 = conn
+            .incr(&key, 1)
+            .wrap_err("Failed to increment login failures in redis")
+            .map_err(LoginLockoutStoreError::UnexpectedError)?;
+
+        // the window starts at the first failure rather than sliding with each one
+        if failures == 1 {
+            conn.expire::<_, ()>(&key, window_seconds as i64)
+                .wrap_err("Failed to set login failures expiry in redis")
+                .map_err(LoginLockoutStoreError::UnexpectedError)?;
+        }
+
+        Ok(failures)
+    }
+
+    #[tracing::instrument(name = "get_lockout_count", skip_all)]
+    async fn get_lockout_count(&self, email: &Email) -> Result<u32, LoginLockoutStoreError> {
+        let count: Option<u32> = self
+            .conn
+            .write()
+            .await
+            .get(get_lockouts_key(email))
+            .wrap_err("Failed to get lockout count from redis")
+            .map_err(LoginLockoutStoreError::UnexpectedError)?;
+
+        Ok(count.unwrap_or_default())
+    }
+
+    #[tracing::instrument(name = "lock_account", skip_all)]
+    async fn lock(
+        &mut self,
+        email: &Email,
+        unlock_token: AccountUnlockToken,
+        lock_seconds: u64,
+    ) -> Result<(), LoginLockoutStoreError> {
+        let lockouts_key = get_lockouts_key(email);
+
+        redis::pipe()
+            .atomic()
+            .set_ex(get_lock_key(email), 1, lock_seconds)
+            .ignore()
+            .set_ex(
+                get_unlock_token_key(&unlock_token),
+                email.as_ref(),
+                lock_seconds,
+            )
+            .ignore()
+            .incr(&lockouts_key, 1)
+            .ignore()
+            .expire(&lockouts_key, LOGIN_LOCKOUT_HISTORY_TTL_SECONDS as i64)
+            .ignore()
+            .del(get_failures_key(email))
+            .ignore()
+            .query::<()>(&mut *self.conn.write().await)
+            .wrap_err("Failed to lock account in redis")
+            .map_err(LoginLockoutStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "get_lock_ttl", skip_all)]
+    async fn get_lock_ttl(&self, email: &Email) -> Result<Option<u64>, LoginLockoutStoreError> {
+        // negative when the key is missing
+        let ttl: i64 = self
+            .conn
+            .write()
+            .await
+            .ttl(get_lock_key(email))
+            .wrap_err("Failed to get account lock from redis")
+            .map_err(LoginLockoutStoreError::UnexpectedError)?;
+
+        Ok(u64::try_from(ttl).ok().filter(|ttl| *ttl > 0))
+    }
+
+    #[tracing::instrument(name = "unlock_account", skip_all)]
+    async fn unlock(
+        &mut self,
+        unlock_token: &AccountUnlockToken,
+    ) -> Result<Email, LoginLockoutStoreError> {
+        let email: Option<String> = self
+            .conn
+            .write()
+            .await
+            .get_del(get_unlock_token_key(unlock_token))
+            .wrap_err("Failed to get account unlock token from redis")
+            .map_err(LoginLockoutStoreError::UnexpectedError)?;
+
+        let email = email.ok_or(LoginLockoutStoreError::UnlockTokenNotFound)?;
+        let email =
+            Email::parse(Secret::new(email)).map_err(LoginLockoutStoreError::UnexpectedError)?;
+
+        self.reset(&email).await?;
+
+        Ok(email)
+    }
+
+    #[tracing::instrument(name = "reset_login_lockout", skip_all)]
+    async fn reset(&mut self, email: &Email) -> Result<(), LoginLockoutStoreError> {
+        self.conn
+            .write()
+            .await
+            .del::<_, ()>(&[
+                get_failures_key(email),
+                get_lockouts_key(email),
+                get_lock_key(email),
+            ])
+            .wrap_err("Failed to reset login lockout in redis")
+            .map_err(LoginLockoutStoreError::UnexpectedError)
+    }
+}
+
+const LOGIN_FAILURES_PREFIX: &str = "login_failures:";
+const LOGIN_LOCKOUTS_PREFIX: &str = "login_lockouts:";
+const LOGIN_LOCK_PREFIX: &str = "login_lock:";
+const ACCOUNT_UNLOCK_TOKEN_PREFIX: &str = "account_unlock_token:";
+
+fn get_failures_key(email: &Email) -> String {
+    format!("{}{}", LOGIN_FAILURES_PREFIX, email.as_ref())
+}
+
+fn get_lockouts_key(email: &Email) -> String {
+    format!("{}{}", LOGIN_LOCKOUTS_PREFIX, email.as_ref())
+}
+
+fn get_lock_key(email: &Email) -> String {
+    format!("{}{}", LOGIN_LOCK_PREFIX, email.as_ref())
+}
+
+fn get_unlock_token_key(token: &AccountUnlockToken) -> String {
+    format!("{}{}", ACCOUNT_UNLOCK_TOKEN_PREFIX, token.as_ref())
+}
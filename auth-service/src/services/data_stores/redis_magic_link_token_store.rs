@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::Secret;
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{MagicLinkToken, MagicLinkTokenStore, MagicLinkTokenStoreError},
+        Email,
+    },
+    utils::constants::MAGIC_LINK_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisMagicLinkTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisMagicLinkTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl MagicLinkTokenStore for RedisMagicLinkTokenStore {
+    #[tracing::instrument(name = "add_magic_link_token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: MagicLinkToken,
+        email: Email,
+    ) -> Result<(), MagicLinkTokenStoreError> {
+        let ttl: u64 = MAGIC_LINK_TOKEN_TTL_SECONDS
+            .try_into()
+            .wrap_err("Failed to cast MAGIC_LINK_TOKEN_TTL_SECONDS to u64")
+            .map_err(MagicLinkTokenStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_key(&token), email.as_ref(), ttl)
+            .wrap_err("Failed to set magic link token in redis")
+            .map_err(MagicLinkTokenStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "consume_magic_link_token", skip_all)]
+    async fn consume_token(
+        &mut self,
+        token: &MagicLinkToken,
+    ) -> Result<Email, MagicLinkTokenStoreError> {
+        // taken out with GETDEL so a link can only be opened once
+        let email: Option<String> = self
+            .conn
+            .write()
+            .await
+            .get_del(get_key(token))
+            .wrap_err("Failed to get magic link token from redis")
+            .map_err(MagicLinkTokenStoreError::UnexpectedError)?;
+
+        let email = email.ok_or(MagicLinkTokenStoreError::TokenNotFound)?;
+
+        Email::parse(Secret::new(email)).map_err(MagicLinkTokenStoreError::UnexpectedError)
+    }
+}
+
+const MAGIC_LINK_TOKEN_PREFIX: &str = "magic_link_token:";
+
+fn get_key(token: &MagicLinkToken) -> String {
+    format!("{}{}", MAGIC_LINK_TOKEN_PREFIX, token.as_ref())
+}
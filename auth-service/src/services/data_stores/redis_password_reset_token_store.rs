@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::Secret;
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{PasswordResetToken, PasswordResetTokenStore, PasswordResetTokenStoreError},
+        Email,
+    },
+    utils::constants::PASSWORD_RESET_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisPasswordResetTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisPasswordResetTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl PasswordResetTokenStore for RedisPasswordResetTokenStore {
+    #[tracing::instrument(name = "add_password_reset_token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        email: Email,
+    ) -> Result<(), PasswordResetTokenStoreError> {
+        let ttl: u64 = PASSWORD_RESET_TOKEN_TTL_SECONDS
+            .try_into()
+            .wrap_err("Failed to cast PASSWORD_RESET_TOKEN_TTL_SECONDS to u64")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_key(&token), email.as_ref(), ttl)
+            .wrap_err("Failed to set password reset token in redis")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "consume_password_reset_token", skip_all)]
+    async fn consume_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<Email, PasswordResetTokenStoreError> {
+        // GETDEL makes the token single use, even with concurrent requests
+        let email: Option<String> = self
+            .conn
+            .write()
+            .await
+            .get_del(get_key(token))
+            .wrap_err("Failed to get password reset token from redis")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+
+        let email = email.ok_or(PasswordResetTokenStoreError::TokenNotFound)?;
+
+        Email::parse(Secret::new(email)).map_err(PasswordResetTokenStoreError::UnexpectedError)
+    }
+}
+
+const PASSWORD_RESET_TOKEN_PREFIX: &str = "password_reset_token:";
+
+fn get_key(token: &PasswordResetToken) -> String {
+    format!("{}{}", PASSWORD_RESET_TOKEN_PREFIX, token.as_ref())
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use tokio::sync::RwLock;
+
+use crate::domain::data_stores::{RateLimitStore, RateLimitStoreError};
+
+pub struct RedisRateLimitStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisRateLimitStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    #[tracing::instrument(name = "increment_rate_limit", skip_all)]
+    async fn increment(
+        &mut self,
+        key: &str,
+        window_seconds: u64,
+    ) -> Result<(u32, u64), RateLimitStoreError> {
+        let key = get_key(key);
+        let mut conn = self.conn.write().await;
+
+        let count: u32 = conn
+            .incr(&key, 1)
+            .wrap_err("Failed to increment rate limit counter in redis")
+            .map_err(RateLimitStoreError::UnexpectedError)?;
+
+        let ttl: i64 = conn
+            .ttl(&key)
+            .wrap_err("Failed to get rate limit window from redis")
+            .map_err(RateLimitStoreError::UnexpectedError)?;
+
+        // a fixed window that starts with the first request, also repairing a
+        // counter left without an expiry
+        if count == 1 || ttl < 0 {
+            conn.expire::<_, ()>(&key, window_seconds as i64)
+                .wrap_err("Failed to set rate limit window in redis")
+                .map_err(RateLimitStoreError::UnexpectedError)?;
+
+            return Ok((count, window_seconds));
+        }
+
+        Ok((count, ttl as u64))
+    }
+}
+
+const RATE_LIMIT_PREFIX: &str = "rate_limit:";
+
+fn get_key(key: &str) -> String {
+    format!("{}{}", RATE_LIMIT_PREFIX, key)
+}
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::{Context, Result};
+use redis::{Commands, Connection, ExistenceCheck, SetExpiry, SetOptions};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{
+            RefreshToken, RefreshTokenFamilyId, RefreshTokenRecord, RefreshTokenStore,
+            RefreshTokenStoreError,
+        },
+        Email,
+    },
+    utils::constants::REFRESH_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisRefreshTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisRefreshTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for RedisRefreshTokenStore {
+    #[tracing::instrument(name = "add_refresh_token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        record: RefreshTokenRecord,
+    ) -> Result<(), RefreshTokenStoreError> {
+        let value = serde_json::to_string(&RefreshTokenTuple(
+            record.email.as_ref().to_owned(),
+            record.family_id.as_ref().to_owned(),
+        ))
+        .wrap_err("Failed to json stringify refresh token record")
+        .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        let ttl = get_ttl()?;
+
+        let mut conn = self.conn.write().await;
+
+        conn.set_ex::<_, _, ()>(get_key(&token), value, ttl)
+            .wrap_err("Failed to set refresh token in redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        // remember the user's families so every session can be revoked at once
+        let user_families_key = get_user_families_key(&record.email);
+
+        conn.sadd::<_, _, ()>(&user_families_key, record.family_id.as_ref())
+            .wrap_err("Failed to track refresh token family in redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        conn.expire::<_, ()>(&user_families_key, ttl as i64)
+            .wrap_err("Failed to set refresh token family expiry in redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "get_refresh_token", skip_all)]
+    async fn get_token(
+        &self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenRecord, RefreshTokenStoreError> {
+        let value: Option<String> = self
+            .conn
+            .write()
+            .await
+            .get(get_key(token))
+            .wrap_err("Failed to get refresh token from redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        let value = value.ok_or(RefreshTokenStoreError::TokenNotFound)?;
+
+        let data: RefreshTokenTuple = serde_json::from_str(&value)
+            .wrap_err("Failed to parse refresh token record")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        Ok(RefreshTokenRecord {
+            email: Email::parse(Secret::new(data.0))
+                .map_err(RefreshTokenStoreError::UnexpectedError)?,
+            family_id: RefreshTokenFamilyId::parse(data.1)
+                .map_err(RefreshTokenStoreError::UnexpectedError)?,
+        })
+    }
+
+    #[tracing::instrument(name = "consume_refresh_token", skip_all)]
+    async fn consume_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenRecord, RefreshTokenStoreError> {
+        let record = self.get_token(token).await?;
+
+        let mut conn = self.conn.write().await;
+
+        let revoked: bool = conn
+            .exists(get_revoked_family_key(&record.family_id))
+            .wrap_err("Failed to check refresh token family in redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        if revoked {
+            return Err(RefreshTokenStoreError::TokenRevoked);
+        }
+
+        // SET NX lets exactly one caller mark the token as used, even across replicas
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(get_ttl()? as usize));
+
+        let first_use: Option<String> = conn
+            .set_options(get_used_key(token), true, options)
+            .wrap_err("Failed to mark refresh token as used in redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        match first_use {
+            Some(_) => Ok(record),
+            None => Err(RefreshTokenStoreError::TokenReused(record.family_id)),
+        }
+    }
+
+    #[tracing::instrument(name = "revoke_refresh_token_family", skip_all)]
+    async fn revoke_family(
+        &mut self,
+        family_id: &RefreshTokenFamilyId,
+    ) -> Result<(), RefreshTokenStoreError> {
+        let ttl = get_ttl()?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_revoked_family_key(family_id), true, ttl)
+            .wrap_err("Failed to revoke refresh token family in redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "revoke_all_refresh_tokens", skip_all)]
+    async fn revoke_all_tokens(&mut self, email: &Email) -> Result<(), RefreshTokenStoreError> {
+        let user_families_key = get_user_families_key(email);
+
+        let family_ids: Vec<String> = self
+            .conn
+            .write()
+            .await
+            .smembers(&user_families_key)
+            .wrap_err("Failed to get refresh token families from redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        for family_id in family_ids {
+            let family_id = RefreshTokenFamilyId::parse(family_id)
+                .map_err(RefreshTokenStoreError::UnexpectedError)?;
+            self.revoke_family(&family_id).await?;
+        }
+
+        self.conn
+            .write()
+            .await
+            .del::<_, ()>(&user_families_key)
+            .wrap_err("Failed to delete refresh token families from redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RefreshTokenTuple(pub String, pub String);
+
+const REFRESH_TOKEN_PREFIX: &str = "refresh_token:";
+const USED_REFRESH_TOKEN_PREFIX: &str = "used_refresh_token:";
+const REVOKED_REFRESH_TOKEN_FAMILY_PREFIX: &str = "revoked_refresh_token_family:";
+const USER_REFRESH_TOKEN_FAMILIES_PREFIX: &str = "user_refresh_token_families:";
+
+fn get_ttl() -> Result<u64, RefreshTokenStoreError> {
+    REFRESH_TOKEN_TTL_SECONDS
+        .try_into()
+        .wrap_err("Failed to cast REFRESH_TOKEN_TTL_SECONDS to u64")
+        .map_err(RefreshTokenStoreError::UnexpectedError)
+}
+
+fn get_key(token: &RefreshToken) -> String {
+    format!("{}{}", REFRESH_TOKEN_PREFIX, token.as_ref())
+}
+
+fn get_used_key(token: &RefreshToken) -> String {
+    format!("{}{}", USED_REFRESH_TOKEN_PREFIX, token.as_ref())
+}
+
+fn get_revoked_family_key(family_id: &RefreshTokenFamilyId) -> String {
+    format!(
+        "{}{}",
+        REVOKED_REFRESH_TOKEN_FAMILY_PREFIX,
+        family_id.as_ref()
+    )
+}
+
+fn get_user_families_key(email: &Email) -> String {
+    format!("{}{}", USER_REFRESH_TOKEN_FAMILIES_PREFIX, email.as_ref())
+}
@@ -0,0 +1,221 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        data_stores::{SessionStore, SessionStoreError},
+        Email, Session, SessionClient, SessionId,
+    },
+    utils::constants::REFRESH_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisSessionStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisSessionStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    fn save(&self, conn: &mut Connection, session: &Session) -> Result<(), SessionStoreError> {
+        let value = serde_json::to_string(&StoredSession::from(session))
+            .wrap_err("Failed to serialize session")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        conn.set_ex::<_, _, ()>(get_key(&session.id), value, get_ttl()?)
+            .wrap_err("Failed to set session in redis")
+            .map_err(SessionStoreError::UnexpectedError)
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for RedisSessionStore {
+    #[tracing::instrument(name = "add_session", skip_all)]
+    async fn add_session(&mut self, session: Session) -> Result<(), SessionStoreError> {
+        let mut conn = self.conn.write().await;
+
+        self.save(&mut conn, &session)?;
+
+        let user_sessions_key = get_user_sessions_key(&session.email);
+
+        conn.sadd::<_, _, ()>(&user_sessions_key, session.id.as_ref())
+            .wrap_err("Failed to track session in redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        conn.expire::<_, ()>(&user_sessions_key, get_ttl()? as i64)
+            .wrap_err("Failed to set session list expiry in redis")
+            .map_err(SessionStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "get_session", skip_all)]
+    async fn get_session(&self, id: &SessionId) -> Result<Session, SessionStoreError> {
+        let mut conn = self.conn.write().await;
+
+        get_stored_session(&mut conn, id)?.ok_or(SessionStoreError::SessionNotFound)
+    }
+
+    #[tracing::instrument(name = "touch_session", skip_all)]
+    async fn touch_session(&mut self, id: &SessionId) -> Result<(), SessionStoreError> {
+        let mut conn = self.conn.write().await;
+
+        let mut session =
+            get_stored_session(&mut conn, id)?.ok_or(SessionStoreError::SessionNotFound)?;
+        session.last_used_at = Utc::now();
+
+        self.save(&mut conn, &session)?;
+
+        conn.expire::<_, ()>(get_user_sessions_key(&session.email), get_ttl()? as i64)
+            .wrap_err("Failed to set session list expiry in redis")
+            .map_err(SessionStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "list_sessions", skip_all)]
+    async fn list_sessions(&self, email: &Email) -> Result<Vec<Session>, SessionStoreError> {
+        let mut conn = self.conn.write().await;
+        let user_sessions_key = get_user_sessions_key(email);
+
+        let ids: Vec<String> = conn
+            .smembers(&user_sessions_key)
+            .wrap_err("Failed to get sessions from redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        let mut sessions = Vec::new();
+
+        for id in ids {
+            let session_id =
+                SessionId::parse(id.clone()).map_err(SessionStoreError::UnexpectedError)?;
+
+            match get_stored_session(&mut conn, &session_id)? {
+                Some(session) => sessions.push(session),
+                // expired on its own, so stop listing it
+                None => conn
+                    .srem::<_, _, ()>(&user_sessions_key, id)
+                    .wrap_err("Failed to remove expired session from redis")
+                    .map_err(SessionStoreError::UnexpectedError)?,
+            }
+        }
+
+        sessions.sort_by_key(|session| Reverse(session.created_at));
+
+        Ok(sessions)
+    }
+
+    #[tracing::instrument(name = "revoke_session", skip_all)]
+    async fn revoke_session(
+        &mut self,
+        email: &Email,
+        id: &SessionId,
+    ) -> Result<(), SessionStoreError> {
+        let mut conn = self.conn.write().await;
+
+        match get_stored_session(&mut conn, id)? {
+            Some(session) if &session.email == email => (),
+            _ => return Err(SessionStoreError::SessionNotFound),
+        }
+
+        conn.del::<_, ()>(get_key(id))
+            .wrap_err("Failed to delete session from redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        conn.srem::<_, _, ()>(get_user_sessions_key(email), id.as_ref())
+            .wrap_err("Failed to untrack session in redis")
+            .map_err(SessionStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "revoke_all_sessions", skip_all)]
+    async fn revoke_all_sessions(&mut self, email: &Email) -> Result<(), SessionStoreError> {
+        let mut conn = self.conn.write().await;
+        let user_sessions_key = get_user_sessions_key(email);
+
+        let ids: Vec<String> = conn
+            .smembers(&user_sessions_key)
+            .wrap_err("Failed to get sessions from redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        for id in ids {
+            conn.del::<_, ()>(format!("{}{}", SESSION_PREFIX, id))
+                .wrap_err("Failed to delete session from redis")
+                .map_err(SessionStoreError::UnexpectedError)?;
+        }
+
+        conn.del::<_, ()>(&user_sessions_key)
+            .wrap_err("Failed to delete session list from redis")
+            .map_err(SessionStoreError::UnexpectedError)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSession {
+    email: String,
+    created_at: DateTime<Utc>,
+    last_used_at: DateTime<Utc>,
+    ip: Option<String>,
+    user_agent: Option<String>,
+}
+
+impl From<&Session> for StoredSession {
+    fn from(session: &Session) -> Self {
+        Self {
+            email: session.email.as_ref().to_owned(),
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            ip: session.client.ip.clone(),
+            user_agent: session.client.user_agent.clone(),
+        }
+    }
+}
+
+const SESSION_PREFIX: &str = "session:";
+const USER_SESSIONS_PREFIX: &str = "user_sessions:";
+
+fn get_stored_session(
+    conn: &mut Connection,
+    id: &SessionId,
+) -> Result<Option<Session>, SessionStoreError> {
+    let value: Option<String> = conn
+        .get(get_key(id))
+        .wrap_err("Failed to get session from redis")
+        .map_err(SessionStoreError::UnexpectedError)?;
+
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    let stored: StoredSession = serde_json::from_str(&value)
+        .wrap_err("Failed to parse session")
+        .map_err(SessionStoreError::UnexpectedError)?;
+
+    Ok(Some(Session {
+        id: id.clone(),
+        email: Email::parse(Secret::new(stored.email))
+            .map_err(SessionStoreError::UnexpectedError)?,
+        created_at: stored.created_at,
+        last_used_at: stored.last_used_at,
+        client: SessionClient {
+            ip: stored.ip,
+            user_agent: stored.user_agent,
+        },
+    }))
+}
+
+fn get_ttl() -> Result<u64, SessionStoreError> {
+    REFRESH_TOKEN_TTL_SECONDS
+        .try_into()
+        .wrap_err("Failed to cast REFRESH_TOKEN_TTL_SECONDS to u64")
+        .map_err(SessionStoreError::UnexpectedError)
+}
+
+fn get_key(id: &SessionId) -> String {
+    format!("{}{}", SESSION_PREFIX, id.as_ref())
+}
+
+fn get_user_sessions_key(email: &Email) -> String {
+    format!("{}{}", USER_SESSIONS_PREFIX, email.as_ref())
+}
@@ -1,13 +1,16 @@
 use std::sync::Arc;
 
 use color_eyre::eyre::Context;
-use redis::{Commands, Connection};
+use redis::{Commands, Connection, ExistenceCheck, SetExpiry, SetOptions};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
-use crate::domain::{
-    data_stores::{LoginAttemptId, TwoFACode, TwoFACodeStore, TwoFACodeStoreError},
-    Email,
+use crate::{
+    domain::{
+        data_stores::{LoginAttemptId, TwoFACode, TwoFACodeStore, TwoFACodeStoreError},
+        Email,
+    },
+    utils::constants::MAX_TWO_FA_ATTEMPTS,
 };
 
 pub struct RedisTwoFACodeStore {
@@ -39,7 +42,7 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
             .conn
             .write()
             .await
-            .set_ex(&get_key(&email), tuple, TEN_MINUTES_IN_SECONDS)
+            .set_ex(get_key(&email), tuple, TEN_MINUTES_IN_SECONDS)
             .wrap_err("Failed to set tuple in redis")
             .map_err(TwoFACodeStoreError::UnexpectedError)?)
     }
@@ -75,6 +78,79 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
             Err(_) => Err(TwoFACodeStoreError::LoginAttemptIdNotFound),
         }
     }
+    #[tracing::instrument(name = "record_failed_attempt", skip_all)]
+    async fn record_failed_attempt(
+        &mut self,
+        email: &Email,
+        login_attempt_id: &LoginAttemptId,
+    ) -> Result<u32, TwoFACodeStoreError> {
+        let attempts_key = get_attempts_key(login_attempt_id);
+
+        // INCR is atomic, so concurrent guesses can't share a count
+        let (failed_attempts,): (u32,) = redis::pipe()
+            .atomic()
+            .incr(&attempts_key, 1)
+            .expire(&attempts_key, TEN_MINUTES_IN_SECONDS as i64)
+            .ignore()
+            .query(&mut *self.conn.write().await)
+            .wrap_err("Failed to increment failed 2FA attempts in redis")
+            .map_err(TwoFACodeStoreError::UnexpectedError)?;
+
+        if failed_attempts >= MAX_TWO_FA_ATTEMPTS {
+            match self.get_code(email).await {
+                // a newer login attempt has its own count
+                Ok((current_id, _)) if current_id != *login_attempt_id => (),
+                Ok(_) => self.remove_code(email).await?,
+                Err(TwoFACodeStoreError::LoginAttemptIdNotFound) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(failed_attempts)
+    }
+    #[tracing::instrument(name = "record_resend", skip_all)]
+    async fn record_resend(
+        &mut self,
+        login_attempt_id: &LoginAttemptId,
+        cooldown_seconds: u64,
+    ) -> Result<u32, TwoFACodeStoreError> {
+        let cooldown_key = get_resend_cooldown_key(login_attempt_id);
+        let resends_key = get_resends_key(login_attempt_id);
+        let mut conn = self.conn.write().await;
+
+        // SET NX only succeeds for the first request in each cooldown
+        let started: Option<String> = conn
+            .set_options(
+                &cooldown_key,
+                1,
+                SetOptions::default()
+                    .conditional_set(ExistenceCheck::NX)
+                    .with_expiration(SetExpiry::EX(cooldown_seconds as usize)),
+            )
+            .wrap_err("Failed to set 2FA resend cooldown in redis")
+            .map_err(TwoFACodeStoreError::UnexpectedError)?;
+
+        if started.is_none() {
+            let ttl: i64 = conn
+                .ttl(&cooldown_key)
+                .wrap_err("Failed to get 2FA resend cooldown ttl from redis")
+                .map_err(TwoFACodeStoreError::UnexpectedError)?;
+            return Err(TwoFACodeStoreError::ResendCooldown(
+                ttl.clamp(1, cooldown_seconds as i64) as u64,
+            ));
+        }
+
+        let (resends,): (u32,) = redis::pipe()
+            .atomic()
+            .incr(&resends_key, 1)
+            .expire(&resends_key, TEN_MINUTES_IN_SECONDS as i64)
+            .ignore()
+            .query(&mut *conn)
+            .wrap_err("Failed to increment 2FA resends in redis")
+            .map_err(TwoFACodeStoreError::UnexpectedError)?;
+
+        Ok(resends)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -82,7 +158,26 @@ struct TwoFATuple(pub String, pub String);
 
 pub const TEN_MINUTES_IN_SECONDS: u64 = 600;
 pub const TWO_FA_CODE_PREFIX: &str = "two_fa_code:";
+pub const TWO_FA_ATTEMPTS_PREFIX: &str = "two_fa_attempts:";
+pub const TWO_FA_RESENDS_PREFIX: &str = "two_fa_resends:";
+pub const TWO_FA_RESEND_COOLDOWN_PREFIX: &str = "two_fa_resend_cooldown:";
 
 fn get_key(email: &Email) -> String {
     format!("{}{}", TWO_FA_CODE_PREFIX, email.as_ref())
 }
+
+fn get_attempts_key(login_attempt_id: &LoginAttemptId) -> String {
+    format!("{}{}", TWO_FA_ATTEMPTS_PREFIX, login_attempt_id.as_ref())
+}
+
+fn get_resends_key(login_attempt_id: &LoginAttemptId) -> String {
+    format!("{}{}", TWO_FA_RESENDS_PREFIX, login_attempt_id.as_ref())
+}
+
+fn get_resend_cooldown_key(login_attempt_id: &LoginAttemptId) -> String {
+    format!(
+        "{}{}",
+        TWO_FA_RESEND_COOLDOWN_PREFIX,
+        login_attempt_id.as_ref()
+    )
+}
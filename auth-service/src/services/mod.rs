@@ -1,7 +1,9 @@
 mod data_stores;
 mod mock_email_client;
 mod postmark_email_client;
+mod webhook_dispatcher;
 
 pub use data_stores::*;
 pub use mock_email_client::*;
 pub use postmark_email_client::*;
+pub use webhook_dispatcher::*;
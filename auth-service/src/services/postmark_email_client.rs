@@ -123,6 +123,28 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn send_email_sends_the_expected_request() {
+        let mock_server = MockServer::start().await;
+        let email_client = email_client(mock_server.uri());
+
+        Mock::given(header_exists(POSTMARK_AUTH_HEADER))
+            .and(header("Content-Type", "application/json"))
+            .and(path("/email"))
+            .and(method("POST"))
+            .and(SendEmailBodyMatcher)
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let outcome = email_client
+            .send_email(&email(), &subject(), &content())
+            .await;
+
+        assert!(outcome.is_ok());
+    }
+
     #[tokio::test]
     async fn send_email_fails_if_the_server_return_500() {
         let mock_server = MockServer::start().await;
@@ -0,0 +1,210 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect, Client,
+};
+use tokio::sync::Notify;
+
+use crate::{
+    domain::{
+        data_stores::WebhookStoreError, is_private_ip, WebhookDelivery, WebhookSubscription,
+        WEBHOOK_EVENT_HEADER, WEBHOOK_ID_HEADER, WEBHOOK_SIGNATURE_HEADER,
+        WEBHOOK_TIMESTAMP_HEADER,
+    },
+    store::WebhookStoreType,
+};
+
+const DELIVERY_BATCH_SIZE: u32 = 20;
+
+// longer than a batch can take to send, so claimed deliveries aren't picked up again meanwhile
+const DELIVERY_LEASE_MINUTES: i64 = 5;
+
+// works through the persisted delivery queue in the background; handlers only enqueue
+pub struct WebhookDispatcher {
+    webhook_store: WebhookStoreType,
+    http_client: Client,
+    poll_interval: Duration,
+    wakeup: Arc<Notify>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        webhook_store: WebhookStoreType,
+        http_client: Client,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            webhook_store,
+            http_client,
+            poll_interval,
+            wakeup: Arc::new(Notify::new()),
+        }
+    }
+
+    // notified when deliveries are queued, so they go out without waiting for the next poll
+    pub fn wakeup(&self) -> Arc<Notify> {
+        self.wakeup.clone()
+    }
+
+    pub async fn run(self) {
+        loop {
+            match self.deliver_due().await {
+                // a full batch means more may be due already
+                Ok(attempted) if attempted == DELIVERY_BATCH_SIZE as usize => continue,
+                Ok(_) => {}
+                Err(e) => tracing::error!(error = ?e, "Failed to claim webhook deliveries"),
+            }
+
+            tokio::select! {
+                _ = self.wakeup.notified() => {}
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+    }
+
+    // returns how many deliveries were attempted
+    #[tracing::instrument(name = "Delivering due webhooks", skip_all)]
+    pub async fn deliver_due(&self) -> Result<usize, WebhookStoreError> {
+        let deliveries = self
+            .webhook_store
+            .write()
+            .await
+            .claim_due_deliveries(
+                DELIVERY_BATCH_SIZE,
+                chrono::Duration::minutes(DELIVERY_LEASE_MINUTES),
+            )
+            .await?;
+
+        let attempted = deliveries.len();
+        for delivery in deliveries {
+            self.attempt(delivery).await;
+        }
+
+        Ok(attempted)
+    }
+
+    async fn attempt(&self, mut delivery: WebhookDelivery) {
+        let subscription = match self
+            .webhook_store
+            .read()
+            .await
+            .get_subscription(&delivery.subscription_id)
+            .await
+        {
+            Ok(subscription) => subscription,
+            // deleted since the event was queued, its deliveries went with it
+            Err(WebhookStoreError::SubscriptionNotFound) => return,
+            Err(e) => {
+                tracing::error!(error = ?e, "Failed to load webhook subscription");
+                return;
+            }
+        };
+
+        match self.send(&subscription, &delivery).await {
+            Ok(()) => delivery.record_success(Utc::now()),
+            Err(error) => {
+                tracing::warn!(delivery_id = %delivery.id, error, "Webhook delivery failed");
+                delivery.record_failure(error, Utc::now());
+            }
+        }
+
+        if let Err(e) = self
+            .webhook_store
+            .write()
+            .await
+            .update_delivery(&delivery)
+            .await
+        {
+            tracing::error!(error = ?e, "Failed to record webhook delivery attempt");
+        }
+    }
+
+    #[tracing::instrument(name = "Sending webhook", skip_all, fields(delivery_id = %delivery.id))]
+    async fn send(
+        &self,
+        subscription: &WebhookSubscription,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), String> {
+        let timestamp = Utc::now().timestamp();
+        let signature = subscription.secret.sign(timestamp, &delivery.payload);
+
+        let response = self
+            .http_client
+            .post(&subscription.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, delivery.id.to_string())
+            .header(WEBHOOK_EVENT_HEADER, delivery.event.as_ref())
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Endpoint responded with {}", status));
+        }
+
+        Ok(())
+    }
+}
+
+// Redirects aren't followed, since a receiver could use one to bounce the dispatcher onto an
+// internal address. Unless `allow_private_hosts`, host names are only connected to through
+// the public addresses they resolve to, at the time of each delivery.
+pub fn webhook_http_client(
+    timeout: Duration,
+    allow_private_hosts: bool,
+) -> reqwest::Result<Client> {
+    let builder = Client::builder()
+        .timeout(timeout)
+        .redirect(redirect::Policy::none());
+
+    if allow_private_hosts {
+        builder.build()
+    } else {
+        builder
+            .dns_resolver(Arc::new(PublicAddressResolver))
+            .build()
+    }
+}
+
+// the connection is made to the addresses returned here, so a name can't be rebound to a
+// private address between the check and the request
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| !is_private_ip(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolver_refuses_names_of_private_addresses() {
+        let name = Name::from_str("localhost").unwrap();
+
+        assert!(PublicAddressResolver.resolve(name).await.is_err());
+    }
+}
@@ -1,12 +1,37 @@
 use std::sync::Arc;
 
-use tokio::sync::RwLock;
+use axum::extract::FromRef;
+use tokio::sync::{Notify, RwLock};
 
-use crate::domain::{BannedTokenStore, EmailClient, TwoFACodeStore, UserStore};
+use crate::domain::{
+    ApiKeyStore, AuditLogStore, AuthorizationCodeStore, BannedTokenStore, EmailClient,
+    EmailVerificationTokenStore, LoginLockoutStore, MachineClientStore, MagicLinkTokenStore,
+    OAuthClientStore, PasswordResetTokenStore, RateLimitStore, RecoveryCodeStore,
+    RefreshTokenStore, RoleStore, SessionStore, TotpSecretStore, TwoFACodeStore, UserStore,
+    WebhookStore,
+};
+use crate::utils::signing_key::{PublicKeys, PUBLIC_KEYS};
 
 pub type UserStoreType = Arc<RwLock<dyn UserStore + Send + Sync>>;
 pub type BannedTokenStoreType = Arc<RwLock<dyn BannedTokenStore + Send + Sync>>;
 pub type TwoFACodeStoreType = Arc<RwLock<dyn TwoFACodeStore + Send + Sync>>;
+pub type RefreshTokenStoreType = Arc<RwLock<dyn RefreshTokenStore + Send + Sync>>;
+pub type PasswordResetTokenStoreType = Arc<RwLock<dyn PasswordResetTokenStore + Send + Sync>>;
+pub type EmailVerificationTokenStoreType =
+    Arc<RwLock<dyn EmailVerificationTokenStore + Send + Sync>>;
+pub type TotpSecretStoreType = Arc<RwLock<dyn TotpSecretStore + Send + Sync>>;
+pub type RecoveryCodeStoreType = Arc<RwLock<dyn RecoveryCodeStore + Send + Sync>>;
+pub type LoginLockoutStoreType = Arc<RwLock<dyn LoginLockoutStore + Send + Sync>>;
+pub type RateLimitStoreType = Arc<RwLock<dyn RateLimitStore + Send + Sync>>;
+pub type MagicLinkTokenStoreType = Arc<RwLock<dyn MagicLinkTokenStore + Send + Sync>>;
+pub type OAuthClientStoreType = Arc<RwLock<dyn OAuthClientStore + Send + Sync>>;
+pub type AuthorizationCodeStoreType = Arc<RwLock<dyn AuthorizationCodeStore + Send + Sync>>;
+pub type MachineClientStoreType = Arc<RwLock<dyn MachineClientStore + Send + Sync>>;
+pub type ApiKeyStoreType = Arc<RwLock<dyn ApiKeyStore + Send + Sync>>;
+pub type RoleStoreType = Arc<RwLock<dyn RoleStore + Send + Sync>>;
+pub type SessionStoreType = Arc<RwLock<dyn SessionStore + Send + Sync>>;
+pub type AuditLogStoreType = Arc<RwLock<dyn AuditLogStore + Send + Sync>>;
+pub type WebhookStoreType = Arc<RwLock<dyn WebhookStore + Send + Sync>>;
 pub type EmailClientType = Arc<dyn EmailClient + Send + Sync>;
 
 #[derive(Clone)]
@@ -14,21 +39,100 @@ pub struct AppState {
     pub user_store: UserStoreType,
     pub banned_tokens_store: BannedTokenStoreType,
     pub two_fa_code_store: TwoFACodeStoreType,
+    pub refresh_token_store: RefreshTokenStoreType,
+    pub password_reset_token_store: PasswordResetTokenStoreType,
+    pub email_verification_token_store: EmailVerificationTokenStoreType,
+    pub totp_secret_store: TotpSecretStoreType,
+    pub recovery_code_store: RecoveryCodeStoreType,
+    pub login_lockout_store: LoginLockoutStoreType,
+    pub rate_limit_store: RateLimitStoreType,
+    pub magic_link_token_store: MagicLinkTokenStoreType,
+    pub oauth_client_store: OAuthClientStoreType,
+    pub authorization_code_store: AuthorizationCodeStoreType,
+    pub machine_client_store: MachineClientStoreType,
+    pub api_key_store: ApiKeyStoreType,
+    pub role_store: RoleStoreType,
+    pub session_store: SessionStoreType,
+    pub audit_log_store: AuditLogStoreType,
+    pub webhook_store: WebhookStoreType,
+    // wakes the webhook dispatcher when deliveries are queued
+    pub webhook_wakeup: Arc<Notify>,
     pub email_client: EmailClientType,
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_store: UserStoreType,
         banned_tokens_store: BannedTokenStoreType,
         two_fa_code_store: TwoFACodeStoreType,
+        refresh_token_store: RefreshTokenStoreType,
+        password_reset_token_store: PasswordResetTokenStoreType,
+        email_verification_token_store: EmailVerificationTokenStoreType,
+        totp_secret_store: TotpSecretStoreType,
+        recovery_code_store: RecoveryCodeStoreType,
+        login_lockout_store: LoginLockoutStoreType,
+        rate_limit_store: RateLimitStoreType,
+        magic_link_token_store: MagicLinkTokenStoreType,
+        oauth_client_store: OAuthClientStoreType,
+        authorization_code_store: AuthorizationCodeStoreType,
+        machine_client_store: MachineClientStoreType,
+        api_key_store: ApiKeyStoreType,
+        role_store: RoleStoreType,
+        session_store: SessionStoreType,
+        audit_log_store: AuditLogStoreType,
+        webhook_store: WebhookStoreType,
+        webhook_wakeup: Arc<Notify>,
         email_client: EmailClientType,
     ) -> Self {
         Self {
             user_store,
             banned_tokens_store,
             two_fa_code_store,
+            refresh_token_store,
+            password_reset_token_store,
+            email_verification_token_store,
+            totp_secret_store,
+            recovery_code_store,
+            login_lockout_store,
+            rate_limit_store,
+            magic_link_token_store,
+            oauth_client_store,
+            authorization_code_store,
+            machine_client_store,
+            api_key_store,
+            role_store,
+            session_store,
+            audit_log_store,
+            webhook_store,
+            webhook_wakeup,
             email_client,
         }
     }
 }
+
+// lets extractors such as `RequirePermission` validate tokens against any state holding these stores
+impl FromRef<AppState> for UserStoreType {
+    fn from_ref(state: &AppState) -> Self {
+        state.user_store.clone()
+    }
+}
+
+impl FromRef<AppState> for BannedTokenStoreType {
+    fn from_ref(state: &AppState) -> Self {
+        state.banned_tokens_store.clone()
+    }
+}
+
+impl FromRef<AppState> for SessionStoreType {
+    fn from_ref(state: &AppState) -> Self {
+        state.session_store.clone()
+    }
+}
+
+// this service verifies its own tokens from its published keys, like any other would
+impl FromRef<AppState> for PublicKeys {
+    fn from_ref(_: &AppState) -> Self {
+        PUBLIC_KEYS.clone()
+    }
+}
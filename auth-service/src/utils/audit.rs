@@ -0,0 +1,101 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    domain::{AuditEvent, AuditEventKind, AuditOutcome, AuthAPIError, SessionClient},
+    store::AppState,
+};
+
+use super::tracing::RequestId;
+
+// who made the request, recorded with every audit event
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuditContext
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let SessionClient { ip, user_agent } =
+            SessionClient::from_request_parts(parts, state).await?;
+
+        let request_id = parts.extensions.get::<RequestId>().map(|id| id.0.clone());
+
+        Ok(Self {
+            ip,
+            user_agent,
+            request_id,
+        })
+    }
+}
+
+impl AuditContext {
+    pub fn event(
+        &self,
+        kind: AuditEventKind,
+        outcome: AuditOutcome,
+        actor: Option<String>,
+        reason: Option<String>,
+    ) -> AuditEvent {
+        AuditEvent {
+            id: Uuid::new_v4(),
+            occurred_at: Utc::now(),
+            kind,
+            outcome,
+            actor,
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+            request_id: self.request_id.clone(),
+            reason,
+        }
+    }
+}
+
+// a failed write is logged rather than failing the request it describes
+#[tracing::instrument(name = "Record audit event", skip_all)]
+pub async fn record_audit_event(state: &AppState, event: AuditEvent) {
+    if let Err(e) = state.audit_log_store.write().await.add_event(event).await {
+        tracing::error!(error = ?e, "Failed to record audit event");
+    }
+}
+
+// records how a request went, a failed one carries its error as the reason
+pub async fn record_outcome(
+    state: &AppState,
+    context: &AuditContext,
+    kind: AuditEventKind,
+    actor: Option<String>,
+    error: Option<&AuthAPIError>,
+) {
+    let event = match error {
+        None => context.event(kind, AuditOutcome::Success, actor, None),
+        Some(e) => context.event(kind, AuditOutcome::Failure, actor, Some(e.to_string())),
+    };
+
+    record_audit_event(state, event).await;
+}
+
+pub async fn record_token_rejection(
+    state: &AppState,
+    context: &AuditContext,
+    actor: Option<String>,
+    reason: &str,
+) {
+    let event = context.event(
+        AuditEventKind::TokenRejected,
+        AuditOutcome::Failure,
+        actor,
+        Some(reason.to_owned()),
+    );
+
+    record_audit_event(state, event).await;
+}
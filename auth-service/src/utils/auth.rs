@@ -1,14 +1,33 @@
-use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
 use chrono::Utc;
 use color_eyre::eyre::{eyre, Context, ContextCompat, Report, Result};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Validation};
-use secrecy::ExposeSecret;
+use jsonwebtoken::{decode, decode_header, encode, Header, Validation};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{domain::email::Email, store::BannedTokenStoreType};
+use crate::{
+    domain::{
+        email::Email, AuthAPIError, RefreshToken, RefreshTokenFamilyId, RefreshTokenRecord,
+        Session, SessionClient, SessionId, SessionStoreError, UserStoreError,
+    },
+    store::{
+        AppState, BannedTokenStoreType, RefreshTokenStoreType, RoleStoreType, SessionStoreType,
+        UserStoreType,
+    },
+};
 
-use super::constants::{JWT_COOKIE_NAME, JWT_SECRET};
+use super::{
+    constants::{AUTH_SERVICE_URL, JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+    signing_key::{PublicKeys, JWT_ALGORITHM, KEY_RING, PUBLIC_KEYS},
+};
 
 pub const TOKEN_TTL_SECONDS: i64 = 600;
 
@@ -20,29 +39,91 @@ pub enum GenerateTokenError {
     UnexpectedError(#[source] Report),
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKind {
+    // `sub` is the user's email
+    #[default]
+    User,
+    // `sub` is a machine client's id
+    Client,
+    // `sub` is the user's email and `aud` the OAuth client acting for them
+    OAuth,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
+    // missing from tokens issued before OpenID Connect support
+    #[serde(default)]
+    pub iat: usize,
+    // unique per token
+    #[serde(default)]
+    pub jti: String,
+    // the session a user token belongs to, absent from tokens issued by `/token`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    // the user's token version when the token was issued, always 0 for client tokens
+    #[serde(default)]
+    pub token_version: u32,
+    #[serde(default)]
+    pub kind: TokenKind,
+    // only set on OAuth access tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    // a snapshot taken when the token was issued, role changes apply from the next one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+}
+
+impl Claims {
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
+}
+
+// id tokens carry an `aud` without being OAuth access tokens, which `validate_any_token`
+// rejects, so they can't be used as access tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub auth_time: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    pub email: String,
+    pub email_verified: bool,
 }
 
 #[tracing::instrument(name = "create_token", skip_all)]
-fn create_token(claims: &Claims) -> Result<String> {
-    encode(
-        &jsonwebtoken::Header::default(),
-        &claims,
-        &EncodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
-    )
-    .wrap_err("Failed to create token")
+fn create_token<T: Serialize>(claims: &T) -> Result<String> {
+    let signing_key = KEY_RING.signing_key();
+
+    let header = Header {
+        kid: Some(signing_key.kid().to_owned()),
+        ..Header::new(JWT_ALGORITHM)
+    };
+
+    encode(&header, &claims, signing_key.encoding_key()).wrap_err("Failed to create token")
 }
 
-#[tracing::instrument(name = "generate_auth_token", skip_all)]
-fn generate_auth_token(email: &Email) -> Result<String> {
+// returns the issued at and expiration times of a new token
+fn token_lifetime() -> Result<(usize, usize)> {
     let delta = chrono::Duration::try_seconds(TOKEN_TTL_SECONDS)
         .wrap_err("Failed to create 10 minute time delta")?;
 
+    let now = Utc::now();
+
     // create JWT experation time
-    let exp = Utc::now()
+    let exp = now
         .checked_add_signed(delta)
         .wrap_err("Failed to add 10 minutes to current time")?
         .timestamp();
@@ -52,15 +133,220 @@ fn generate_auth_token(email: &Email) -> Result<String> {
         .try_into()
         .wrap_err(format!("failed to cast i64 into usize. exp time: {}", exp))?;
 
+    let iat: usize = now
+        .timestamp()
+        .try_into()
+        .wrap_err("failed to cast current time into usize")?;
+
+    Ok((iat, exp))
+}
+
+#[tracing::instrument(name = "generate_auth_token", skip_all)]
+pub async fn generate_auth_token(
+    roles: &RoleStoreType,
+    email: &Email,
+    token_version: u32,
+    session_id: Option<&SessionId>,
+) -> Result<String> {
+    let access = roles
+        .read()
+        .await
+        .get_user_access(email)
+        .await
+        .wrap_err("Failed to retrieve user roles")?;
+
+    let (iat, exp) = token_lifetime()?;
+
     let sub = email.as_ref().to_owned();
 
-    let claims = Claims { sub, exp };
+    let claims = Claims {
+        sub,
+        exp,
+        iat,
+        jti: uuid::Uuid::new_v4().to_string(),
+        sid: session_id.map(|id| id.as_ref().to_owned()),
+        token_version,
+        kind: TokenKind::User,
+        aud: None,
+        scope: None,
+        roles: access.roles,
+        permissions: access.permissions,
+    };
+
+    create_token(&claims)
+}
+
+#[tracing::instrument(name = "generate_client_token", skip_all)]
+pub fn generate_client_token(client_id: &str, scopes: &[String]) -> Result<String> {
+    let (iat, exp) = token_lifetime()?;
+
+    let claims = Claims {
+        sub: client_id.to_owned(),
+        exp,
+        iat,
+        jti: uuid::Uuid::new_v4().to_string(),
+        sid: None,
+        token_version: 0,
+        kind: TokenKind::Client,
+        aud: None,
+        scope: Some(scopes.join(" ")),
+        roles: Vec::new(),
+        permissions: Vec::new(),
+    };
+
+    create_token(&claims)
+}
+
+// limited to the granted scope, it carries no roles so it can't be used on first-party routes
+#[tracing::instrument(name = "generate_oauth_access_token", skip_all)]
+pub fn generate_oauth_access_token(
+    email: &Email,
+    token_version: u32,
+    client_id: &str,
+    scope: Option<String>,
+) -> Result<String> {
+    let (iat, exp) = token_lifetime()?;
+
+    let claims = Claims {
+        sub: email.as_ref().to_owned(),
+        exp,
+        iat,
+        jti: uuid::Uuid::new_v4().to_string(),
+        sid: None,
+        token_version,
+        kind: TokenKind::OAuth,
+        aud: Some(client_id.to_owned()),
+        scope,
+        roles: Vec::new(),
+        permissions: Vec::new(),
+    };
+
+    create_token(&claims)
+}
+
+#[tracing::instrument(name = "generate_id_token", skip_all)]
+pub fn generate_id_token(
+    email: &Email,
+    email_verified: bool,
+    client_id: &str,
+    nonce: Option<String>,
+    auth_time: usize,
+) -> Result<String> {
+    let (iat, exp) = token_lifetime()?;
+
+    let claims = IdTokenClaims {
+        iss: AUTH_SERVICE_URL.to_owned(),
+        sub: email.as_ref().to_owned(),
+        aud: client_id.to_owned(),
+        exp,
+        iat,
+        auth_time,
+        nonce,
+        email: email.as_ref().to_owned(),
+        email_verified,
+    };
 
     create_token(&claims)
 }
 
+// user tokens only, every route acting on an account expects `sub` to be an email
 #[tracing::instrument(name = "validate_token", skip_all)]
-pub async fn validate_token(banned_tokens: &BannedTokenStoreType, token: &str) -> Result<Claims> {
+pub async fn validate_token(
+    banned_tokens: &BannedTokenStoreType,
+    sessions: &SessionStoreType,
+    users: &UserStoreType,
+    token: &str,
+) -> Result<Claims> {
+    let claims = validate_any_token(banned_tokens, sessions, users, token).await?;
+
+    if claims.kind != TokenKind::User {
+        return Err(eyre!("Client token used where a user token is required"));
+    }
+
+    Ok(claims)
+}
+
+#[tracing::instrument(name = "validate_client_token", skip_all)]
+pub async fn validate_client_token(
+    banned_tokens: &BannedTokenStoreType,
+    sessions: &SessionStoreType,
+    users: &UserStoreType,
+    token: &str,
+) -> Result<Claims> {
+    let claims = validate_any_token(banned_tokens, sessions, users, token).await?;
+
+    if claims.kind != TokenKind::Client {
+        return Err(eyre!("User token used where a client token is required"));
+    }
+
+    Ok(claims)
+}
+
+#[tracing::instrument(name = "validate_oauth_token", skip_all)]
+pub async fn validate_oauth_token(
+    banned_tokens: &BannedTokenStoreType,
+    sessions: &SessionStoreType,
+    users: &UserStoreType,
+    token: &str,
+) -> Result<Claims> {
+    let claims = validate_any_token(banned_tokens, sessions, users, token).await?;
+
+    if claims.kind != TokenKind::OAuth {
+        return Err(eyre!("Token wasn't issued to an OAuth client"));
+    }
+
+    Ok(claims)
+}
+
+#[tracing::instrument(name = "validate_any_token", skip_all)]
+pub async fn validate_any_token(
+    banned_tokens: &BannedTokenStoreType,
+    sessions: &SessionStoreType,
+    users: &UserStoreType,
+    token: &str,
+) -> Result<Claims> {
+    let claims = decode_claims(&PUBLIC_KEYS, token)?;
+
+    check_revocation(banned_tokens, sessions, users, token, &claims).await?;
+
+    Ok(claims)
+}
+
+// Checks the signature, expiry and shape of any token with the issuer's public keys alone, so
+// other services can run it too. A token revoked before it expires still passes.
+#[tracing::instrument(name = "decode_claims", skip_all)]
+pub fn decode_claims(keys: &PublicKeys, token: &str) -> Result<Claims> {
+    let header = decode_header(token).wrap_err("Failed to decode token header")?;
+
+    let decoding_key = keys
+        .decoding_key(header.kid.as_deref())
+        .wrap_err("Token signed with an unknown key")?;
+
+    // the audience is checked against the token's kind below
+    let mut validation = Validation::new(JWT_ALGORITHM);
+    validation.validate_aud = false;
+
+    let claims = decode::<Claims>(token, decoding_key, &validation)
+        .map(|data| data.claims)
+        .wrap_err("Failed to decode claims")?;
+
+    if claims.aud.is_some() != (claims.kind == TokenKind::OAuth) {
+        return Err(eyre!("Token audience doesn't match its kind"));
+    }
+
+    Ok(claims)
+}
+
+// rejects a token that was logged out, whose session was revoked or whose user's tokens
+// were all expired since it was issued
+#[tracing::instrument(name = "check_revocation", skip_all)]
+pub async fn check_revocation(
+    banned_tokens: &BannedTokenStoreType,
+    sessions: &SessionStoreType,
+    users: &UserStoreType,
+    token: &str,
+    claims: &Claims,
+) -> Result<()> {
     match banned_tokens.read().await.verify_token_exists(token).await {
         Err(e) => return Err(e.into()),
         Ok(value) => {
@@ -69,13 +355,101 @@ pub async fn validate_token(banned_tokens: &BannedTokenStoreType, token: &str) -
             }
         }
     }
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
-        &Validation::default(),
-    )
-    .map(|data| data.claims)
-    .wrap_err("Failed to decode claims")
+
+    // the token outlives its session if the session was revoked since it was issued
+    if let Some(sid) = &claims.sid {
+        let session_id = SessionId::parse(sid.clone())?;
+
+        match sessions.read().await.get_session(&session_id).await {
+            Ok(session) if session.email.as_ref() == claims.sub => (),
+            Ok(_) | Err(SessionStoreError::SessionNotFound) => {
+                return Err(eyre!("Token belongs to a revoked session"))
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    if matches!(claims.kind, TokenKind::User | TokenKind::OAuth) {
+        let email = Email::parse(claims.sub.clone().into())?;
+
+        match users.read().await.get_user(&email).await {
+            Ok(user) if user.token_version == claims.token_version => (),
+            Ok(_) => return Err(eyre!("Token version is no longer current")),
+            Err(UserStoreError::UserNotFound) => {
+                return Err(eyre!("Token belongs to a deleted user"))
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
+// the user signed in through the session cookie, rejects the request otherwise
+pub struct AuthenticatedUser {
+    pub email: Email,
+    pub claims: Claims,
+    // the raw cookie value, for routes that revoke it
+    pub token: String,
+}
+
+impl AuthenticatedUser {
+    #[tracing::instrument(name = "Authenticate user", skip_all)]
+    pub async fn from_token(
+        banned_tokens: &BannedTokenStoreType,
+        sessions: &SessionStoreType,
+        users: &UserStoreType,
+        token: String,
+    ) -> Result<Self, AuthAPIError> {
+        let claims = match validate_token(banned_tokens, sessions, users, &token).await {
+            Ok(claims) => claims,
+            Err(_) => return Err(AuthAPIError::InvalidToken),
+        };
+
+        let email = match Email::parse(claims.sub.clone().into()) {
+            Ok(email) => email,
+            Err(_) => return Err(AuthAPIError::InvalidToken),
+        };
+
+        Ok(Self {
+            email,
+            claims,
+            token,
+        })
+    }
+
+    pub fn session_id(&self) -> Option<SessionId> {
+        self.claims
+            .sid
+            .clone()
+            .and_then(|sid| SessionId::parse(sid).ok())
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthenticatedUser
+where
+    S: Send + Sync,
+    BannedTokenStoreType: FromRef<S>,
+    SessionStoreType: FromRef<S>,
+    UserStoreType: FromRef<S>,
+{
+    type Rejection = AuthAPIError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = match CookieJar::from_headers(&parts.headers).get(JWT_COOKIE_NAME) {
+            None => return Err(AuthAPIError::MissingToken),
+            Some(cookie) => cookie.value().to_owned(),
+        };
+
+        Self::from_token(
+            &BannedTokenStoreType::from_ref(state),
+            &SessionStoreType::from_ref(state),
+            &UserStoreType::from_ref(state),
+            token,
+        )
+        .await
+    }
 }
 
 #[tracing::instrument(name = "create_auth_cookie", skip_all)]
@@ -90,27 +464,146 @@ fn create_auth_cookie(token: String) -> Cookie<'static> {
 }
 
 #[tracing::instrument(name = "generate_auth_cookie", skip_all)]
-pub fn generate_auth_cookie(email: &Email) -> Result<Cookie<'static>> {
-    let token = generate_auth_token(email)?;
+pub async fn generate_auth_cookie(
+    roles: &RoleStoreType,
+    email: &Email,
+    token_version: u32,
+    session_id: &SessionId,
+) -> Result<Cookie<'static>> {
+    let token = generate_auth_token(roles, email, token_version, Some(session_id)).await?;
     Ok(create_auth_cookie(token))
 }
 
+// signs in a user who just authenticated, returning the auth and refresh cookies of a new session
+#[tracing::instrument(name = "start_session", skip_all)]
+pub async fn start_session(
+    state: &AppState,
+    email: &Email,
+    client: SessionClient,
+) -> Result<(Cookie<'static>, Cookie<'static>)> {
+    let token_version = state
+        .user_store
+        .read()
+        .await
+        .get_user(email)
+        .await
+        .wrap_err("Failed to retrieve user")?
+        .token_version;
+
+    let session = Session::new(email.clone(), client);
+    let session_id = session.id.clone();
+
+    state
+        .session_store
+        .write()
+        .await
+        .add_session(session)
+        .await
+        .wrap_err("Failed to store session")?;
+
+    let auth_cookie =
+        generate_auth_cookie(&state.role_store, email, token_version, &session_id).await?;
+    let refresh_cookie =
+        generate_refresh_cookie(&state.refresh_token_store, email, session_id).await?;
+
+    Ok((auth_cookie, refresh_cookie))
+}
+
+// every token of the user stops working, including access tokens that haven't expired yet
+// and API keys
+#[tracing::instrument(name = "end_all_sessions", skip_all)]
+pub async fn end_all_sessions(state: &AppState, email: &Email) -> Result<()> {
+    // the bump alone rejects every access token, the rest stops them being refreshed.
+    // A deleted user has no version left to bump, their tokens are rejected anyway
+    match state
+        .user_store
+        .write()
+        .await
+        .bump_token_version(email)
+        .await
+    {
+        Ok(_) | Err(UserStoreError::UserNotFound) => (),
+        Err(e) => return Err(Report::new(e).wrap_err("Failed to bump token version")),
+    }
+
+    state
+        .session_store
+        .write()
+        .await
+        .revoke_all_sessions(email)
+        .await
+        .wrap_err("Failed to revoke sessions")?;
+
+    state
+        .refresh_token_store
+        .write()
+        .await
+        .revoke_all_tokens(email)
+        .await
+        .wrap_err("Failed to revoke refresh tokens")?;
+
+    state
+        .api_key_store
+        .write()
+        .await
+        .revoke_all_keys(email)
+        .await
+        .wrap_err("Failed to revoke API keys")
+}
+
+#[tracing::instrument(name = "create_refresh_cookie", skip_all)]
+fn create_refresh_cookie(token: String) -> Cookie<'static> {
+    Cookie::build((REFRESH_TOKEN_COOKIE_NAME, token))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .build()
+}
+
+#[tracing::instrument(name = "generate_refresh_cookie", skip_all)]
+pub async fn generate_refresh_cookie(
+    refresh_tokens: &RefreshTokenStoreType,
+    email: &Email,
+    family_id: RefreshTokenFamilyId,
+) -> Result<Cookie<'static>> {
+    let token = RefreshToken::default();
+    let record = RefreshTokenRecord {
+        email: email.clone(),
+        family_id,
+    };
+
+    refresh_tokens
+        .write()
+        .await
+        .add_token(token.clone(), record)
+        .await
+        .wrap_err("Failed to store refresh token")?;
+
+    Ok(create_refresh_cookie(token.as_ref().to_owned()))
+}
+
 #[cfg(test)]
 mod tests {
     use secrecy::Secret;
-    // use std::{collections::HashMap, sync::Arc};
-    //
-    // use tokio::sync::RwLock;
-    //
+    use std::sync::Arc;
+    // use std::collections::HashMap;
+
+    use tokio::sync::RwLock;
+
+    use crate::domain::Role;
+    use crate::services::{HashmapRefreshTokenStore, HashmapRoleStore};
     // use crate::domain::BannedTokenStore;
 
     use super::*;
 
     #[tokio::test]
     async fn test_generate_auth_cookie() {
+        let roles: RoleStoreType = Arc::new(RwLock::new(HashmapRoleStore::default()));
         let email = Email::parse(Secret::new("test@test.com".to_owned())).unwrap();
 
-        let cookie = generate_auth_cookie(&email).unwrap();
+        let cookie = generate_auth_cookie(&roles, &email, 0, &SessionId::default())
+            .await
+            .unwrap();
 
         assert_eq!(cookie.name(), JWT_COOKIE_NAME);
         assert_eq!(cookie.value().split('.').count(), 3);
@@ -119,6 +612,40 @@ mod tests {
         assert_eq!(cookie.same_site(), Some(SameSite::Lax));
     }
 
+    #[tokio::test]
+    async fn test_generate_auth_token_embeds_roles() {
+        let mut store = HashmapRoleStore::default();
+        store.roles.insert(
+            "admin".to_owned(),
+            Role {
+                name: "admin".to_owned(),
+                description: String::new(),
+                permissions: vec!["roles:assign".to_owned()],
+            },
+        );
+        let roles: RoleStoreType = Arc::new(RwLock::new(store));
+        let email = Email::parse(Secret::new("test@test.com".to_owned())).unwrap();
+        roles
+            .write()
+            .await
+            .assign_role(&email, "admin")
+            .await
+            .unwrap();
+
+        let token = generate_auth_token(&roles, &email, 3, None).await.unwrap();
+
+        let header = decode_header(&token).unwrap();
+        let key = KEY_RING.verification_key(header.kid.as_deref()).unwrap();
+        let claims = decode::<Claims>(&token, key.decoding_key(), &Validation::new(JWT_ALGORITHM))
+            .unwrap()
+            .claims;
+
+        assert_eq!(claims.token_version, 3);
+        assert_eq!(claims.roles, vec!["admin"]);
+        assert!(claims.has_permission("roles:assign"));
+        assert!(!claims.has_permission("users:read"));
+    }
+
     #[tokio::test]
     async fn test_create_auth_cookie() {
         let token = "test_token".to_owned();
@@ -130,6 +657,26 @@ mod tests {
         assert_eq!(cookie.same_site(), Some(SameSite::Lax));
     }
 
+    #[tokio::test]
+    async fn test_generate_refresh_cookie() {
+        let refresh_tokens: RefreshTokenStoreType =
+            Arc::new(RwLock::new(HashmapRefreshTokenStore::default()));
+        let email = Email::parse(Secret::new("test@test.com".to_owned())).unwrap();
+        let family_id = RefreshTokenFamilyId::default();
+
+        let cookie = generate_refresh_cookie(&refresh_tokens, &email, family_id.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(cookie.name(), REFRESH_TOKEN_COOKIE_NAME);
+        assert_eq!(cookie.http_only(), Some(true));
+
+        let token = RefreshToken::parse(cookie.value().to_owned()).unwrap();
+        let record = refresh_tokens.read().await.get_token(&token).await.unwrap();
+        assert_eq!(record.email, email);
+        assert_eq!(record.family_id, family_id);
+    }
+
     // #[tokio::test]
     // async fn test_valildate_token_with_valid_token() {
     //     let banned_token_store: BannedTokenStoreType =
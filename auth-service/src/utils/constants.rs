@@ -4,15 +4,28 @@ use secrecy::Secret;
 use std::env as std_env;
 
 lazy_static! {
-    pub static ref JWT_SECRET: Secret<String> = set_token();
+    pub static ref JWT_SIGNING_KEY: Secret<String> = set_signing_key();
+    pub static ref JWT_PREVIOUS_SIGNING_KEYS: Secret<String> = set_previous_signing_keys();
     pub static ref DATABASE_URL: Secret<String> = set_database_url();
     pub static ref REDIS_HOST_NAME: String = set_redis_host();
     pub static ref POSTMARK_AUTH_TOKEN: Secret<String> = set_postmark_auth_token();
+    pub static ref AUTH_SERVICE_URL: String = set_auth_service_url();
+    pub static ref TOTP_ENCRYPTION_KEY: Secret<String> = set_totp_encryption_key();
+    pub static ref WEBHOOK_ENCRYPTION_KEY: Secret<String> = set_webhook_encryption_key();
+    pub static ref WEBHOOK_ALLOW_PRIVATE_HOSTS: bool = set_webhook_allow_private_hosts();
+    pub static ref LOGIN_MAX_FAILED_ATTEMPTS: u32 = set_login_max_failed_attempts();
+    pub static ref LOGIN_FAILURE_WINDOW_SECONDS: u64 = set_login_failure_window_seconds();
+    pub static ref LOGIN_LOCKOUT_SECONDS: u64 = set_login_lockout_seconds();
 }
 
-fn set_token() -> Secret<String> {
+fn set_signing_key() -> Secret<String> {
     dotenv().ok();
-    Secret::new(std_env::var(env::JWT_SECRET_ENV_VAR).expect("JWT_SECRET must be set"))
+    Secret::new(std_env::var(env::JWT_SIGNING_KEY_ENV_VAR).expect("JWT_SIGNING_KEY must be set"))
+}
+
+fn set_previous_signing_keys() -> Secret<String> {
+    dotenv().ok();
+    Secret::new(std_env::var(env::JWT_PREVIOUS_SIGNING_KEYS_ENV_VAR).unwrap_or_default())
 }
 
 fn set_database_url() -> Secret<String> {
@@ -32,15 +45,106 @@ fn set_postmark_auth_token() -> Secret<String> {
     )
 }
 
+fn set_auth_service_url() -> String {
+    dotenv().ok();
+    std_env::var(env::AUTH_SERVICE_URL_ENV_VAR).unwrap_or(DEFAULT_AUTH_SERVICE_URL.to_owned())
+}
+
+fn set_totp_encryption_key() -> Secret<String> {
+    dotenv().ok();
+    Secret::new(
+        std_env::var(env::TOTP_ENCRYPTION_KEY_ENV_VAR).expect("TOTP_ENCRYPTION_KEY must be set"),
+    )
+}
+
+fn set_webhook_encryption_key() -> Secret<String> {
+    dotenv().ok();
+    Secret::new(
+        std_env::var(env::WEBHOOK_ENCRYPTION_KEY_ENV_VAR)
+            .expect("WEBHOOK_ENCRYPTION_KEY must be set"),
+    )
+}
+
+fn set_webhook_allow_private_hosts() -> bool {
+    dotenv().ok();
+    std_env::var(env::WEBHOOK_ALLOW_PRIVATE_HOSTS_ENV_VAR)
+        .map(|value| {
+            value
+                .parse()
+                .expect("WEBHOOK_ALLOW_PRIVATE_HOSTS must be true or false")
+        })
+        .unwrap_or(false)
+}
+
+fn set_login_max_failed_attempts() -> u32 {
+    dotenv().ok();
+    std_env::var(env::LOGIN_MAX_FAILED_ATTEMPTS_ENV_VAR)
+        .map(|value| {
+            value
+                .parse()
+                .expect("LOGIN_MAX_FAILED_ATTEMPTS must be a number")
+        })
+        .unwrap_or(DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS)
+}
+
+fn set_login_failure_window_seconds() -> u64 {
+    dotenv().ok();
+    std_env::var(env::LOGIN_FAILURE_WINDOW_SECONDS_ENV_VAR)
+        .map(|value| {
+            value
+                .parse()
+                .expect("LOGIN_FAILURE_WINDOW_SECONDS must be a number")
+        })
+        .unwrap_or(DEFAULT_LOGIN_FAILURE_WINDOW_SECONDS)
+}
+
+fn set_login_lockout_seconds() -> u64 {
+    dotenv().ok();
+    std_env::var(env::LOGIN_LOCKOUT_SECONDS_ENV_VAR)
+        .map(|value| {
+            value
+                .parse()
+                .expect("LOGIN_LOCKOUT_SECONDS must be a number")
+        })
+        .unwrap_or(DEFAULT_LOGIN_LOCKOUT_SECONDS)
+}
+
 pub mod env {
-    pub const JWT_SECRET_ENV_VAR: &str = "JWT_SECRET";
+    pub const JWT_SIGNING_KEY_ENV_VAR: &str = "JWT_SIGNING_KEY";
+    pub const JWT_PREVIOUS_SIGNING_KEYS_ENV_VAR: &str = "JWT_PREVIOUS_SIGNING_KEYS";
     pub const DATABASE_URL_ENV_VAR: &str = "DATABASE_URL";
     pub const REDIS_HOST_NAME_ENV_VAR: &str = "REDIS_HOST_NAME";
     pub const POSTMARK_AUTH_TOKEN_ENV_VAR: &str = "POSTMARK_AUTH_TOKEN";
+    pub const AUTH_SERVICE_URL_ENV_VAR: &str = "AUTH_SERVICE_URL";
+    pub const TOTP_ENCRYPTION_KEY_ENV_VAR: &str = "TOTP_ENCRYPTION_KEY";
+    pub const WEBHOOK_ENCRYPTION_KEY_ENV_VAR: &str = "WEBHOOK_ENCRYPTION_KEY";
+    pub const WEBHOOK_ALLOW_PRIVATE_HOSTS_ENV_VAR: &str = "WEBHOOK_ALLOW_PRIVATE_HOSTS";
+    pub const LOGIN_MAX_FAILED_ATTEMPTS_ENV_VAR: &str = "LOGIN_MAX_FAILED_ATTEMPTS";
+    pub const LOGIN_FAILURE_WINDOW_SECONDS_ENV_VAR: &str = "LOGIN_FAILURE_WINDOW_SECONDS";
+    pub const LOGIN_LOCKOUT_SECONDS_ENV_VAR: &str = "LOGIN_LOCKOUT_SECONDS";
 }
 
 pub const JWT_COOKIE_NAME: &str = "jwt";
+pub const REFRESH_TOKEN_COOKIE_NAME: &str = "refresh_token";
 pub const DEFAULT_REDIS_HOSTNAME: &str = "127.0.0.1";
+pub const DEFAULT_AUTH_SERVICE_URL: &str = "http://localhost:3000";
+pub const TOTP_ISSUER: &str = "LGR Auth";
+pub const DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS: u32 = 5;
+pub const DEFAULT_LOGIN_FAILURE_WINDOW_SECONDS: u64 = 60 * 15;
+pub const DEFAULT_LOGIN_LOCKOUT_SECONDS: u64 = 60;
+pub const REFRESH_TOKEN_TTL_SECONDS: i64 = 60 * 60 * 24 * 14;
+pub const PASSWORD_RESET_TOKEN_TTL_SECONDS: i64 = 60 * 30;
+pub const MAGIC_LINK_TOKEN_TTL_SECONDS: i64 = 60 * 15;
+pub const AUTHORIZATION_CODE_TTL_SECONDS: u64 = 60;
+pub const EMAIL_VERIFICATION_TOKEN_TTL_SECONDS: i64 = 60 * 60 * 24;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+pub const RECOVERY_CODE_LENGTH: usize = 10;
+pub const MAX_TWO_FA_ATTEMPTS: u32 = 5;
+pub const MAX_TWO_FA_RESENDS: u32 = 3;
+pub const TWO_FA_RESEND_COOLDOWN_SECONDS: u64 = 30;
+pub const MAX_LOGIN_LOCKOUT_SECONDS: u64 = 60 * 60 * 24;
+// lockouts older than this no longer lengthen the next one
+pub const LOGIN_LOCKOUT_HISTORY_TTL_SECONDS: u64 = 60 * 60 * 24;
 
 pub mod prod {
     pub const APP_ADDRESS: &str = "0.0.0.0:3000";
@@ -52,6 +156,12 @@ pub mod prod {
         pub const SENDER: &str = "bogdan@codeiron.io";
         pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
     }
+    pub mod webhooks {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
+        pub const POLL_INTERVAL: Duration = std::time::Duration::from_secs(5);
+    }
 }
 
 pub mod test {
@@ -62,4 +172,10 @@ pub mod test {
         pub const SENDER: &str = "test@email.com";
         pub const TIMEOUT: Duration = std::time::Duration::from_millis(200);
     }
+    pub mod webhooks {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_millis(500);
+        pub const POLL_INTERVAL: Duration = std::time::Duration::from_millis(100);
+    }
 }
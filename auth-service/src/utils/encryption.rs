@@ -0,0 +1,137 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use color_eyre::eyre::{eyre, Context, Result};
+use lazy_static::lazy_static;
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use secrecy::{ExposeSecret, Secret};
+
+use super::constants::{TOTP_ENCRYPTION_KEY, WEBHOOK_ENCRYPTION_KEY};
+
+lazy_static! {
+    pub static ref TOTP_SECRET_CIPHER: SecretCipher =
+        SecretCipher::from_base64(&TOTP_ENCRYPTION_KEY)
+            .expect("TOTP_ENCRYPTION_KEY must be a base64 encoded 32 byte key");
+    pub static ref WEBHOOK_SECRET_CIPHER: SecretCipher =
+        SecretCipher::from_base64(&WEBHOOK_ENCRYPTION_KEY)
+            .expect("WEBHOOK_ENCRYPTION_KEY must be a base64 encoded 32 byte key");
+}
+
+// AES-256-GCM for secrets that have to be read back, unlike passwords which are hashed.
+// The random nonce is stored in front of the ciphertext.
+pub struct SecretCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl SecretCipher {
+    pub fn new(key: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| eyre!("Encryption key must be {} bytes", AES_256_GCM.key_len()))?;
+
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    pub fn from_base64(key: &Secret<String>) -> Result<Self> {
+        let key = STANDARD
+            .decode(key.expose_secret().trim())
+            .wrap_err("Failed to decode encryption key")?;
+
+        Self::new(&key)
+    }
+
+    pub fn encrypt(&self, plaintext: &Secret<String>) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| eyre!("Failed to generate nonce"))?;
+
+        let mut in_out = plaintext.expose_secret().as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| eyre!("Failed to encrypt secret"))?;
+
+        Ok(STANDARD.encode([nonce.as_slice(), &in_out].concat()))
+    }
+
+    pub fn decrypt(&self, ciphertext: &str) -> Result<Secret<String>> {
+        let data = STANDARD
+            .decode(ciphertext)
+            .wrap_err("Failed to decode ciphertext")?;
+
+        if data.len() < NONCE_LEN {
+            return Err(eyre!("Ciphertext is too short"));
+        }
+
+        let (nonce, sealed) = data.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| eyre!("Invalid nonce"))?;
+
+        let mut in_out = sealed.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| eyre!("Failed to decrypt secret"))?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map(Secret::new)
+            .wrap_err("Decrypted secret is not valid utf-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> SecretCipher {
+        let mut key = [0u8; 32];
+        SystemRandom::new().fill(&mut key).unwrap();
+        SecretCipher::new(&key).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = cipher();
+        let plaintext = Secret::new("JBSWY3DPEHPK3PXP".to_owned());
+
+        let ciphertext = cipher.encrypt(&plaintext).unwrap();
+
+        assert!(!ciphertext.contains(plaintext.expose_secret()));
+        assert_eq!(
+            cipher.decrypt(&ciphertext).unwrap().expose_secret(),
+            plaintext.expose_secret()
+        );
+    }
+
+    #[test]
+    fn test_same_plaintext_encrypts_differently() {
+        let cipher = cipher();
+        let plaintext = Secret::new("JBSWY3DPEHPK3PXP".to_owned());
+
+        assert_ne!(
+            cipher.encrypt(&plaintext).unwrap(),
+            cipher.encrypt(&plaintext).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decrypt_with_other_key_fails() {
+        let ciphertext = cipher()
+            .encrypt(&Secret::new("JBSWY3DPEHPK3PXP".to_owned()))
+            .unwrap();
+
+        assert!(cipher().decrypt(&ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_invalid_key_length_is_rejected() {
+        assert!(SecretCipher::new(&[0u8; 16]).is_err());
+    }
+}
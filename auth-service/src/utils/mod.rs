@@ -1,3 +1,10 @@
+pub mod audit;
 pub mod auth;
 pub mod constants;
+pub mod encryption;
+pub mod permission;
+pub mod rate_limit;
+pub mod signing_key;
+pub mod totp;
 pub mod tracing;
+pub mod webhooks;
@@ -0,0 +1,223 @@
+use std::marker::PhantomData;
+
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    domain::AuthAPIError,
+    store::{BannedTokenStoreType, SessionStoreType, UserStoreType},
+};
+
+use super::{
+    auth::{check_revocation, decode_claims, Claims, TokenKind},
+    constants::JWT_COOKIE_NAME,
+    signing_key::PublicKeys,
+};
+
+pub trait Permission {
+    const NAME: &'static str;
+}
+
+pub struct AssignRoles;
+
+impl Permission for AssignRoles {
+    const NAME: &'static str = "roles:assign";
+}
+
+pub struct ManageUsers;
+
+impl Permission for ManageUsers {
+    const NAME: &'static str = "users:manage";
+}
+
+pub struct ReadAuditLog;
+
+impl Permission for ReadAuditLog {
+    const NAME: &'static str = "audit:read";
+}
+
+pub struct ManageWebhooks;
+
+impl Permission for ManageWebhooks {
+    const NAME: &'static str = "webhooks:manage";
+}
+
+// Rejects the request unless the caller's user token grants `P`. Only the token's signature
+// and expiry are checked, against the issuer's public keys, so any service holding the JWKS
+// can use it. A token revoked before it expires is still accepted.
+pub struct RequireVerifiedPermission<P: Permission> {
+    pub claims: Claims,
+    permission: PhantomData<P>,
+}
+
+#[axum::async_trait]
+impl<S, P> FromRequestParts<S> for RequireVerifiedPermission<P>
+where
+    S: Send + Sync,
+    P: Permission,
+    PublicKeys: FromRef<S>,
+{
+    type Rejection = AuthAPIError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = request_token(parts)?;
+        let claims = user_claims(&PublicKeys::from_ref(state), &token)?;
+
+        Ok(Self {
+            claims: require::<P>(claims)?,
+            permission: PhantomData,
+        })
+    }
+}
+
+// `RequireVerifiedPermission`, also rejecting tokens revoked since they were issued, which
+// needs this service's stores
+pub struct RequirePermission<P: Permission> {
+    pub claims: Claims,
+    permission: PhantomData<P>,
+}
+
+#[axum::async_trait]
+impl<S, P> FromRequestParts<S> for RequirePermission<P>
+where
+    S: Send + Sync,
+    P: Permission,
+    PublicKeys: FromRef<S>,
+    BannedTokenStoreType: FromRef<S>,
+    SessionStoreType: FromRef<S>,
+    UserStoreType: FromRef<S>,
+{
+    type Rejection = AuthAPIError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = request_token(parts)?;
+        let claims = user_claims(&PublicKeys::from_ref(state), &token)?;
+
+        if check_revocation(
+            &BannedTokenStoreType::from_ref(state),
+            &SessionStoreType::from_ref(state),
+            &UserStoreType::from_ref(state),
+            &token,
+            &claims,
+        )
+        .await
+        .is_err()
+        {
+            return Err(AuthAPIError::InvalidToken);
+        }
+
+        Ok(Self {
+            claims: require::<P>(claims)?,
+            permission: PhantomData,
+        })
+    }
+}
+
+// from a bearer header or the session cookie
+fn request_token(parts: &Parts) -> Result<String, AuthAPIError> {
+    let bearer = parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_owned);
+
+    match bearer {
+        Some(token) => Ok(token),
+        None => match CookieJar::from_headers(&parts.headers).get(JWT_COOKIE_NAME) {
+            Some(cookie) => Ok(cookie.value().to_owned()),
+            None => Err(AuthAPIError::MissingToken),
+        },
+    }
+}
+
+// permissions are only ever granted to users, never to clients
+fn user_claims(keys: &PublicKeys, token: &str) -> Result<Claims, AuthAPIError> {
+    match decode_claims(keys, token) {
+        Ok(claims) if claims.kind == TokenKind::User => Ok(claims),
+        _ => Err(AuthAPIError::InvalidToken),
+    }
+}
+
+fn require<P: Permission>(claims: Claims) -> Result<Claims, AuthAPIError> {
+    if !claims.has_permission(P::NAME) {
+        return Err(AuthAPIError::MissingPermission);
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+    use jsonwebtoken::{encode, Header};
+
+    use crate::utils::signing_key::{JWT_ALGORITHM, KEY_RING};
+
+    use super::*;
+
+    fn token(kind: TokenKind, permissions: &[&str]) -> String {
+        let claims = Claims {
+            sub: "test@test.com".to_owned(),
+            exp: 4_102_444_800,
+            iat: 1_700_000_000,
+            jti: "test".to_owned(),
+            sid: None,
+            token_version: 0,
+            kind,
+            aud: None,
+            scope: None,
+            roles: Vec::new(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        };
+
+        let signing_key = KEY_RING.signing_key();
+        let header = Header {
+            kid: Some(signing_key.kid().to_owned()),
+            ..Header::new(JWT_ALGORITHM)
+        };
+
+        encode(&header, &claims, signing_key.encoding_key()).unwrap()
+    }
+
+    async fn verify(token: &str) -> Result<Claims, AuthAPIError> {
+        let (mut parts, _) = Request::builder()
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        // all another service would hold, no stores involved
+        let state = PublicKeys::from_jwks(&KEY_RING.jwks()).unwrap();
+
+        RequireVerifiedPermission::<ManageUsers>::from_request_parts(&mut parts, &state)
+            .await
+            .map(|permission| permission.claims)
+    }
+
+    #[tokio::test]
+    async fn test_verified_permission_accepts_granted_user_token() {
+        let claims = verify(&token(TokenKind::User, &[ManageUsers::NAME]))
+            .await
+            .unwrap();
+
+        assert_eq!(claims.sub, "test@test.com");
+    }
+
+    #[tokio::test]
+    async fn test_verified_permission_rejects_missing_permission() {
+        let result = verify(&token(TokenKind::User, &[AssignRoles::NAME])).await;
+
+        assert_eq!(result.err(), Some(AuthAPIError::MissingPermission));
+    }
+
+    #[tokio::test]
+    async fn test_verified_permission_rejects_client_token() {
+        let result = verify(&token(TokenKind::Client, &[ManageUsers::NAME])).await;
+
+        assert_eq!(result.err(), Some(AuthAPIError::InvalidToken));
+    }
+}
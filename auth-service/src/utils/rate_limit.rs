@@ -0,0 +1,138 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::{domain::AuthAPIError, store::RateLimitStoreType};
+
+// requests larger than this are rejected before they reach the handler anyway
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitKey {
+    Ip,
+    // falls back to the client ip when the body has no email
+    Email,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub name: &'static str,
+    pub key: RateLimitKey,
+    pub max_requests: u32,
+    pub window_seconds: u64,
+}
+
+pub const SIGNUP_RATE_LIMIT: RateLimit = RateLimit {
+    name: "signup",
+    key: RateLimitKey::Ip,
+    max_requests: 10,
+    window_seconds: 3600,
+};
+
+pub const LOGIN_RATE_LIMIT: RateLimit = RateLimit {
+    name: "login",
+    key: RateLimitKey::Ip,
+    max_requests: 20,
+    window_seconds: 60,
+};
+
+pub const VERIFY_2FA_RATE_LIMIT: RateLimit = RateLimit {
+    name: "verify_2fa",
+    key: RateLimitKey::Email,
+    max_requests: 10,
+    window_seconds: 60,
+};
+
+pub const MAGIC_LINK_RATE_LIMIT: RateLimit = RateLimit {
+    name: "magic_link",
+    key: RateLimitKey::Email,
+    max_requests: 5,
+    window_seconds: 900,
+};
+
+pub const PASSWORD_RESET_RATE_LIMIT: RateLimit = RateLimit {
+    name: "password_reset",
+    key: RateLimitKey::Email,
+    max_requests: 5,
+    window_seconds: 900,
+};
+
+pub const VERIFY_EMAIL_RESEND_RATE_LIMIT: RateLimit = RateLimit {
+    name: "verify_email_resend",
+    key: RateLimitKey::Email,
+    max_requests: 3,
+    window_seconds: 900,
+};
+
+// across login attempts, each attempt also has its own cooldown and resend cap
+pub const RESEND_2FA_RATE_LIMIT: RateLimit = RateLimit {
+    name: "resend_2fa",
+    key: RateLimitKey::Email,
+    max_requests: 5,
+    window_seconds: 900,
+};
+
+// the request has no email in its body, it comes from a logged in session
+pub const ENABLE_2FA_REQUEST_RATE_LIMIT: RateLimit = RateLimit {
+    name: "enable_2fa_request",
+    key: RateLimitKey::Ip,
+    max_requests: 5,
+    window_seconds: 900,
+};
+
+pub const OAUTH_TOKEN_RATE_LIMIT: RateLimit = RateLimit {
+    name: "oauth_token",
+    key: RateLimitKey::Ip,
+    max_requests: 30,
+    window_seconds: 60,
+};
+
+#[derive(Deserialize)]
+struct EmailBody {
+    email: String,
+}
+
+#[tracing::instrument(name = "Rate limit", skip_all, fields(limit = limit.name))]
+pub async fn rate_limit(
+    State((rate_limit_store, limit)): State<(RateLimitStoreType, RateLimit)>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (request, key) = match limit.key {
+        RateLimitKey::Ip => (request, format!("{}:ip:{}", limit.name, addr.ip())),
+        RateLimitKey::Email => {
+            let (parts, body) = request.into_parts();
+            let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+                Ok(bytes) => bytes,
+                Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+            };
+
+            let key = match serde_json::from_slice::<EmailBody>(&bytes) {
+                Ok(body) => format!("{}:email:{}", limit.name, body.email.trim().to_lowercase()),
+                Err(_) => format!("{}:ip:{}", limit.name, addr.ip()),
+            };
+
+            (Request::from_parts(parts, Body::from(bytes)), key)
+        }
+    };
+
+    let result = rate_limit_store
+        .write()
+        .await
+        .increment(&key, limit.window_seconds)
+        .await;
+
+    match result {
+        Ok((count, _)) if count <= limit.max_requests => next.run(request).await,
+        Ok((_, retry_after)) => AuthAPIError::TooManyRequests(retry_after).into_response(),
+        Err(e) => AuthAPIError::UnexpectedError(e.into()).into_response(),
+    }
+}
@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::eyre::{eyre, Context, Result};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+    },
+    Algorithm, DecodingKey, EncodingKey,
+};
+use lazy_static::lazy_static;
+use ring::{
+    digest::{digest, SHA256},
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use secrecy::{ExposeSecret, Secret};
+
+use super::constants::{JWT_PREVIOUS_SIGNING_KEYS, JWT_SIGNING_KEY};
+
+pub const JWT_ALGORITHM: Algorithm = Algorithm::EdDSA;
+
+lazy_static! {
+    pub static ref KEY_RING: KeyRing =
+        KeyRing::from_pems(&JWT_SIGNING_KEY, &JWT_PREVIOUS_SIGNING_KEYS).expect(
+            "JWT_SIGNING_KEY and JWT_PREVIOUS_SIGNING_KEYS must be PKCS#8 PEM encoded Ed25519 private keys"
+        );
+    // verifies tokens the same way other services do, from the published keys only
+    pub static ref PUBLIC_KEYS: PublicKeys =
+        PublicKeys::from_jwks(&KEY_RING.jwks()).expect("Failed to load the key ring's JWKS");
+}
+
+// The current key signs every new token. Previous keys are only used to verify
+// tokens issued before a rotation, and can be dropped once those have expired.
+pub struct KeyRing {
+    current: SigningKey,
+    previous: Vec<SigningKey>,
+}
+
+impl KeyRing {
+    pub fn new(current: SigningKey, previous: Vec<SigningKey>) -> Self {
+        Self { current, previous }
+    }
+
+    pub fn from_pems(current: &Secret<String>, previous: &Secret<String>) -> Result<Self> {
+        let previous = pem::parse_many(previous.expose_secret())
+            .wrap_err("Failed to parse previous signing key PEMs")?
+            .iter()
+            .map(|pem| SigningKey::from_pkcs8(pem.contents()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(SigningKey::from_pem(current)?, previous))
+    }
+
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.current
+    }
+
+    // tokens issued before key ids were introduced carry no `kid`, so they can
+    // only have been signed by the current key
+    pub fn verification_key(&self, kid: Option<&str>) -> Option<&SigningKey> {
+        match kid {
+            None => Some(&self.current),
+            Some(kid) => std::iter::once(&self.current)
+                .chain(self.previous.iter())
+                .find(|key| key.kid() == kid),
+        }
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: std::iter::once(&self.current)
+                .chain(self.previous.iter())
+                .map(SigningKey::jwk)
+                .collect(),
+        }
+    }
+}
+
+// The public half of a key ring, as published at `/.well-known/jwks.json`. It is all
+// another service needs to verify tokens, without sharing any private key.
+#[derive(Clone)]
+pub struct PublicKeys {
+    keys: Arc<Vec<(Option<String>, DecodingKey)>>,
+}
+
+impl PublicKeys {
+    pub fn from_jwks(jwks: &JwkSet) -> Result<Self> {
+        let keys = jwks
+            .keys
+            .iter()
+            .map(|jwk| {
+                let key = DecodingKey::from_jwk(jwk).wrap_err("Failed to build decoding key")?;
+                Ok((jwk.common.key_id.clone(), key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if keys.is_empty() {
+            return Err(eyre!("The JWKS has no keys"));
+        }
+
+        Ok(Self {
+            keys: Arc::new(keys),
+        })
+    }
+
+    // the current key is published first, the only one a token without a `kid` can be from
+    pub fn decoding_key(&self, kid: Option<&str>) -> Option<&DecodingKey> {
+        match kid {
+            None => self.keys.first(),
+            Some(kid) => self.keys.iter().find(|(id, _)| id.as_deref() == Some(kid)),
+        }
+        .map(|(_, key)| key)
+    }
+}
+
+pub struct SigningKey {
+    kid: String,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public_key: String,
+}
+
+impl SigningKey {
+    pub fn from_pem(pem: &Secret<String>) -> Result<Self> {
+        let pem = pem::parse(pem.expose_secret()).wrap_err("Failed to parse signing key PEM")?;
+        Self::from_pkcs8(pem.contents())
+    }
+
+    pub fn from_pkcs8(der: &[u8]) -> Result<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der)
+            .map_err(|e| eyre!("Invalid Ed25519 signing key: {}", e))?;
+
+        let public_key = URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref());
+
+        Ok(Self {
+            kid: compute_thumbprint(&public_key),
+            encoding_key: EncodingKey::from_ed_der(der),
+            decoding_key: DecodingKey::from_ed_components(&public_key)
+                .wrap_err("Failed to build decoding key")?,
+            public_key,
+        })
+    }
+
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding_key
+    }
+
+    pub fn jwk(&self) -> Jwk {
+        Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(KeyAlgorithm::EdDSA),
+                key_id: Some(self.kid.clone()),
+                ..Default::default()
+            },
+            algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: self.public_key.clone(),
+            }),
+        }
+    }
+}
+
+// RFC 7638 JWK thumbprint, so a key's id is stable without extra configuration
+fn compute_thumbprint(public_key: &str) -> String {
+    let canonical_jwk = format!(r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#, public_key);
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, canonical_jwk.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{decode, encode, Header, Validation};
+    use ring::rand::SystemRandom;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestClaims {
+        sub: String,
+        exp: usize,
+    }
+
+    fn generate_pem() -> Secret<String> {
+        let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pem = pem::Pem::new("PRIVATE KEY", document.as_ref().to_vec());
+        Secret::new(pem::encode(&pem))
+    }
+
+    #[test]
+    fn test_invalid_pem_is_rejected() {
+        let pem = Secret::new("not a key".to_owned());
+        assert!(SigningKey::from_pem(&pem).is_err());
+    }
+
+    #[test]
+    fn test_token_verifies_with_published_jwk() {
+        let signing_key = SigningKey::from_pem(&generate_pem()).unwrap();
+        let claims = TestClaims {
+            sub: "test@test.com".to_owned(),
+            exp: 4_102_444_800,
+        };
+
+        let token = encode(
+            &Header::new(JWT_ALGORITHM),
+            &claims,
+            signing_key.encoding_key(),
+        )
+        .unwrap();
+
+        let decoding_key = DecodingKey::from_jwk(&signing_key.jwk()).unwrap();
+        let decoded =
+            decode::<TestClaims>(&token, &decoding_key, &Validation::new(JWT_ALGORITHM)).unwrap();
+
+        assert_eq!(decoded.claims, claims);
+    }
+
+    #[test]
+    fn test_kid_is_stable_for_the_same_key() {
+        let pem = generate_pem();
+        let first = SigningKey::from_pem(&pem).unwrap();
+        let second = SigningKey::from_pem(&pem).unwrap();
+        let other = SigningKey::from_pem(&generate_pem()).unwrap();
+
+        assert_eq!(first.kid(), second.kid());
+        assert_ne!(first.kid(), other.kid());
+        assert_eq!(first.jwk().common.key_id.as_deref(), Some(first.kid()));
+    }
+
+    #[test]
+    fn test_key_ring_finds_previous_key_by_kid() {
+        let previous_pem = generate_pem();
+        let previous = SigningKey::from_pem(&previous_pem).unwrap();
+        let key_ring = KeyRing::from_pems(&generate_pem(), &previous_pem).unwrap();
+
+        let claims = TestClaims {
+            sub: "test@test.com".to_owned(),
+            exp: 4_102_444_800,
+        };
+        let header = Header {
+            kid: Some(previous.kid().to_owned()),
+            ..Header::new(JWT_ALGORITHM)
+        };
+        let token = encode(&header, &claims, previous.encoding_key()).unwrap();
+
+        let key = key_ring
+            .verification_key(Some(previous.kid()))
+            .expect("Previous key not found");
+        let decoded =
+            decode::<TestClaims>(&token, key.decoding_key(), &Validation::new(JWT_ALGORITHM))
+                .unwrap();
+
+        assert_eq!(decoded.claims, claims);
+        assert_ne!(key_ring.signing_key().kid(), previous.kid());
+        assert_eq!(key_ring.jwks().keys.len(), 2);
+    }
+
+    #[test]
+    fn test_key_ring_rejects_unknown_kid() {
+        let key_ring = KeyRing::from_pems(&generate_pem(), &Secret::new(String::new())).unwrap();
+        let other = SigningKey::from_pem(&generate_pem()).unwrap();
+
+        assert!(key_ring.verification_key(Some(other.kid())).is_none());
+        assert_eq!(key_ring.jwks().keys.len(), 1);
+    }
+
+    #[test]
+    fn test_public_keys_verify_tokens_from_every_published_key() {
+        let current_pem = generate_pem();
+        let previous_pem = generate_pem();
+        let current = SigningKey::from_pem(&current_pem).unwrap();
+        let previous = SigningKey::from_pem(&previous_pem).unwrap();
+        let key_ring = KeyRing::from_pems(&current_pem, &previous_pem).unwrap();
+        let public_keys = PublicKeys::from_jwks(&key_ring.jwks()).unwrap();
+
+        let claims = TestClaims {
+            sub: "test@test.com".to_owned(),
+            exp: 4_102_444_800,
+        };
+
+        for (signing_key, kid) in [(&current, None), (&previous, Some(previous.kid()))] {
+            let header = Header {
+                kid: kid.map(str::to_owned),
+                ..Header::new(JWT_ALGORITHM)
+            };
+            let token = encode(&header, &claims, signing_key.encoding_key()).unwrap();
+
+            let key = public_keys.decoding_key(kid).expect("Key not found");
+            let decoded =
+                decode::<TestClaims>(&token, key, &Validation::new(JWT_ALGORITHM)).unwrap();
+            assert_eq!(decoded.claims, claims);
+        }
+
+        let other = SigningKey::from_pem(&generate_pem()).unwrap();
+        assert!(public_keys.decoding_key(Some(other.kid())).is_none());
+    }
+
+    #[test]
+    fn test_token_from_other_key_is_rejected() {
+        let signing_key = SigningKey::from_pem(&generate_pem()).unwrap();
+        let other_key = SigningKey::from_pem(&generate_pem()).unwrap();
+        let claims = TestClaims {
+            sub: "test@test.com".to_owned(),
+            exp: 4_102_444_800,
+        };
+
+        let token = encode(
+            &Header::new(JWT_ALGORITHM),
+            &claims,
+            other_key.encoding_key(),
+        )
+        .unwrap();
+
+        let result = decode::<TestClaims>(
+            &token,
+            signing_key.decoding_key(),
+            &Validation::new(JWT_ALGORITHM),
+        );
+
+        assert!(result.is_err());
+    }
+}
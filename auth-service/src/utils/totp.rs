@@ -0,0 +1,211 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{Context, Result};
+use data_encoding::BASE32_NOPAD;
+use ring::hmac;
+use secrecy::ExposeSecret;
+use url::Url;
+
+use crate::{
+    domain::{Email, RecoveryCode, TotpSecret, TotpSecretStoreError, TwoFACode},
+    store::{RecoveryCodeStoreType, TotpSecretStoreType},
+};
+
+use super::constants::{RECOVERY_CODE_COUNT, TOTP_ISSUER};
+
+pub const TOTP_STEP_SECONDS: u64 = 30;
+pub const TOTP_DIGITS: u32 = 6;
+// accept codes from one step either side of the current one to allow for clock drift
+pub const TOTP_SKEW_STEPS: u64 = 1;
+
+pub fn current_step() -> Result<u64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .wrap_err("System time is before the unix epoch")?;
+
+    Ok(now.as_secs() / TOTP_STEP_SECONDS)
+}
+
+// RFC 6238 with the RFC 4226 defaults authenticator apps assume: HMAC-SHA1 and 6 digits
+pub fn generate_code(secret: &TotpSecret, step: u64) -> Result<String> {
+    let key = BASE32_NOPAD
+        .decode(secret.as_ref().expose_secret().as_bytes())
+        .wrap_err("Failed to decode TOTP secret")?;
+
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, &key);
+    let digest = hmac::sign(&key, &step.to_be_bytes());
+    let digest = digest.as_ref();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    Ok(format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    ))
+}
+
+// returns the step the code was generated for, so callers can reject replays
+pub fn verify_code(secret: &TotpSecret, code: &TwoFACode, step: u64) -> Result<Option<u64>> {
+    let mut matched = None;
+
+    for candidate in step.saturating_sub(TOTP_SKEW_STEPS)..=step + TOTP_SKEW_STEPS {
+        let expected = generate_code(secret, candidate)?;
+
+        // check every step so timing doesn't reveal which one matched
+        if ring::constant_time::verify_slices_are_equal(
+            expected.as_bytes(),
+            code.as_ref().as_bytes(),
+        )
+        .is_ok()
+        {
+            matched = Some(candidate);
+        }
+    }
+
+    Ok(matched)
+}
+
+// checks the code and marks its step as used, returning false for wrong or replayed codes
+pub async fn consume_code(
+    totp_secrets: &TotpSecretStoreType,
+    email: &Email,
+    secret: &TotpSecret,
+    code: &TwoFACode,
+) -> Result<bool> {
+    let step = match verify_code(secret, code, current_step()?)? {
+        Some(step) => step,
+        None => return Ok(false),
+    };
+
+    match totp_secrets
+        .write()
+        .await
+        .record_used_step(email, step)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(TotpSecretStoreError::StepAlreadyUsed) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// an enrolled but unconfirmed secret doesn't count
+pub async fn totp_enabled(totp_secrets: &TotpSecretStoreType, email: &Email) -> Result<bool> {
+    match totp_secrets.read().await.get_secret(email).await {
+        Ok(record) => Ok(record.confirmed),
+        Err(TotpSecretStoreError::SecretNotFound) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn otpauth_uri(secret: &TotpSecret, email: &Email) -> Result<String> {
+    let mut uri = Url::parse("otpauth://totp/").wrap_err("Failed to build otpauth uri")?;
+
+    uri.set_path(&format!("{}:{}", TOTP_ISSUER, email.as_ref()));
+    uri.query_pairs_mut()
+        .append_pair("secret", secret.as_ref().expose_secret())
+        .append_pair("issuer", TOTP_ISSUER)
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", &TOTP_DIGITS.to_string())
+        .append_pair("period", &TOTP_STEP_SECONDS.to_string());
+
+    Ok(uri.to_string())
+}
+
+// replaces any existing codes, the plaintext is only ever returned here
+#[tracing::instrument(name = "generate_recovery_codes", skip_all)]
+pub async fn generate_recovery_codes(
+    recovery_codes: &RecoveryCodeStoreType,
+    email: &Email,
+) -> Result<Vec<String>> {
+    let codes: Vec<RecoveryCode> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| RecoveryCode::default())
+        .collect();
+
+    recovery_codes
+        .write()
+        .await
+        .replace_codes(email, &codes)
+        .await
+        .wrap_err("Failed to store recovery codes")?;
+
+    Ok(codes
+        .iter()
+        .map(|code| code.as_ref().expose_secret().to_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    // the RFC 6238 SHA1 test key "12345678901234567890"
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret::parse(Secret::new(BASE32_NOPAD.encode(b"12345678901234567890"))).unwrap()
+    }
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        // RFC 6238 appendix B, truncated to 6 digits
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ];
+
+        for (time, code) in vectors {
+            assert_eq!(
+                generate_code(&rfc_secret(), time / TOTP_STEP_SECONDS).unwrap(),
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_code_accepts_adjacent_steps() {
+        let secret = TotpSecret::default();
+        let step = 1_000_000;
+
+        for offset in [-1i64, 0, 1] {
+            let code_step = (step as i64 + offset) as u64;
+            let code = TwoFACode::parse(generate_code(&secret, code_step).unwrap()).unwrap();
+
+            assert_eq!(verify_code(&secret, &code, step).unwrap(), Some(code_step));
+        }
+    }
+
+    #[test]
+    fn test_verify_code_rejects_distant_steps() {
+        let secret = TotpSecret::default();
+        let step = 1_000_000;
+
+        let code = TwoFACode::parse(generate_code(&secret, step + 2).unwrap()).unwrap();
+
+        assert_eq!(verify_code(&secret, &code, step).unwrap(), None);
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let secret = rfc_secret();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+
+        let uri = Url::parse(&otpauth_uri(&secret, &email).unwrap()).unwrap();
+
+        assert_eq!(uri.scheme(), "otpauth");
+        assert_eq!(uri.host_str(), Some("totp"));
+        assert!(uri.query_pairs().any(
+            |(key, value)| key == "secret" && value == secret.as_ref().expose_secret().as_str()
+        ));
+    }
+}
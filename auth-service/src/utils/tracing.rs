@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use axum::{body::Body, extract::Request, response::Response};
+use axum::{body::Body, extract::Request, http::HeaderValue, middleware::Next, response::Response};
 use color_eyre::eyre::Result;
 use tracing::{Level, Span};
 use tracing_error::ErrorLayer;
@@ -19,8 +19,32 @@ pub fn init_tracing() -> Result<()> {
     Ok(())
 }
 
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// shared by the request's span, its audit events and the `x-request-id` response header
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+// must run before the trace layer, so the span picks up the id
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId(uuid::Uuid::new_v4().to_string());
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = next.run(request).await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
 pub fn make_span_with_request_id(request: &Request<Body>) -> Span {
-    let request_id = uuid::Uuid::new_v4();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     tracing::span!(
         Level::INFO,
         "[REQUEST]",
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::{Email, WebhookDelivery, WebhookEventKind},
+    store::AppState,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookPayload<'a> {
+    // the same for every subscriber, so receivers can drop duplicates
+    id: Uuid,
+    #[serde(rename = "type")]
+    kind: &'a str,
+    occurred_at: DateTime<Utc>,
+    data: WebhookData<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookData<'a> {
+    email: &'a str,
+}
+
+// queues the event for every subscription to it; like the audit log, a failure is logged
+// rather than failing the request that caused it
+#[tracing::instrument(name = "Publish webhook event", skip_all)]
+pub async fn publish_event(state: &AppState, kind: WebhookEventKind, email: &Email) {
+    let payload = WebhookPayload {
+        id: Uuid::new_v4(),
+        kind: kind.as_ref(),
+        occurred_at: Utc::now(),
+        data: WebhookData {
+            email: email.as_ref(),
+        },
+    };
+
+    let payload = match serde_json::to_string(&payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to serialize webhook payload");
+            return;
+        }
+    };
+
+    let mut webhook_store = state.webhook_store.write().await;
+
+    let subscriptions = match webhook_store.list_subscriptions().await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to list webhook subscriptions");
+            return;
+        }
+    };
+
+    let mut queued = false;
+    for subscription in subscriptions.iter().filter(|s| s.subscribes_to(kind)) {
+        let delivery = WebhookDelivery::new(subscription.id, kind, payload.clone());
+        match webhook_store.add_delivery(delivery).await {
+            Ok(()) => queued = true,
+            Err(e) => tracing::error!(error = ?e, "Failed to queue webhook delivery"),
+        }
+    }
+
+    if queued {
+        state.webhook_wakeup.notify_one();
+    }
+}
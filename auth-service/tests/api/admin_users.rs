@@ -0,0 +1,233 @@
+use auth_service::{
+    domain::ErrorResponse,
+    routes::{AdminUserResponse, ListUsersResponse},
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+async fn login(app: &TestApp, email: &str, password: &str) -> reqwest::Response {
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": password,
+    }))
+    .await
+}
+
+async fn error_message(response: reqwest::Response) -> String {
+    response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialize response body to ErrorResponse")
+        .error
+}
+
+#[tokio::test]
+async fn should_return_403_without_permission() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.get_admin_users("").await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.post_disable_user(&email).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_list_users_by_page_and_search() {
+    let mut app = TestApp::new().await;
+
+    let first = signup(&app).await;
+    signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.get_admin_users("page=1&perPage=2").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<ListUsersResponse>()
+        .await
+        .expect("Could not deserialize response body to ListUsersResponse");
+    assert_eq!(body.users.len(), 2);
+    assert_eq!(body.total, 3);
+
+    let body = app
+        .get_admin_users("page=2&perPage=2")
+        .await
+        .json::<ListUsersResponse>()
+        .await
+        .expect("Could not deserialize response body to ListUsersResponse");
+    assert_eq!(body.users.len(), 1);
+    assert_eq!(body.page, 2);
+
+    let search = &first[..8];
+    let body = app
+        .get_admin_users(&format!("email={}", search.to_uppercase()))
+        .await
+        .json::<ListUsersResponse>()
+        .await
+        .expect("Could not deserialize response body to ListUsersResponse");
+    assert_eq!(body.total, 1);
+    assert_eq!(body.users[0].email, first);
+    assert_eq!(body.users[0].status, "active");
+
+    let response = app.get_admin_users("perPage=0").await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(error_message(response).await, "Invalid query parameters");
+
+    let response = app.get_admin_user("not-an-email").await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(error_message(response).await, "Invalid email address");
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_inspect_user() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.get_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<AdminUserResponse>()
+        .await
+        .expect("Could not deserialize response body to AdminUserResponse");
+    assert_eq!(body.email, email);
+    assert_eq!(body.status, "active");
+    assert!(!body.requires_2fa);
+
+    let response = app.get_admin_user(&get_random_email()).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_disable_and_enable_user() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.post_disable_user(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app.post_disable_user(&get_random_email()).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(error_message(response).await, "Account is disabled");
+
+    let response = app.post_enable_user(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_force_password_reset() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.post_force_password_reset(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(
+        error_message(response).await,
+        "Password must be reset before logging in"
+    );
+
+    let token = app.get_emailed_token(&email, "password_reset_token").await;
+    let response = app
+        .post_password_reset_confirm(&serde_json::json!({
+            "token": token,
+            "password": "new-password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = login(&app, &email, "new-password123").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_toggle_2fa() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app
+        .put_user_2fa(&email, &serde_json::json!({ "requires2FA": true }))
+        .await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let response = app
+        .put_user_2fa(&email, &serde_json::json!({ "requires2FA": false }))
+        .await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_delete_user() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.delete_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app.get_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await
+}
@@ -0,0 +1,246 @@
+use auth_service::{
+    domain::{AccountStatus, Email, ErrorResponse},
+    routes::{ApiKeyResponse, CreateApiKeyResponse, CredentialKind, VerifyTokenResponse},
+};
+use secrecy::Secret;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_and_login(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+async fn create_key(app: &TestApp) -> CreateApiKeyResponse {
+    let response = app
+        .post_api_key(&serde_json::json!({
+            "name": "CI deploys",
+            "scopes": ["deploy", "read"],
+            "expiresInDays": 30
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    response
+        .json::<CreateApiKeyResponse>()
+        .await
+        .expect("Could not deserialize response body to CreateApiKeyResponse")
+}
+
+#[tokio::test]
+async fn should_create_and_verify_api_key() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+    let created = create_key(&app).await;
+
+    assert!(created.key.starts_with("lgr_"));
+    assert_eq!(created.api_key.name, "CI deploys");
+    assert!(created.api_key.expires_at > created.api_key.created_at);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": created.key }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<VerifyTokenResponse>()
+        .await
+        .expect("Could not deserialize response body to VerifyTokenResponse");
+    assert_eq!(body.kind, CredentialKind::ApiKey);
+    assert_eq!(body.sub, email);
+    assert_eq!(body.scope.as_deref(), Some("deploy read"));
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_list_keys_without_secrets() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+    let first = create_key(&app).await;
+    let second = create_key(&app).await;
+
+    let response = app.get_api_keys().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response.text().await.unwrap();
+    assert!(!body.contains(&first.key));
+    assert!(!body.contains(&second.key));
+
+    let keys: Vec<ApiKeyResponse> = serde_json::from_str(&body).unwrap();
+    let ids: Vec<_> = keys.iter().map(|key| key.id).collect();
+    assert_eq!(ids, vec![first.api_key.id, second.api_key.id]);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_revoke_api_key() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+    let created = create_key(&app).await;
+    let id = created.api_key.id.to_string();
+
+    let response = app.delete_api_key(&id).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": created.key }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.delete_api_key(&id).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_revoke_other_users_keys() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+    let created = create_key(&app).await;
+
+    // the cookie jar is shared, so logging in again switches the session
+    signup_and_login(&app).await;
+
+    let response = app.delete_api_key(&created.api_key.id.to_string()).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.get_api_keys().await;
+    let keys: Vec<ApiKeyResponse> = response.json().await.unwrap();
+    assert!(keys.is_empty());
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_invalid_api_key_requests() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_api_key(&serde_json::json!({ "name": "ci", "expiresInDays": 30 }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    signup_and_login(&app).await;
+
+    let invalid_bodies = [
+        serde_json::json!({ "name": "", "expiresInDays": 30 }),
+        serde_json::json!({ "name": "ci", "expiresInDays": 0 }),
+        serde_json::json!({ "name": "ci", "expiresInDays": 366 }),
+        serde_json::json!({ "name": "ci", "scopes": ["two words"], "expiresInDays": 30 }),
+    ];
+
+    for body in invalid_bodies {
+        let response = app.post_api_key(&body).await;
+        assert_eq!(response.status().as_u16(), 400, "body: {}", body);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            "Invalid API key name, scopes or expiry"
+        );
+    }
+
+    let response = app.delete_api_key("not-a-uuid").await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Invalid API key id"
+    );
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": format!("lgr_{}", "a".repeat(64)) }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+async fn verify_key(app: &TestApp, key: &str) -> u16 {
+    app.post_verify_token(&serde_json::json!({ "token": key }))
+        .await
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn should_reject_api_key_of_disabled_user() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+    let created = create_key(&app).await;
+    assert_eq!(verify_key(&app, &created.key).await, 200);
+
+    // the owner's status is checked on every use, whatever changed it
+    let parsed = Email::parse(Secret::new(email.clone())).unwrap();
+    app.user_store
+        .write()
+        .await
+        .update_account_status(&parsed, AccountStatus::Disabled)
+        .await
+        .expect("Failed to disable user");
+    assert_eq!(verify_key(&app, &created.key).await, 401);
+
+    app.user_store
+        .write()
+        .await
+        .update_account_status(&parsed, AccountStatus::Active)
+        .await
+        .expect("Failed to enable user");
+    assert_eq!(verify_key(&app, &created.key).await, 200);
+
+    // disabling through the admin API revokes the keys, they stay revoked once re-enabled
+    app.login_as_admin().await;
+
+    let response = app.post_disable_user(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+    assert_eq!(verify_key(&app, &created.key).await, 401);
+
+    let response = app.post_enable_user(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+    assert_eq!(verify_key(&app, &created.key).await, 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_revoke_api_keys_when_logging_out_everywhere() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+    let created = create_key(&app).await;
+
+    let response = app.delete_sessions().await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    assert_eq!(verify_key(&app, &created.key).await, 401);
+
+    app.clean_up().await
+}
@@ -0,0 +1,247 @@
+use auth_service::{
+    domain::ErrorResponse, routes::ListAuditEventsResponse, utils::tracing::REQUEST_ID_HEADER,
+};
+use reqwest::header::USER_AGENT;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+async fn list_events(app: &TestApp, query: &str) -> ListAuditEventsResponse {
+    let response = app.get_audit_events(query).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    response
+        .json::<ListAuditEventsResponse>()
+        .await
+        .expect("Could not deserialize response body to ListAuditEventsResponse")
+}
+
+#[tokio::test]
+async fn should_record_signups_and_logins() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "wrong-password",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .http_client
+        .post(format!("{}/login", &app.address))
+        .header(USER_AGENT, "audit-test")
+        .json(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+    let request_id = response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .expect("No request id header")
+        .to_owned();
+
+    app.login_as_admin().await;
+
+    let body = list_events(&app, &format!("actor={}", email)).await;
+    assert_eq!(body.total, 3);
+
+    let kinds: Vec<(&str, &str)> = body
+        .events
+        .iter()
+        .map(|event| (event.kind.as_str(), event.outcome.as_str()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("login", "success"),
+            ("login", "failure"),
+            ("signup", "success")
+        ]
+    );
+
+    let login = &body.events[0];
+    assert_eq!(login.user_agent.as_deref(), Some("audit-test"));
+    assert_eq!(login.ip.as_deref(), Some("127.0.0.1"));
+    assert_eq!(login.request_id.as_deref(), Some(request_id.as_str()));
+    assert_eq!(
+        body.events[1].reason.as_deref(),
+        Some("Incorrect credentials")
+    );
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_record_magic_link_logins() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let response = app
+        .post_request_magic_link(&serde_json::json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let token = app.get_emailed_token(&email, "magic_link_token").await;
+
+    let response = app
+        .post_magic_link_login(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_magic_link_login(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login_as_admin().await;
+
+    let body = list_events(&app, &format!("type=login&actor={}", email)).await;
+    assert_eq!(body.total, 1);
+    assert_eq!(body.events[0].outcome, "success");
+
+    let body = list_events(&app, "type=login&outcome=failure").await;
+    assert_eq!(body.total, 1);
+    assert_eq!(body.events[0].actor, None);
+    assert_eq!(body.events[0].reason.as_deref(), Some("Invalid token"));
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_record_logouts_and_token_rejections() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_logout().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": "not-a-token" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login_as_admin().await;
+
+    let body = list_events(&app, "type=logout").await;
+    assert_eq!(body.total, 1);
+    assert_eq!(body.events[0].actor.as_deref(), Some(email.as_str()));
+    assert_eq!(body.events[0].outcome, "success");
+
+    let body = list_events(&app, "type=token_rejected").await;
+    assert_eq!(body.total, 1);
+    assert_eq!(body.events[0].outcome, "failure");
+    assert!(body.events[0].reason.is_some());
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_filter_and_paginate_events() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    for _ in 0..3 {
+        let response = app
+            .post_login(&serde_json::json!({
+                "email": email,
+                "password": "wrong-password",
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.login_as_admin().await;
+
+    let body = list_events(&app, "type=login&outcome=failure&perPage=2").await;
+    assert_eq!(body.total, 3);
+    assert_eq!(body.events.len(), 2);
+    assert_eq!(body.per_page, 2);
+
+    let body = list_events(&app, "type=login&outcome=failure&perPage=2&page=2").await;
+    assert_eq!(body.events.len(), 1);
+
+    let body = list_events(&app, "type=login&since=2999-01-01T00:00:00Z").await;
+    assert_eq!(body.total, 0);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_invalid_queries() {
+    let mut app = TestApp::new().await;
+
+    app.login_as_admin().await;
+
+    let cases = [
+        ("type=unknown", "Unknown event type"),
+        ("outcome=maybe", "Unknown outcome"),
+        ("perPage=1000", "Invalid query parameters"),
+    ];
+
+    for (query, message) in cases {
+        let response = app.get_audit_events(query).await;
+        assert_eq!(response.status().as_u16(), 400, "query: {}", query);
+
+        let body = response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse");
+        assert_eq!(body.error, message);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_403_without_permission() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.get_audit_events("").await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await
+}
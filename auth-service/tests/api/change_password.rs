@@ -0,0 +1,186 @@
+use auth_service::utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME};
+use reqwest::Url;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_and_login(app: &TestApp) -> (String, reqwest::Response) {
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    (random_email, response)
+}
+
+fn get_cookie(response: &reqwest::Response, name: &str) -> String {
+    response
+        .cookies()
+        .find(|cookie| cookie.name() == name)
+        .unwrap_or_else(|| panic!("No {} cookie found", name))
+        .value()
+        .to_owned()
+}
+
+fn set_cookie(app: &TestApp, name: &str, value: &str) {
+    app.cookie_jar.add_cookie_str(
+        &format!("{}={}; HttpOnly; SameSite=Lax; Path=/", name, value),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_change_password(&serde_json::json!({
+            "currentPassword": "password123",
+            "newPassword": "newpassword123",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    set_cookie(&app, JWT_COOKIE_NAME, "invalid");
+
+    let response = app
+        .post_change_password(&serde_json::json!({
+            "currentPassword": "password123",
+            "newPassword": "newpassword123",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+
+    let response = app
+        .post_change_password(&serde_json::json!({ "newPassword": "newpassword123" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 422);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_if_new_password_invalid() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+
+    let response = app
+        .post_change_password(&serde_json::json!({
+            "currentPassword": "password123",
+            "newPassword": "short",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_current_password_incorrect() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+
+    let response = app
+        .post_change_password(&serde_json::json!({
+            "currentPassword": "wrongpassword",
+            "newPassword": "newpassword123",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_change_password_and_revoke_other_sessions() {
+    let mut app = TestApp::new().await;
+
+    let (email, response) = signup_and_login(&app).await;
+    let other_refresh_token = get_cookie(&response, REFRESH_TOKEN_COOKIE_NAME);
+
+    // log in again so the cookie jar holds a second session of the same user
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_change_password(&serde_json::json!({
+            "currentPassword": "password123",
+            "newPassword": "newpassword123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // the session that made the change is reissued and keeps working
+    let new_jwt = get_cookie(&response, JWT_COOKIE_NAME);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": new_jwt }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    set_cookie(&app, REFRESH_TOKEN_COOKIE_NAME, &other_refresh_token);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "newpassword123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
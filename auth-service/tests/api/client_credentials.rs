@@ -0,0 +1,157 @@
+use auth_service::{
+    domain::{ClientSecret, MachineClient, OAuthErrorResponse},
+    routes::{CredentialKind, TokenResponse, VerifyTokenResponse},
+};
+use secrecy::ExposeSecret;
+
+use super::helpers::TestApp;
+
+const CLIENT_ID: &str = "billing-service";
+
+async fn register_client(app: &TestApp) -> ClientSecret {
+    let secret = ClientSecret::default();
+    let client = MachineClient::parse(
+        CLIENT_ID.to_owned(),
+        "Billing service".to_owned(),
+        secret.hash(),
+        vec!["users:read".to_owned(), "users:write".to_owned()],
+    )
+    .unwrap();
+
+    app.machine_client_store
+        .write()
+        .await
+        .add_client(client)
+        .await
+        .expect("Failed to register client");
+
+    secret
+}
+
+async fn oauth_error(response: reqwest::Response) -> String {
+    response
+        .json::<OAuthErrorResponse>()
+        .await
+        .expect("Could not deserialize response body to OAuthErrorResponse")
+        .error
+}
+
+#[tokio::test]
+async fn should_issue_client_token() {
+    let mut app = TestApp::new().await;
+
+    let secret = register_client(&app).await;
+
+    let response = app
+        .post_token(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", CLIENT_ID),
+            ("client_secret", secret.as_ref().expose_secret()),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert_eq!(body.token_type, "Bearer");
+    assert_eq!(body.scope.as_deref(), Some("users:read users:write"));
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": body.access_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let verified = response
+        .json::<VerifyTokenResponse>()
+        .await
+        .expect("Could not deserialize response body to VerifyTokenResponse");
+    assert_eq!(verified.kind, CredentialKind::Client);
+    assert_eq!(verified.sub, CLIENT_ID);
+
+    // a client token doesn't act on behalf of a user
+    let response = app.get_userinfo(&body.access_token).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_accept_basic_auth_and_narrow_scope() {
+    let mut app = TestApp::new().await;
+
+    let secret = register_client(&app).await;
+
+    let response = app
+        .post_token_with_basic_auth(
+            CLIENT_ID,
+            secret.as_ref().expose_secret(),
+            &[
+                ("grant_type", "client_credentials"),
+                ("scope", "users:read"),
+            ],
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert_eq!(body.scope.as_deref(), Some("users:read"));
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_invalid_client_credentials() {
+    let mut app = TestApp::new().await;
+
+    let secret = register_client(&app).await;
+    let other_secret = ClientSecret::default();
+
+    let cases = [
+        (CLIENT_ID, other_secret.as_ref().expose_secret().as_str()),
+        ("unknown-service", secret.as_ref().expose_secret().as_str()),
+    ];
+
+    for (client_id, client_secret) in cases {
+        let response = app
+            .post_token(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+            ])
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+        assert_eq!(oauth_error(response).await, "invalid_client");
+    }
+
+    let response = app
+        .post_token(&[("grant_type", "client_credentials")])
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_scope_not_granted_to_client() {
+    let mut app = TestApp::new().await;
+
+    let secret = register_client(&app).await;
+
+    let response = app
+        .post_token(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", CLIENT_ID),
+            ("client_secret", secret.as_ref().expose_secret()),
+            ("scope", "users:read admin"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "invalid_scope");
+
+    app.clean_up().await
+}
@@ -0,0 +1,189 @@
+use auth_service::{
+    domain::Email,
+    routes::TwoFactorAuthResponse,
+    utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+};
+use reqwest::Url;
+use secrecy::Secret;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_and_login(app: &TestApp, requires_2fa: bool) -> String {
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": requires_2fa
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+
+    if requires_2fa {
+        assert_eq!(response.status().as_u16(), 206);
+
+        let login_attempt_id = response
+            .json::<TwoFactorAuthResponse>()
+            .await
+            .expect("Could not deserialize response body to TwoFactorAuthResponse")
+            .login_attempt_id;
+
+        let (_, code) = app
+            .two_fa_code_store
+            .read()
+            .await
+            .get_code(&Email::parse(Secret::new(random_email.clone())).unwrap())
+            .await
+            .expect("No 2FA code stored");
+
+        let response = app
+            .post_verify_2fa(&serde_json::json!({
+                "email": random_email,
+                "loginAttemptId": login_attempt_id,
+                "2FACode": code.as_ref(),
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+    } else {
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    random_email
+}
+
+fn get_cookie(app: &TestApp, name: &str) -> String {
+    let url = Url::parse("http://127.0.0.1").expect("Failed to parse URL");
+    let cookies = reqwest::cookie::CookieStore::cookies(app.cookie_jar.as_ref(), &url)
+        .expect("No cookies stored");
+
+    cookies
+        .to_str()
+        .expect("Invalid cookie header")
+        .split("; ")
+        .find_map(|cookie| cookie.strip_prefix(&format!("{}=", name)))
+        .unwrap_or_else(|| panic!("No {} cookie found", name))
+        .to_owned()
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .delete_account(&serde_json::json!({ "password": "password123" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_incorrect_password() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app, false).await;
+
+    let response = app
+        .delete_account(&serde_json::json!({ "password": "wrongpassword" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // the account is left untouched
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_delete_account_and_revoke_credentials() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app, true).await;
+    let jwt = get_cookie(&app, JWT_COOKIE_NAME);
+    let refresh_token = get_cookie(&app, REFRESH_TOKEN_COOKIE_NAME);
+
+    // leave a 2FA code pending
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let response = app
+        .delete_account(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let parsed_email = Email::parse(Secret::new(email.clone())).unwrap();
+    assert!(app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&parsed_email)
+        .await
+        .is_err());
+
+    assert!(app
+        .banned_tokens_store
+        .read()
+        .await
+        .verify_token_exists(&jwt)
+        .await
+        .expect("Failed to check banned token"));
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": jwt }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}={}; HttpOnly; SameSite=Lax; Path=/",
+            REFRESH_TOKEN_COOKIE_NAME, refresh_token
+        ),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // the address is free to sign up again
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    app.clean_up().await
+}
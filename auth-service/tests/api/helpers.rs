@@ -1,9 +1,30 @@
 use std::{str::FromStr, sync::Arc};
 
 use auth_service::{
-    domain::Email, get_postgres_pool, get_redis_client, services::{MockEmailClient, PostgresUserStore, PostmarkEmailClient, RedisBannedTokenStore, RedisTwoFACodeStore}, store::{AppState, BannedTokenStoreType, EmailClientType, TwoFACodeStoreType, UserStoreType}, utils::constants::{test, DATABASE_URL, DEFAULT_REDIS_HOSTNAME}, Application
+    domain::Email,
+    get_postgres_pool, get_redis_client,
+    services::{
+        webhook_http_client, HashmapRateLimitStore, PostgresApiKeyStore, PostgresAuditLogStore,
+        PostgresMachineClientStore, PostgresOAuthClientStore, PostgresRecoveryCodeStore,
+        PostgresRoleStore, PostgresTotpSecretStore, PostgresUserStore, PostgresWebhookStore,
+        PostmarkEmailClient, RedisAuthorizationCodeStore, RedisBannedTokenStore,
+        RedisEmailVerificationTokenStore, RedisLoginLockoutStore, RedisMagicLinkTokenStore,
+        RedisPasswordResetTokenStore, RedisRefreshTokenStore, RedisSessionStore,
+        RedisTwoFACodeStore, WebhookDispatcher,
+    },
+    store::{
+        ApiKeyStoreType, AppState, AuditLogStoreType, AuthorizationCodeStoreType,
+        BannedTokenStoreType, EmailVerificationTokenStoreType, LoginLockoutStoreType,
+        MachineClientStoreType, MagicLinkTokenStoreType, OAuthClientStoreType,
+        PasswordResetTokenStoreType, RateLimitStoreType, RecoveryCodeStoreType,
+        RefreshTokenStoreType, RoleStoreType, SessionStoreType, TotpSecretStoreType,
+        TwoFACodeStoreType, UserStoreType, WebhookStoreType,
+    },
+    utils::constants::{test, DATABASE_URL, DEFAULT_REDIS_HOSTNAME, JWT_COOKIE_NAME},
+    Application,
 };
-use reqwest::{cookie::Jar, Client};
+use redis::Commands;
+use reqwest::{cookie::Jar, Client, Url};
 use secrecy::{ExposeSecret, Secret};
 use sqlx::{
     postgres::{PgConnectOptions, PgPoolOptions},
@@ -11,16 +32,24 @@ use sqlx::{
 };
 use tokio::sync::RwLock;
 use uuid::Uuid;
-use wiremock::MockServer;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
 
 pub struct TestApp {
     pub address: String,
     pub cookie_jar: Arc<Jar>,
     pub http_client: reqwest::Client,
+    pub user_store: UserStoreType,
     pub banned_tokens_store: BannedTokenStoreType,
     pub two_fa_code_store: TwoFACodeStoreType,
+    pub refresh_token_store: RefreshTokenStoreType,
+    pub oauth_client_store: OAuthClientStoreType,
+    pub machine_client_store: MachineClientStoreType,
+    pub role_store: RoleStoreType,
+    pub session_store: SessionStoreType,
     pub email_server: MockServer,
-    pub email_client: EmailClientType,
     pub db_name: String,
     pub clean_up_called: bool,
 }
@@ -31,7 +60,8 @@ impl TestApp {
         let clean_up_called = false;
 
         let pg_pool = configure_postgresql(&db_name).await;
-        let user_store: UserStoreType = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool)));
+        let user_store: UserStoreType =
+            Arc::new(RwLock::new(PostgresUserStore::new(pg_pool.clone())));
 
         let redis_connection = Arc::new(RwLock::new(configure_redis()));
 
@@ -39,21 +69,116 @@ impl TestApp {
             RedisBannedTokenStore::new(redis_connection.clone()),
         ));
 
-        let two_fa_code_store: TwoFACodeStoreType =
-            Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_connection)));
+        let two_fa_code_store: TwoFACodeStoreType = Arc::new(RwLock::new(
+            RedisTwoFACodeStore::new(redis_connection.clone()),
+        ));
+
+        let refresh_token_store: RefreshTokenStoreType = Arc::new(RwLock::new(
+            RedisRefreshTokenStore::new(redis_connection.clone()),
+        ));
+
+        let password_reset_token_store: PasswordResetTokenStoreType = Arc::new(RwLock::new(
+            RedisPasswordResetTokenStore::new(redis_connection.clone()),
+        ));
+
+        let email_verification_token_store: EmailVerificationTokenStoreType =
+            Arc::new(RwLock::new(RedisEmailVerificationTokenStore::new(
+                redis_connection.clone(),
+            )));
+
+        let login_lockout_store: LoginLockoutStoreType = Arc::new(RwLock::new(
+            RedisLoginLockoutStore::new(redis_connection.clone()),
+        ));
+
+        let magic_link_token_store: MagicLinkTokenStoreType = Arc::new(RwLock::new(
+            RedisMagicLinkTokenStore::new(redis_connection.clone()),
+        ));
+
+        let session_store: SessionStoreType = Arc::new(RwLock::new(RedisSessionStore::new(
+            redis_connection.clone(),
+        )));
+
+        let authorization_code_store: AuthorizationCodeStoreType = Arc::new(RwLock::new(
+            RedisAuthorizationCodeStore::new(redis_connection),
+        ));
+
+        // in memory so parallel tests, which all connect from 127.0.0.1, don't
+        // share limits
+        let rate_limit_store: RateLimitStoreType =
+            Arc::new(RwLock::new(HashmapRateLimitStore::default()));
+
+        let totp_secret_store: TotpSecretStoreType =
+            Arc::new(RwLock::new(PostgresTotpSecretStore::new(pg_pool.clone())));
+
+        let recovery_code_store: RecoveryCodeStoreType =
+            Arc::new(RwLock::new(PostgresRecoveryCodeStore::new(pg_pool.clone())));
+
+        let oauth_client_store: OAuthClientStoreType =
+            Arc::new(RwLock::new(PostgresOAuthClientStore::new(pg_pool.clone())));
+
+        let machine_client_store: MachineClientStoreType = Arc::new(RwLock::new(
+            PostgresMachineClientStore::new(pg_pool.clone()),
+        ));
+
+        let api_key_store: ApiKeyStoreType =
+            Arc::new(RwLock::new(PostgresApiKeyStore::new(pg_pool.clone())));
+
+        let role_store: RoleStoreType =
+            Arc::new(RwLock::new(PostgresRoleStore::new(pg_pool.clone())));
+
+        let audit_log_store: AuditLogStoreType =
+            Arc::new(RwLock::new(PostgresAuditLogStore::new(pg_pool.clone())));
+
+        let webhook_store: WebhookStoreType =
+            Arc::new(RwLock::new(PostgresWebhookStore::new(pg_pool)));
+
+        let webhook_dispatcher = WebhookDispatcher::new(
+            webhook_store.clone(),
+            // the mock receivers listen on 127.0.0.1
+            webhook_http_client(test::webhooks::TIMEOUT, true).unwrap(),
+            test::webhooks::POLL_INTERVAL,
+        );
 
         let email_server = MockServer::start().await;
+
+        // accept emails a test doesn't set expectations for, like the
+        // verification email sent on every signup
+        Mock::given(path("/email"))
+            .and(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .with_priority(u8::MAX)
+            .mount(&email_server)
+            .await;
+
         let base_url = email_server.uri();
         let email_client = Arc::new(configure_postmark_email_client(base_url));
 
-
         let app_state = AppState::new(
-            user_store,
+            user_store.clone(),
             banned_tokens_store.clone(),
             two_fa_code_store.clone(),
-            email_client.clone(),
+            refresh_token_store.clone(),
+            password_reset_token_store,
+            email_verification_token_store,
+            totp_secret_store,
+            recovery_code_store,
+            login_lockout_store,
+            rate_limit_store,
+            magic_link_token_store,
+            oauth_client_store.clone(),
+            authorization_code_store,
+            machine_client_store.clone(),
+            api_key_store,
+            role_store.clone(),
+            session_store.clone(),
+            audit_log_store,
+            webhook_store,
+            webhook_dispatcher.wakeup(),
+            email_client,
         );
 
+        tokio::spawn(webhook_dispatcher.run());
+
         let app = Application::build(app_state, test::APP_ADDRESS)
             .await
             .expect("Failed to build app");
@@ -77,10 +202,15 @@ impl TestApp {
             address,
             cookie_jar,
             http_client,
+            user_store,
             banned_tokens_store,
             two_fa_code_store,
+            refresh_token_store,
+            oauth_client_store,
+            machine_client_store,
+            role_store,
+            session_store,
             email_server,
-            email_client,
             db_name,
             clean_up_called,
         }
@@ -88,7 +218,7 @@ impl TestApp {
 
     pub async fn post_logout(&self) -> reqwest::Response {
         self.http_client
-            .post(&format!("{}/logout", &self.address))
+            .post(format!("{}/logout", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
@@ -99,7 +229,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/signup", &self.address))
+            .post(format!("{}/signup", &self.address))
             .json(&body)
             .send()
             .await
@@ -111,7 +241,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/login", &self.address))
+            .post(format!("{}/login", &self.address))
             .json(&body)
             .send()
             .await
@@ -142,6 +272,558 @@ impl TestApp {
             .expect("Failed to execute request")
     }
 
+    pub async fn post_request_magic_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/magic-link/request", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_magic_link_login<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/magic-link/login", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    // redirects aren't followed, so tests can inspect where the user would be sent
+    pub async fn get_authorize(&self, query: &[(&str, &str)]) -> reqwest::Response {
+        reqwest::Client::builder()
+            .cookie_provider(self.cookie_jar.clone())
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap()
+            .get(format!("{}/authorize", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_openid_configuration(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!(
+                "{}/.well-known/openid-configuration",
+                &self.address
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_userinfo(&self, access_token: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/userinfo", &self.address))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_token_with_basic_auth(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        form: &[(&str, &str)],
+    ) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/token", &self.address))
+            .basic_auth(client_id, Some(client_secret))
+            .form(form)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_token(&self, form: &[(&str, &str)]) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/token", &self.address))
+            .form(form)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_resend_2fa<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/resend-2fa", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_jwks(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/.well-known/jwks.json", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_refresh_token(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/token/refresh", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_password_reset_request<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/password-reset/request", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_password_reset_confirm<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/password-reset/confirm", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_change_password<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/change-password", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_account<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .delete(format!("{}/account", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_api_key<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/api-keys", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_api_keys(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/api-keys", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_api_key(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/api-keys/{}", &self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_sessions(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/sessions", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_session(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/sessions/{}", &self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_sessions(&self) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/sessions", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_roles(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/roles", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_user_roles(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/users/{}/roles", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_user_role(&self, email: &str, role: &str) -> reqwest::Response {
+        self.http_client
+            .put(format!(
+                "{}/admin/users/{}/roles/{}",
+                &self.address, email, role
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_user_role(&self, email: &str, role: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!(
+                "{}/admin/users/{}/roles/{}",
+                &self.address, email, role
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_audit_events(&self, query: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/audit-events?{}", &self.address, query))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_webhook<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/admin/webhooks", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_webhooks(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/webhooks", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_webhook(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/admin/webhooks/{}", &self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_webhook_deliveries(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!(
+                "{}/admin/webhooks/{}/deliveries",
+                &self.address, id
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_redeliver_webhook(&self, id: &str, delivery_id: &str) -> reqwest::Response {
+        self.http_client
+            .post(format!(
+                "{}/admin/webhooks/{}/deliveries/{}/redeliver",
+                &self.address, id, delivery_id
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_admin_users(&self, query: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/users?{}", &self.address, query))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_admin_user(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/users/{}", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_admin_user(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/admin/users/{}", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_disable_user(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/admin/users/{}/disable", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_enable_user(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/admin/users/{}/enable", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_force_password_reset(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .post(format!(
+                "{}/admin/users/{}/password-reset",
+                &self.address, email
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_user_2fa<Body>(&self, email: &str, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/admin/users/{}/2fa", &self.address, email))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_request_enable_2fa(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/2fa/enable/request", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_enable_2fa<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/enable", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_disable_2fa<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/disable", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_enroll_totp(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/2fa/totp/enroll", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_confirm_totp<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/totp/confirm", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_regenerate_recovery_codes<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/recovery-codes", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_unlock_account<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/unlock-account", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_verify_email<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/verify-email", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_verify_email_resend<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/verify-email/resend", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    // lets the verification token's Redis key lapse as if its TTL had run out
+    pub fn expire_email_verification_token(&self, token: &str) {
+        let key = format!("email_verification_token:{}", token);
+
+        configure_redis()
+            .expire::<_, ()>(key, 0)
+            .expect("Failed to expire the email verification token");
+    }
+
+    // reads the token from the link in the most recent matching email sent to `email`
+    pub async fn get_emailed_token(&self, email: &str, query_param: &str) -> String {
+        let requests = self
+            .email_server
+            .received_requests()
+            .await
+            .expect("Request recording is disabled");
+
+        requests
+            .iter()
+            .rev()
+            .filter_map(|request| request.body_json::<serde_json::Value>().ok())
+            .filter(|body| body["To"] == email)
+            .filter_map(|body| Url::parse(body["TextBody"].as_str()?).ok())
+            .find_map(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == query_param)
+                    .map(|(_, value)| value.into_owned())
+            })
+            .expect("No email with a matching token was sent")
+    }
+
+    pub async fn verify_email(&self, email: &str) -> reqwest::Response {
+        let token = self
+            .get_emailed_token(email, "email_verification_token")
+            .await;
+
+        self.post_verify_email(&serde_json::json!({ "token": token }))
+            .await
+    }
+
+    // signs up a new admin and logs in as them, returning the session token. The first
+    // admin can only be granted outside the API.
+    pub async fn login_as_admin(&self) -> String {
+        let email = get_random_email();
+
+        let response = self
+            .post_signup(&serde_json::json!({
+                "email": email,
+                "password": "password123",
+                "requires2FA": false
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+
+        let response = self.verify_email(&email).await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        self.role_store
+            .write()
+            .await
+            .assign_role(
+                &Email::parse(Secret::new(email.clone())).expect("Failed to parse email"),
+                "admin",
+            )
+            .await
+            .expect("Failed to assign admin role");
+
+        let response = self
+            .post_login(&serde_json::json!({
+                "email": email,
+                "password": "password123",
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        let auth_cookie = response
+            .cookies()
+            .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+            .expect("No auth cookie found");
+
+        auth_cookie.value().to_owned()
+    }
+
     pub async fn clean_up(&mut self) {
         if self.clean_up_called {
             return;
@@ -167,7 +849,7 @@ pub fn get_random_email() -> String {
 async fn configure_postgresql(db_name: &str) -> PgPool {
     let postgresql_conn_url = DATABASE_URL.to_owned();
 
-    configure_database(&postgresql_conn_url.expose_secret(), &db_name).await;
+    configure_database(postgresql_conn_url.expose_secret(), db_name).await;
 
     let postgresql_conn_url_with_db =
         Secret::new(format!("{}/{}", postgresql_conn_url.expose_secret(), db_name).to_string());
@@ -253,7 +935,7 @@ fn configure_postmark_email_client(base_url: String) -> PostmarkEmailClient {
     let postmark_auth_token = Secret::new("auth_token".to_owned());
 
     let sender = Email::parse(Secret::new(test::email_client::SENDER.to_owned())).unwrap();
-    
+
     let http_client = Client::builder()
         .timeout(test::email_client::TIMEOUT)
         .build()
@@ -0,0 +1,126 @@
+use auth_service::utils::{
+    auth::{Claims, TokenKind},
+    constants::JWT_COOKIE_NAME,
+};
+use jsonwebtoken::{
+    decode, decode_header, encode, jwk::JwkSet, Algorithm, DecodingKey, EncodingKey, Header,
+    Validation,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+#[tokio::test]
+async fn should_return_200_with_public_keys() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_jwks().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let jwks = response
+        .json::<JwkSet>()
+        .await
+        .expect("Could not deserialize response body to JwkSet");
+
+    assert!(!jwks.keys.is_empty());
+    for jwk in jwks.keys.iter() {
+        assert!(jwk.is_supported());
+        assert!(jwk.common.key_id.is_some());
+    }
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_verify_issued_token_with_published_key() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+
+    let jwks = app
+        .get_jwks()
+        .await
+        .json::<JwkSet>()
+        .await
+        .expect("Could not deserialize response body to JwkSet");
+
+    let kid = decode_header(auth_cookie.value())
+        .expect("Failed to decode token header")
+        .kid
+        .expect("Token has no kid header");
+
+    let jwk = jwks.find(&kid).expect("Signing key not published");
+    let decoding_key = DecodingKey::from_jwk(jwk).expect("Failed to build decoding key");
+
+    let claims = decode::<Claims>(
+        auth_cookie.value(),
+        &decoding_key,
+        &Validation::new(Algorithm::EdDSA),
+    )
+    .expect("Failed to verify token with published key")
+    .claims;
+
+    assert_eq!(claims.sub, random_email);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_symmetrically_signed_token() {
+    let mut app = TestApp::new().await;
+
+    let claims = Claims {
+        sub: get_random_email(),
+        exp: 4_102_444_800,
+        iat: 1_700_000_000,
+        jti: "forged".to_owned(),
+        sid: None,
+        token_version: 0,
+        kind: TokenKind::User,
+        aud: None,
+        scope: None,
+        roles: Vec::new(),
+        permissions: Vec::new(),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret("secret".as_bytes()),
+    )
+    .expect("Failed to create token");
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": token }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    app.clean_up().await
+}
@@ -2,7 +2,10 @@ use auth_service::{
     domain::Email, routes::TwoFactorAuthResponse, utils::constants::JWT_COOKIE_NAME,
 };
 use secrecy::Secret;
-use wiremock::{matchers::{method, path}, Mock, ResponseTemplate};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
 
 use crate::helpers::get_random_email;
 
@@ -109,6 +112,9 @@ async fn should_return_200_if_valid_credentials_and_2fa_disabled() {
 
     assert_eq!(response.status().as_u16(), 201);
 
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     let login_body = serde_json::json!({
         "email": random_email,
         "password": "password123",
@@ -140,7 +146,10 @@ async fn should_return_206_if_valid_credentials_and_2fa_enabled() {
     });
 
     app.post_signup(&signup_body).await;
-    
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     Mock::given(path("/email"))
         .and(method("POST"))
         .respond_with(ResponseTemplate::new(200))
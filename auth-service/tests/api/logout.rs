@@ -2,7 +2,7 @@ use crate::helpers::get_random_email;
 
 use super::helpers::TestApp;
 use auth_service::{
-    domain::{Email, ErrorResponse},
+    domain::{Email, ErrorResponse, Session, SessionClient},
     utils::{auth::generate_auth_cookie, constants::JWT_COOKIE_NAME},
 };
 use reqwest::Url;
@@ -44,10 +44,30 @@ async fn should_return_401_if_invalid_token() {
 async fn should_return_200_if_valid_jwt_cookie() {
     let mut app = TestApp::new().await;
 
-    let cookie = generate_auth_cookie(
-        &Email::parse(Secret::new("test@test.com".to_owned())).expect("Failed to parse email"),
-    )
-    .expect("Email failed");
+    // tokens are only valid for users that exist
+    let random_email = get_random_email();
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let email = Email::parse(Secret::new(random_email)).expect("Failed to parse email");
+    let session = Session::new(email.clone(), SessionClient::default());
+    let session_id = session.id.clone();
+    app.session_store
+        .write()
+        .await
+        .add_session(session)
+        .await
+        .expect("Failed to add session");
+
+    let cookie = generate_auth_cookie(&app.role_store, &email, 0, &session_id)
+        .await
+        .expect("Email failed");
     app.cookie_jar.add_cookie_str(
         &cookie.to_string(),
         &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
@@ -67,6 +87,16 @@ async fn should_return_200_if_valid_jwt_cookie() {
     let response = app.post_logout().await;
 
     assert_eq!(response.status().as_u16(), 200);
+
+    let banned = app
+        .banned_tokens_store
+        .read()
+        .await
+        .verify_token_exists(cookie.value())
+        .await
+        .expect("Failed to check banned token store");
+
+    assert!(banned);
     app.clean_up().await
 }
 
@@ -85,6 +115,9 @@ async fn should_return_400_if_logout_called_twice() {
 
     assert_eq!(response.status().as_u16(), 201);
 
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     let login_body = serde_json::json!({
         "email": random_email,
         "password": "password123",
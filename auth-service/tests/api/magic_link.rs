@@ -0,0 +1,157 @@
+use auth_service::{
+    domain::MagicLinkToken,
+    routes::{TwoFAMethod, TwoFactorAuthResponse},
+    utils::constants::JWT_COOKIE_NAME,
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup(app: &TestApp, requires_2fa: bool) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": requires_2fa
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+async fn request_link(app: &TestApp, email: &str) -> String {
+    let response = app
+        .post_request_magic_link(&serde_json::json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.get_emailed_token(email, "magic_link_token").await
+}
+
+#[tokio::test]
+async fn should_log_in_with_magic_link_once() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app, false).await;
+    let token = request_link(&app, &email).await;
+
+    let response = app
+        .post_magic_link_login(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+    assert!(!auth_cookie.value().is_empty());
+
+    let response = app
+        .post_magic_link_login(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_require_2fa_after_magic_link() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app, true).await;
+    let token = request_link(&app, &email).await;
+
+    let response = app
+        .post_magic_link_login(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+    assert!(response
+        .cookies()
+        .all(|cookie| cookie.name() != JWT_COOKIE_NAME));
+
+    let body = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse");
+    assert_eq!(body.two_fa_method, TwoFAMethod::Email);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_send_link_to_unknown_email() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_request_magic_link(&serde_json::json!({ "email": get_random_email() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let test_tokens = [
+        "invalid".to_owned(),
+        MagicLinkToken::default().as_ref().to_owned(),
+    ];
+
+    for token in test_tokens {
+        let response = app
+            .post_magic_link_login(&serde_json::json!({ "token": token }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_if_invalid_email() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_request_magic_link(&serde_json::json!({ "email": "invalid" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_request_magic_link(&serde_json::json!({ "mail": "test@test.com" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    let response = app
+        .post_magic_link_login(&serde_json::json!({ "magic": "token" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await
+}
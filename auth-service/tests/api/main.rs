@@ -1,6 +1,27 @@
+mod admin_users;
+mod api_keys;
+mod audit_events;
+mod change_password;
+mod client_credentials;
+mod delete_account;
 mod helpers;
+mod jwks;
 mod login;
 mod logout;
+mod magic_link;
+mod oauth;
+mod password_reset;
+mod rate_limit;
+mod recovery_codes;
+mod refresh_token;
+mod resend_2fa;
+mod roles;
+mod sessions;
 mod signup;
+mod totp;
+mod two_fa_settings;
+mod unlock_account;
 mod verify_2fa;
+mod verify_email;
 mod verify_token;
+mod webhooks;
@@ -0,0 +1,488 @@
+use auth_service::{
+    domain::{OAuthClient, OAuthErrorResponse},
+    routes::{
+        CredentialKind, OpenIdConfiguration, TokenResponse, UserInfoResponse, VerifyTokenResponse,
+    },
+    utils::{
+        auth::IdTokenClaims,
+        constants::{AUTH_SERVICE_URL, JWT_COOKIE_NAME},
+        signing_key::{JWT_ALGORITHM, KEY_RING},
+    },
+};
+use jsonwebtoken::{decode, decode_header, Validation};
+use reqwest::Url;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+const CLIENT_ID: &str = "test-client";
+const REDIRECT_URI: &str = "https://client.example.com/callback";
+// the example pair from RFC 7636 appendix B
+const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+async fn register_client(app: &TestApp) {
+    let client = OAuthClient::parse(
+        CLIENT_ID.to_owned(),
+        "Test client".to_owned(),
+        vec![REDIRECT_URI.to_owned()],
+    )
+    .unwrap();
+
+    app.oauth_client_store
+        .write()
+        .await
+        .add_client(client)
+        .await
+        .expect("Failed to register client");
+}
+
+async fn signup_and_login(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+fn authorize_query<'a>() -> Vec<(&'a str, &'a str)> {
+    vec![
+        ("response_type", "code"),
+        ("client_id", CLIENT_ID),
+        ("redirect_uri", REDIRECT_URI),
+        ("code_challenge", CODE_CHALLENGE),
+        ("code_challenge_method", "S256"),
+        ("state", "xyz"),
+    ]
+}
+
+fn location(response: &reqwest::Response) -> Url {
+    let location = response
+        .headers()
+        .get("location")
+        .expect("No Location header")
+        .to_str()
+        .unwrap();
+
+    Url::parse(REDIRECT_URI).unwrap().join(location).unwrap()
+}
+
+fn query_param(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+async fn authorization_code(app: &TestApp, extra: &[(&'static str, &'static str)]) -> String {
+    let mut query = authorize_query();
+    query.extend_from_slice(extra);
+
+    let response = app.get_authorize(&query).await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = location(&response);
+    assert!(location.as_str().starts_with(REDIRECT_URI));
+    assert_eq!(query_param(&location, "state").as_deref(), Some("xyz"));
+
+    query_param(&location, "code").expect("No code in redirect")
+}
+
+async fn exchange(app: &TestApp, code: &str, code_verifier: &str) -> reqwest::Response {
+    app.post_token(&[
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", REDIRECT_URI),
+        ("client_id", CLIENT_ID),
+        ("code_verifier", code_verifier),
+    ])
+    .await
+}
+
+async fn oauth_error(response: reqwest::Response) -> String {
+    response
+        .json::<OAuthErrorResponse>()
+        .await
+        .expect("Could not deserialize response body to OAuthErrorResponse")
+        .error
+}
+
+#[tokio::test]
+async fn should_issue_token_for_code_and_verifier() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    signup_and_login(&app).await;
+
+    let code = authorization_code(&app, &[]).await;
+
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+
+    let body = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert_eq!(body.token_type, "Bearer");
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": body.access_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // codes are single use
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "invalid_grant");
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_accept_access_token_on_first_party_routes() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    app.login_as_admin().await;
+
+    let code = authorization_code(&app, &[("scope", "openid email")]).await;
+
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert_eq!(body.scope.as_deref(), Some("openid email"));
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": body.access_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let verified = response
+        .json::<VerifyTokenResponse>()
+        .await
+        .expect("Could not deserialize response body to VerifyTokenResponse");
+    assert_eq!(verified.kind, CredentialKind::OAuth);
+    assert_eq!(verified.aud.as_deref(), Some(CLIENT_ID));
+    assert_eq!(verified.scope.as_deref(), Some("openid email"));
+    // the user is an admin, the client acting for them isn't
+    assert!(verified.roles.is_empty());
+    assert!(verified.permissions.is_empty());
+
+    // a bearer token takes precedence over the admin's session cookie
+    let response = app
+        .http_client
+        .get(format!("{}/admin/users", &app.address))
+        .bearer_auth(&body.access_token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}={}; HttpOnly; SameSite=Lax; Path=/",
+            JWT_COOKIE_NAME, body.access_token
+        ),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+
+    let response = app.get_api_keys().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_wrong_code_verifier() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    signup_and_login(&app).await;
+
+    let code = authorization_code(&app, &[]).await;
+
+    let response = exchange(&app, &code, &"a".repeat(43)).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "invalid_grant");
+
+    // a failed exchange burns the code
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_code_of_disabled_user() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    let email = signup_and_login(&app).await;
+
+    let code = authorization_code(&app, &[]).await;
+
+    app.login_as_admin().await;
+    let response = app.post_disable_user(&email).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "invalid_grant");
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_send_signed_out_users_to_login() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+
+    let response = app.get_authorize(&authorize_query()).await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = location(&response);
+    let return_to = query_param(&location, "return_to").expect("No return_to in redirect");
+    assert!(return_to.starts_with("/authorize?"));
+    assert!(return_to.contains("state=xyz"));
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_redirect_to_unregistered_uri() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+
+    let mut query = authorize_query();
+    query[2] = ("redirect_uri", "https://evil.example.com/callback");
+
+    let response = app.get_authorize(&query).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "invalid_request");
+
+    let mut query = authorize_query();
+    query[1] = ("client_id", "unknown-client");
+
+    let response = app.get_authorize(&query).await;
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(oauth_error(response).await, "invalid_client");
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_require_pkce() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    signup_and_login(&app).await;
+
+    let without_challenge: Vec<_> = authorize_query()
+        .into_iter()
+        .filter(|(key, _)| !key.starts_with("code_challenge"))
+        .collect();
+
+    let mut plain_challenge = authorize_query();
+    plain_challenge[4] = ("code_challenge_method", "plain");
+
+    for query in [without_challenge, plain_challenge] {
+        let response = app.get_authorize(&query).await;
+        assert_eq!(response.status().as_u16(), 303);
+
+        let location = location(&response);
+        assert!(location.as_str().starts_with(REDIRECT_URI));
+        assert_eq!(
+            query_param(&location, "error").as_deref(),
+            Some("invalid_request")
+        );
+        assert_eq!(query_param(&location, "state").as_deref(), Some("xyz"));
+        assert!(query_param(&location, "code").is_none());
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_unsupported_grant_type() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_token(&[("grant_type", "password")]).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "unsupported_grant_type");
+
+    let response = app
+        .post_token(&[("grant_type", "authorization_code")])
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(oauth_error(response).await, "invalid_request");
+
+    app.clean_up().await
+}
+
+fn decode_id_token(id_token: &str) -> IdTokenClaims {
+    let header = decode_header(id_token).unwrap();
+    let key = KEY_RING.verification_key(header.kid.as_deref()).unwrap();
+
+    let mut validation = Validation::new(JWT_ALGORITHM);
+    validation.set_audience(&[CLIENT_ID]);
+    validation.set_issuer(&[AUTH_SERVICE_URL.as_str()]);
+
+    decode::<IdTokenClaims>(id_token, key.decoding_key(), &validation)
+        .expect("Invalid id token")
+        .claims
+}
+
+#[tokio::test]
+async fn should_serve_openid_configuration() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_openid_configuration().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let config = response
+        .json::<OpenIdConfiguration>()
+        .await
+        .expect("Could not deserialize response body to OpenIdConfiguration");
+    assert_eq!(config.issuer, AUTH_SERVICE_URL.as_str());
+    assert_eq!(
+        config.token_endpoint,
+        format!("{}/token", AUTH_SERVICE_URL.as_str())
+    );
+    assert_eq!(config.code_challenge_methods_supported, vec!["S256"]);
+    assert_eq!(config.token_endpoint_auth_methods_supported, vec!["none"]);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_issue_id_token_for_openid_scope() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    let email = signup_and_login(&app).await;
+
+    let code = authorization_code(&app, &[("scope", "openid email"), ("nonce", "n-0S6")]).await;
+
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+
+    let id_token = body.id_token.expect("No id token issued");
+    let claims = decode_id_token(&id_token);
+    assert_eq!(claims.sub, email);
+    assert_eq!(claims.email, email);
+    assert!(claims.email_verified);
+    assert_eq!(claims.nonce.as_deref(), Some("n-0S6"));
+    assert!(claims.auth_time > 0 && claims.auth_time <= claims.iat);
+
+    // an id token is for the client only, it can't stand in for the access token
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": id_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.get_userinfo(&body.access_token).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let userinfo = response
+        .json::<UserInfoResponse>()
+        .await
+        .expect("Could not deserialize response body to UserInfoResponse");
+    assert_eq!(userinfo.sub, claims.sub);
+    assert_eq!(userinfo.email, email);
+    assert!(userinfo.email_verified);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_keep_auth_time_across_token_refresh() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    signup_and_login(&app).await;
+
+    // iat has a resolution of a second
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let code = authorization_code(&app, &[("scope", "openid")]).await;
+
+    let body = exchange(&app, &code, CODE_VERIFIER)
+        .await
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+
+    let claims = decode_id_token(&body.id_token.expect("No id token issued"));
+    assert!(claims.auth_time < claims.iat);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_issue_id_token_without_openid_scope() {
+    let mut app = TestApp::new().await;
+
+    register_client(&app).await;
+    signup_and_login(&app).await;
+
+    let code = authorization_code(&app, &[("scope", "email")]).await;
+
+    let response = exchange(&app, &code, CODE_VERIFIER).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Could not deserialize response body to TokenResponse");
+    assert!(body.id_token.is_none());
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_userinfo_without_valid_token() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_userinfo("invalid").await;
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response.headers().get("www-authenticate").unwrap(),
+        "Bearer error=\"invalid_token\""
+    );
+    assert_eq!(oauth_error(response).await, "invalid_token");
+
+    app.clean_up().await
+}
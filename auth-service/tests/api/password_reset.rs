@@ -0,0 +1,178 @@
+use auth_service::{domain::PasswordResetToken, utils::constants::REFRESH_TOKEN_COOKIE_NAME};
+use reqwest::Url;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn mount_email_mock(app: &TestApp, expected_calls: u64) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(expected_calls)
+        .mount(&app.email_server)
+        .await;
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_password_reset_request(&serde_json::json!({ "mail": "test@test.com" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    let response = app
+        .post_password_reset_confirm(&serde_json::json!({ "token": "token" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_if_invalid_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_password_reset_request(&serde_json::json!({ "email": "invalid" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_password_reset_confirm(&serde_json::json!({
+            "token": PasswordResetToken::default().as_ref(),
+            "password": "short",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_200_without_email_for_unknown_user() {
+    let mut app = TestApp::new().await;
+
+    mount_email_mock(&app, 0).await;
+
+    let response = app
+        .post_password_reset_request(&serde_json::json!({ "email": get_random_email() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let test_tokens = [
+        "invalid".to_owned(),
+        PasswordResetToken::default().as_ref().to_owned(),
+    ];
+
+    for token in test_tokens {
+        let response = app
+            .post_password_reset_confirm(&serde_json::json!({
+                "token": token,
+                "password": "newpassword123",
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reset_password_and_revoke_sessions() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let refresh_token = response
+        .cookies()
+        .find(|cookie| cookie.name() == REFRESH_TOKEN_COOKIE_NAME)
+        .expect("No refresh cookie found")
+        .value()
+        .to_owned();
+
+    mount_email_mock(&app, 1).await;
+
+    let response = app
+        .post_password_reset_request(&serde_json::json!({ "email": random_email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let token = app
+        .get_emailed_token(&random_email, "password_reset_token")
+        .await;
+
+    let confirm_body = serde_json::json!({
+        "token": token,
+        "password": "newpassword123",
+    });
+
+    let response = app.post_password_reset_confirm(&confirm_body).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // the token is single use
+    let response = app.post_password_reset_confirm(&confirm_body).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // sessions created before the reset can no longer be refreshed
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}={}; HttpOnly; SameSite=Lax; Path=/",
+            REFRESH_TOKEN_COOKIE_NAME, refresh_token
+        ),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "newpassword123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
@@ -0,0 +1,122 @@
+use auth_service::{
+    domain::LoginAttemptId,
+    utils::rate_limit::{
+        OAUTH_TOKEN_RATE_LIMIT, RESEND_2FA_RATE_LIMIT, SIGNUP_RATE_LIMIT, VERIFY_2FA_RATE_LIMIT,
+    },
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+fn retry_after(response: &reqwest::Response) -> u64 {
+    response
+        .headers()
+        .get("retry-after")
+        .expect("No Retry-After header")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn should_return_429_when_signup_limit_exceeded() {
+    let mut app = TestApp::new().await;
+
+    for _ in 0..SIGNUP_RATE_LIMIT.max_requests {
+        let response = app
+            .post_signup(&serde_json::json!({
+                "email": get_random_email(),
+                "password": "password123",
+                "requires2FA": false
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 429);
+
+    let seconds = retry_after(&response);
+    assert!(seconds > 0 && seconds <= SIGNUP_RATE_LIMIT.window_seconds);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_limit_verify_2fa_per_email() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let verify = |email: String| {
+        let app = &app;
+        async move {
+            app.post_verify_2fa(&serde_json::json!({
+                "email": email,
+                "loginAttemptId": LoginAttemptId::default().as_ref(),
+                "2FACode": "123456",
+            }))
+            .await
+        }
+    };
+
+    for _ in 0..VERIFY_2FA_RATE_LIMIT.max_requests {
+        let response = verify(email.clone()).await;
+        assert_ne!(response.status().as_u16(), 429);
+    }
+
+    // the same address typed differently shares the limit
+    let response = verify(email.to_uppercase()).await;
+    assert_eq!(response.status().as_u16(), 429);
+    assert!(retry_after(&response) <= VERIFY_2FA_RATE_LIMIT.window_seconds);
+
+    let response = verify(get_random_email()).await;
+    assert_ne!(response.status().as_u16(), 429);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_limit_resend_2fa_per_email() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let body = serde_json::json!({
+        "email": email,
+        "loginAttemptId": LoginAttemptId::default().as_ref(),
+    });
+
+    for _ in 0..RESEND_2FA_RATE_LIMIT.max_requests {
+        let response = app.post_resend_2fa(&body).await;
+        assert_ne!(response.status().as_u16(), 429);
+    }
+
+    let response = app.post_resend_2fa(&body).await;
+    assert_eq!(response.status().as_u16(), 429);
+    assert!(retry_after(&response) <= RESEND_2FA_RATE_LIMIT.window_seconds);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_limit_oauth_token_requests() {
+    let mut app = TestApp::new().await;
+
+    for _ in 0..OAUTH_TOKEN_RATE_LIMIT.max_requests {
+        let response = app.post_token(&[("grant_type", "password")]).await;
+        assert_eq!(response.status().as_u16(), 400);
+    }
+
+    let response = app.post_token(&[("grant_type", "password")]).await;
+    assert_eq!(response.status().as_u16(), 429);
+    assert!(retry_after(&response) <= OAUTH_TOKEN_RATE_LIMIT.window_seconds);
+
+    app.clean_up().await
+}
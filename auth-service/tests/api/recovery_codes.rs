@@ -0,0 +1,199 @@
+use auth_service::{
+    routes::{RecoveryCodesResponse, SignupResponse, TwoFactorAuthResponse},
+    utils::constants::{JWT_COOKIE_NAME, RECOVERY_CODE_COUNT},
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_with_2fa(app: &TestApp, email: &str) -> Vec<String> {
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let body = response
+        .json::<SignupResponse>()
+        .await
+        .expect("Could not deserialize response body to SignupResponse");
+
+    let response = app.verify_email(email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    body.recovery_codes.expect("No recovery codes returned")
+}
+
+async fn verify_2fa_with(app: &TestApp, email: &str, code: &str) -> reqwest::Response {
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let body = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse");
+
+    app.post_verify_2fa(&serde_json::json!({
+        "email": email,
+        "loginAttemptId": body.login_attempt_id,
+        "2FACode": code,
+    }))
+    .await
+}
+
+#[tokio::test]
+async fn should_return_recovery_codes_on_signup_with_2fa() {
+    let mut app = TestApp::new().await;
+
+    let codes = signup_with_2fa(&app, &get_random_email()).await;
+    assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let body = response
+        .json::<SignupResponse>()
+        .await
+        .expect("Could not deserialize response body to SignupResponse");
+    assert!(body.recovery_codes.is_none());
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_accept_recovery_code_once() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let codes = signup_with_2fa(&app, &email).await;
+
+    let response = verify_2fa_with(&app, &email, &codes[0]).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+    assert!(!auth_cookie.value().is_empty());
+
+    let response = verify_2fa_with(&app, &email, &codes[0]).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // codes are accepted however the user types them
+    let retyped = codes[1].replace('-', "").to_uppercase();
+    let response = verify_2fa_with(&app, &email, &retyped).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_for_another_users_recovery_code() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_with_2fa(&app, &email).await;
+    let other_codes = signup_with_2fa(&app, &get_random_email()).await;
+
+    let response = verify_2fa_with(&app, &email, &other_codes[0]).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_regenerate_recovery_codes(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_409_if_2fa_not_enabled() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_regenerate_recovery_codes(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_regenerate_recovery_codes() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let old_codes = signup_with_2fa(&app, &email).await;
+
+    let response = verify_2fa_with(&app, &email, &old_codes[0]).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_regenerate_recovery_codes(&serde_json::json!({ "password": "wrongpassword" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_regenerate_recovery_codes(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let new_codes = response
+        .json::<RecoveryCodesResponse>()
+        .await
+        .expect("Could not deserialize response body to RecoveryCodesResponse")
+        .recovery_codes;
+    assert_eq!(new_codes.len(), RECOVERY_CODE_COUNT);
+
+    let response = verify_2fa_with(&app, &email, &old_codes[1]).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = verify_2fa_with(&app, &email, &new_codes[0]).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
@@ -0,0 +1,168 @@
+use auth_service::{
+    domain::{Email, RefreshToken},
+    utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+};
+use reqwest::Url;
+use secrecy::Secret;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_and_login(app: &TestApp) -> (String, String) {
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let refresh_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == REFRESH_TOKEN_COOKIE_NAME)
+        .expect("No refresh cookie found");
+
+    (random_email, refresh_cookie.value().to_owned())
+}
+
+fn set_refresh_cookie(app: &TestApp, token: &str) {
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}={}; HttpOnly; SameSite=Lax; Path=/",
+            REFRESH_TOKEN_COOKIE_NAME, token
+        ),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+}
+
+#[tokio::test]
+async fn should_return_400_if_refresh_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_refresh_token().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_refresh_token() {
+    let mut app = TestApp::new().await;
+
+    set_refresh_cookie(&app, "invalid");
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    set_refresh_cookie(&app, RefreshToken::default().as_ref());
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_200_and_rotate_refresh_token() {
+    let mut app = TestApp::new().await;
+
+    let (email, old_refresh_token) = signup_and_login(&app).await;
+
+    let response = app.post_refresh_token().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+
+    assert!(!auth_cookie.value().is_empty());
+
+    let refresh_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == REFRESH_TOKEN_COOKIE_NAME)
+        .expect("No refresh cookie found");
+
+    assert_ne!(refresh_cookie.value(), old_refresh_token);
+
+    let record = app
+        .refresh_token_store
+        .read()
+        .await
+        .get_token(&RefreshToken::parse(refresh_cookie.value().to_owned()).unwrap())
+        .await
+        .expect("Rotated refresh token was not stored");
+
+    assert_eq!(
+        record.email,
+        Email::parse(Secret::new(email)).expect("Failed to parse email")
+    );
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": auth_cookie.value() }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_revoke_token_family_if_refresh_token_reused() {
+    let mut app = TestApp::new().await;
+
+    let (_, old_refresh_token) = signup_and_login(&app).await;
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let new_refresh_token = response
+        .cookies()
+        .find(|cookie| cookie.name() == REFRESH_TOKEN_COOKIE_NAME)
+        .expect("No refresh cookie found")
+        .value()
+        .to_owned();
+
+    // replay the already rotated token
+    set_refresh_cookie(&app, &old_refresh_token);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // the token issued by the legitimate rotation is revoked along with its family
+    set_refresh_cookie(&app, &new_refresh_token);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_after_logout() {
+    let mut app = TestApp::new().await;
+
+    let (_, refresh_token) = signup_and_login(&app).await;
+
+    let response = app.post_logout().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    set_refresh_cookie(&app, &refresh_token);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
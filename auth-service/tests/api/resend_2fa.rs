@@ -0,0 +1,154 @@
+use auth_service::{
+    domain::{Email, LoginAttemptId},
+    routes::TwoFactorAuthResponse,
+    utils::constants::TWO_FA_RESEND_COOLDOWN_SECONDS,
+};
+use secrecy::Secret;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn login_with_2fa(app: &TestApp) -> (String, String) {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let login_attempt_id = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse")
+        .login_attempt_id;
+
+    (email, login_attempt_id)
+}
+
+async fn stored_code(app: &TestApp, email: &str) -> String {
+    let (_, code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .expect("No code stored");
+
+    code.as_ref().to_owned()
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_resend_2fa(&serde_json::json!({ "email": get_random_email() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_if_invalid_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_resend_2fa(&serde_json::json!({
+            "email": get_random_email(),
+            "loginAttemptId": "invalid",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_login_attempt_unknown() {
+    let mut app = TestApp::new().await;
+
+    let (email, _) = login_with_2fa(&app).await;
+
+    let response = app
+        .post_resend_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": LoginAttemptId::default().as_ref(),
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_resend_fresh_code_with_cooldown() {
+    let mut app = TestApp::new().await;
+
+    let (email, login_attempt_id) = login_with_2fa(&app).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_resend_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_resend_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 429);
+
+    let retry_after: u64 = response
+        .headers()
+        .get("retry-after")
+        .expect("No Retry-After header")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= TWO_FA_RESEND_COOLDOWN_SECONDS);
+
+    // the same login attempt carries on with the new code
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": stored_code(&app, &email).await,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
@@ -0,0 +1,289 @@
+use auth_service::{
+    domain::ErrorResponse,
+    routes::{RoleResponse, UserRolesResponse, VerifyTokenResponse},
+    utils::constants::JWT_COOKIE_NAME,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+// returns the session token, which reflects the user's roles at login
+async fn login(app: &TestApp, email: &str) -> String {
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+
+    auth_cookie.value().to_owned()
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_roles().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_403_without_permission() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    login(&app, &email).await;
+
+    let response = app.get_roles().await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.put_user_role(&email, "admin").await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_list_roles_for_admin() {
+    let mut app = TestApp::new().await;
+
+    app.login_as_admin().await;
+
+    let response = app.get_roles().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let roles = response
+        .json::<Vec<RoleResponse>>()
+        .await
+        .expect("Could not deserialize response body to roles");
+    let admin = roles
+        .iter()
+        .find(|role| role.name == "admin")
+        .expect("No admin role");
+    assert_eq!(
+        admin.permissions,
+        vec![
+            "audit:read",
+            "roles:assign",
+            "users:manage",
+            "webhooks:manage"
+        ]
+    );
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_embed_roles_in_tokens() {
+    let mut app = TestApp::new().await;
+
+    let token = app.login_as_admin().await;
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<VerifyTokenResponse>()
+        .await
+        .expect("Could not deserialize response body to VerifyTokenResponse");
+    assert_eq!(body.roles, vec!["admin"]);
+    assert_eq!(
+        body.permissions,
+        vec![
+            "audit:read",
+            "roles:assign",
+            "users:manage",
+            "webhooks:manage"
+        ]
+    );
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_accept_bearer_token() {
+    let mut app = TestApp::new().await;
+
+    let token = app.login_as_admin().await;
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/admin/roles", &app.address))
+        .bearer_auth(token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_assign_and_unassign_roles() {
+    let mut app = TestApp::new().await;
+
+    let other = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.put_user_role(&other, "admin").await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    // assigning twice is not an error
+    let response = app.put_user_role(&other, "admin").await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app.get_user_roles(&other).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<UserRolesResponse>()
+        .await
+        .expect("Could not deserialize response body to UserRolesResponse");
+    assert_eq!(body.roles, vec!["admin"]);
+    assert_eq!(
+        body.permissions,
+        vec![
+            "audit:read",
+            "roles:assign",
+            "users:manage",
+            "webhooks:manage"
+        ]
+    );
+
+    let response = app.delete_user_role(&other, "admin").await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let body = app
+        .get_user_roles(&other)
+        .await
+        .json::<UserRolesResponse>()
+        .await
+        .expect("Could not deserialize response body to UserRolesResponse");
+    assert!(body.roles.is_empty());
+    assert!(body.permissions.is_empty());
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_404_for_unknown_role_or_user() {
+    let mut app = TestApp::new().await;
+
+    let other = signup(&app).await;
+    app.login_as_admin().await;
+
+    let response = app.put_user_role(&other, "owner").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.put_user_role(&get_random_email(), "admin").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_user_role(&other, "owner").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_user_role(&get_random_email(), "admin").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.get_user_roles(&get_random_email()).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_for_malformed_email() {
+    let mut app = TestApp::new().await;
+
+    app.login_as_admin().await;
+
+    let response = app.get_user_roles("not-an-email").await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let body = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialize response body to ErrorResponse");
+    assert_eq!(body.error, "Invalid email address");
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_expire_tokens_when_roles_change() {
+    let mut app = TestApp::new().await;
+
+    let other = signup(&app).await;
+    let stale_token = login(&app, &other).await;
+    app.login_as_admin().await;
+
+    let response = app.put_user_role(&other, "admin").await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    // the old token still claims no roles, so it can't be trusted any more
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": stale_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let token = login(&app, &other).await;
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<VerifyTokenResponse>()
+        .await
+        .expect("Could not deserialize response body to VerifyTokenResponse");
+    assert_eq!(body.roles, vec!["admin"]);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_keep_tokens_when_no_role_is_removed() {
+    let mut app = TestApp::new().await;
+
+    let other = signup(&app).await;
+    let token = login(&app, &other).await;
+    app.login_as_admin().await;
+
+    let response = app.delete_user_role(&other, "admin").await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app.delete_user_role(&other, "owner").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
@@ -0,0 +1,206 @@
+use auth_service::{routes::SessionResponse, utils::constants::JWT_COOKIE_NAME};
+use reqwest::header::USER_AGENT;
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    email
+}
+
+// logs in from the given user agent and returns the issued access token
+async fn login(app: &TestApp, email: &str, user_agent: &str) -> String {
+    let response = app
+        .http_client
+        .post(format!("{}/login", &app.address))
+        .header(USER_AGENT, user_agent)
+        .json(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+
+    auth_cookie.value().to_owned()
+}
+
+async fn list_sessions(app: &TestApp) -> Vec<SessionResponse> {
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    response
+        .json::<Vec<SessionResponse>>()
+        .await
+        .expect("Could not deserialize response body to Vec<SessionResponse>")
+}
+
+#[tokio::test]
+async fn should_list_sessions_with_client_details() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    login(&app, &email, "laptop-browser").await;
+    login(&app, &email, "phone-app").await;
+
+    let sessions = list_sessions(&app).await;
+    assert_eq!(sessions.len(), 2);
+
+    // newest first, and the newest is the one making the request
+    assert_eq!(sessions[0].user_agent.as_deref(), Some("phone-app"));
+    assert!(sessions[0].current);
+    assert_eq!(sessions[1].user_agent.as_deref(), Some("laptop-browser"));
+    assert!(!sessions[1].current);
+    assert!(sessions
+        .iter()
+        .all(|session| session.ip.as_deref() == Some("127.0.0.1")));
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_when_not_logged_in() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app.delete_sessions().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_revoke_a_single_session() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let old_token = login(&app, &email, "laptop-browser").await;
+    let current_token = login(&app, &email, "phone-app").await;
+
+    let sessions = list_sessions(&app).await;
+    let old_session = sessions
+        .iter()
+        .find(|session| !session.current)
+        .expect("No other session found");
+
+    let response = app.delete_session(&old_session.id).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": old_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": current_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let sessions = list_sessions(&app).await;
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].current);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_stop_refreshing_a_revoked_session() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    login(&app, &email, "laptop-browser").await;
+
+    let sessions = list_sessions(&app).await;
+    let response = app.delete_session(&sessions[0].id).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_revoke_all_sessions() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let old_token = login(&app, &email, "laptop-browser").await;
+    let current_token = login(&app, &email, "phone-app").await;
+
+    let response = app.delete_sessions().await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    for token in [old_token, current_token] {
+        let response = app
+            .post_verify_token(&serde_json::json!({ "token": token }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_404_for_unknown_session() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    login(&app, &email, "laptop-browser").await;
+
+    let response = app.delete_session(&uuid::Uuid::new_v4().to_string()).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_session("not-a-session").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_revoke_another_users_session() {
+    let mut app = TestApp::new().await;
+
+    let other_email = signup(&app).await;
+    let other_token = login(&app, &other_email, "laptop-browser").await;
+    let other_session = list_sessions(&app).await.remove(0);
+
+    let email = signup(&app).await;
+    login(&app, &email, "phone-app").await;
+
+    let response = app.delete_session(&other_session.id).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": other_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
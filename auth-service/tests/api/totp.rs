@@ -0,0 +1,218 @@
+use auth_service::{
+    domain::{Email, TotpSecret},
+    routes::{ConfirmTotpResponse, EnrollTotpResponse, TwoFAMethod, TwoFactorAuthResponse},
+    utils::{
+        constants::RECOVERY_CODE_COUNT,
+        totp::{current_step, generate_code},
+    },
+};
+use secrecy::Secret;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_and_login(app: &TestApp) -> String {
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    random_email
+}
+
+async fn enroll(app: &TestApp) -> TotpSecret {
+    let response = app.post_enroll_totp().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<EnrollTotpResponse>()
+        .await
+        .expect("Could not deserialize response body to EnrollTotpResponse");
+
+    assert!(body.otpauth_uri.starts_with("otpauth://totp/"));
+    assert!(body.otpauth_uri.contains(&body.secret));
+
+    TotpSecret::parse(Secret::new(body.secret)).expect("Invalid TOTP secret")
+}
+
+async fn login_with_totp(app: &TestApp, email: &str) -> String {
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let body = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse");
+
+    assert_eq!(body.two_fa_method, TwoFAMethod::Totp);
+
+    body.login_attempt_id
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_enroll_totp().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_confirm_totp(&serde_json::json!({ "code": "123456" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_400_if_confirming_without_enrollment() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+
+    let response = app
+        .post_confirm_totp(&serde_json::json!({ "code": "123456" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_confirmation_code_incorrect() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app).await;
+    let secret = enroll(&app).await;
+
+    // a code far outside the skew window
+    let code = generate_code(&secret, current_step().unwrap() + 10).unwrap();
+
+    let response = app
+        .post_confirm_totp(&serde_json::json!({ "code": code }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_use_totp_for_login_once_confirmed() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+    let secret = enroll(&app).await;
+
+    let step = current_step().unwrap();
+    let response = app
+        .post_confirm_totp(&serde_json::json!({ "code": generate_code(&secret, step).unwrap() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<ConfirmTotpResponse>()
+        .await
+        .expect("Could not deserialize response body to ConfirmTotpResponse");
+    assert_eq!(body.recovery_codes.len(), RECOVERY_CODE_COUNT);
+
+    let response = app.post_enroll_totp().await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    // no code is emailed to users with an authenticator app
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let login_attempt_id = login_with_totp(&app, &email).await;
+
+    // the confirmation used the current step, so the next one is within the skew window
+    let code = generate_code(&secret, step + 1).unwrap();
+
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": code,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_replayed_and_emailed_codes() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+    let secret = enroll(&app).await;
+
+    let step = current_step().unwrap();
+    let code = generate_code(&secret, step).unwrap();
+
+    let response = app
+        .post_confirm_totp(&serde_json::json!({ "code": code }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let login_attempt_id = login_with_totp(&app, &email).await;
+
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": code,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // the code kept alongside the login attempt is never sent, so it mustn't work either
+    let (_, stored_code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.clone())).unwrap())
+        .await
+        .expect("No login attempt stored");
+
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": stored_code.as_ref(),
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
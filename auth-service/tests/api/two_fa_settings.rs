@@ -0,0 +1,274 @@
+use auth_service::{
+    domain::{Email, TotpSecret},
+    routes::{
+        Enable2FAResponse, EnrollTotpResponse, SignupResponse, TwoFAMethod, TwoFactorAuthResponse,
+    },
+    utils::{
+        constants::RECOVERY_CODE_COUNT,
+        totp::{current_step, generate_code},
+    },
+};
+use secrecy::Secret;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup_and_login(app: &TestApp) -> String {
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    random_email
+}
+
+async fn emailed_code(app: &TestApp, email: &str) -> String {
+    let (_, code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .expect("No code stored");
+
+    code.as_ref().to_owned()
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_request_enable_2fa().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_enable_2fa(&serde_json::json!({ "2FACode": "123456" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_disable_2fa(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_enable_code_incorrect() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+
+    // no code requested yet
+    let response = app
+        .post_enable_2fa(&serde_json::json!({ "2FACode": "123456" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.post_request_enable_2fa().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let code = emailed_code(&app, &email).await;
+    let wrong_code = if code == "123456" { "654321" } else { "123456" };
+
+    let response = app
+        .post_enable_2fa(&serde_json::json!({ "2FACode": wrong_code }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_enable_2fa(&serde_json::json!({ "2FACode": "abc" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_enable_2fa_with_emailed_code() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_request_enable_2fa().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let code = emailed_code(&app, &email).await;
+
+    let response = app
+        .post_enable_2fa(&serde_json::json!({ "2FACode": code }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<Enable2FAResponse>()
+        .await
+        .expect("Could not deserialize response body to Enable2FAResponse");
+    assert_eq!(body.recovery_codes.len(), RECOVERY_CODE_COUNT);
+
+    let response = app.post_request_enable_2fa().await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let body = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse");
+    assert_eq!(body.two_fa_method, TwoFAMethod::Email);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_disable_2fa_with_password() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let recovery_codes = response
+        .json::<SignupResponse>()
+        .await
+        .expect("Could not deserialize response body to SignupResponse")
+        .recovery_codes
+        .expect("No recovery codes returned");
+
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let login_attempt_id = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse")
+        .login_attempt_id;
+
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": recovery_codes[0],
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_disable_2fa(&serde_json::json!({ "password": "wrongpassword" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_disable_2fa(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_disable_2fa(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_remove_authenticator_app_when_disabling() {
+    let mut app = TestApp::new().await;
+
+    let email = signup_and_login(&app).await;
+
+    let response = app.post_enroll_totp().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let secret = response
+        .json::<EnrollTotpResponse>()
+        .await
+        .expect("Could not deserialize response body to EnrollTotpResponse")
+        .secret;
+    let secret = TotpSecret::parse(Secret::new(secret)).expect("Invalid TOTP secret");
+
+    let response = app
+        .post_confirm_totp(&serde_json::json!({
+            "code": generate_code(&secret, current_step().unwrap()).unwrap()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_disable_2fa(&serde_json::json!({ "password": "password123" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // a new authenticator app can be enrolled from scratch
+    let response = app.post_enroll_totp().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
@@ -0,0 +1,150 @@
+use auth_service::{
+    domain::AccountUnlockToken,
+    utils::constants::{DEFAULT_LOGIN_LOCKOUT_SECONDS, DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS},
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+async fn signup(app: &TestApp) -> String {
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    random_email
+}
+
+async fn login(app: &TestApp, email: &str, password: &str) -> reqwest::Response {
+    app.post_login(&serde_json::json!({
+        "email": email,
+        "password": password,
+    }))
+    .await
+}
+
+fn retry_after(response: &reqwest::Response) -> u64 {
+    response
+        .headers()
+        .get("retry-after")
+        .expect("No Retry-After header")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn should_lock_account_after_repeated_failed_logins() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+
+    for _ in 1..DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS {
+        let response = login(&app, &email, "wrongpassword").await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    let response = login(&app, &email, "wrongpassword").await;
+    assert_eq!(response.status().as_u16(), 423);
+    assert_eq!(retry_after(&response), DEFAULT_LOGIN_LOCKOUT_SECONDS);
+
+    // the right password doesn't help while locked
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 423);
+    assert!(retry_after(&response) <= DEFAULT_LOGIN_LOCKOUT_SECONDS);
+
+    let token = app.get_emailed_token(&email, "unlock_token").await;
+
+    let response = app
+        .post_unlock_account(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_unlock_account(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reset_failures_after_successful_login() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+
+    for _ in 1..DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS {
+        let response = login(&app, &email, "wrongpassword").await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    let response = login(&app, &email, "password123").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    for _ in 1..DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS {
+        let response = login(&app, &email, "wrongpassword").await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_lock_unknown_accounts() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+
+    for _ in 0..=DEFAULT_LOGIN_MAX_FAILED_ATTEMPTS {
+        let response = login(&app, &email, "wrongpassword").await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_unlock_token() {
+    let mut app = TestApp::new().await;
+
+    let test_tokens = [
+        "invalid".to_owned(),
+        AccountUnlockToken::default().as_ref().to_owned(),
+    ];
+
+    for token in test_tokens {
+        let response = app
+            .post_unlock_account(&serde_json::json!({ "token": token }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_unlock_account(&serde_json::json!({ "unlock": "token" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await
+}
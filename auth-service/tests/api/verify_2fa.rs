@@ -1,5 +1,7 @@
 use auth_service::{
-    domain::Email, routes::TwoFactorAuthResponse, utils::constants::JWT_COOKIE_NAME,
+    domain::Email,
+    routes::TwoFactorAuthResponse,
+    utils::constants::{JWT_COOKIE_NAME, MAX_TWO_FA_ATTEMPTS},
 };
 use secrecy::Secret;
 use wiremock::{
@@ -73,6 +75,9 @@ async fn should_return_401_if_old_code() {
 
     assert_eq!(response.status().as_u16(), 201);
 
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     Mock::given(path("/email"))
         .and(method("POST"))
         .respond_with(ResponseTemplate::new(200))
@@ -145,6 +150,9 @@ async fn should_return_200() {
         }))
         .await;
 
+    let response = app.verify_email(email.as_ref()).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     Mock::given(path("/email"))
         .and(method("POST"))
         .respond_with(ResponseTemplate::new(200))
@@ -209,6 +217,9 @@ async fn should_return_401_if_same_code_twice() {
         }))
         .await;
 
+    let response = app.verify_email(email.as_ref()).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     Mock::given(path("/email"))
         .and(method("POST"))
         .respond_with(ResponseTemplate::new(200))
@@ -268,3 +279,66 @@ async fn should_return_401_if_same_code_twice() {
     );
     app.clean_up().await
 }
+
+#[tokio::test]
+async fn should_invalidate_code_after_too_many_failed_attempts() {
+    let mut app = TestApp::new().await;
+    let email = Email::parse(Secret::new(get_random_email())).unwrap();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email.as_ref(),
+            "password": "password123",
+            "requires2FA": true,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(email.as_ref()).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email.as_ref(),
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let (attempt_id, code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&email)
+        .await
+        .expect("Failed to get code for email");
+
+    let wrong_code = if code.as_ref() == "123456" {
+        "654321"
+    } else {
+        "123456"
+    };
+
+    for _ in 0..MAX_TWO_FA_ATTEMPTS {
+        let response = app
+            .post_verify_2fa(&serde_json::json!({
+                "email": email.as_ref(),
+                "loginAttemptId": attempt_id.as_ref(),
+                "2FACode": wrong_code
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    // the right code no longer works once the attempts are used up
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email.as_ref(),
+            "loginAttemptId": attempt_id.as_ref(),
+            "2FACode": code.as_ref()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
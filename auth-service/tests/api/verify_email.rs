@@ -0,0 +1,219 @@
+use auth_service::{
+    domain::EmailVerificationToken, utils::rate_limit::VERIFY_EMAIL_RESEND_RATE_LIMIT,
+};
+
+use crate::helpers::get_random_email;
+
+use super::helpers::TestApp;
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_verify_email(&serde_json::json!({ "code": "123456" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 422);
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let test_tokens = [
+        "invalid".to_owned(),
+        EmailVerificationToken::default().as_ref().to_owned(),
+    ];
+
+    for token in test_tokens {
+        let response = app
+            .post_verify_email(&serde_json::json!({ "token": token }))
+            .await;
+
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_refuse_login_until_email_verified() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let login_body = serde_json::json!({
+        "email": random_email,
+        "password": "password123",
+    });
+
+    let response = app.post_login(&login_body).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let token = app
+        .get_emailed_token(&random_email, "email_verification_token")
+        .await;
+
+    let response = app
+        .post_verify_email(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_login(&login_body).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // the token is single use
+    let response = app
+        .post_verify_email(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_not_reveal_verification_state_for_wrong_password() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "wrongpassword",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_reject_expired_token_and_resend_a_new_one() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let expired_token = app
+        .get_emailed_token(&random_email, "email_verification_token")
+        .await;
+    app.expire_email_verification_token(&expired_token);
+
+    let response = app
+        .post_verify_email(&serde_json::json!({ "token": expired_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_verify_email_resend(&serde_json::json!({ "email": random_email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let token = app
+        .get_emailed_token(&random_email, "email_verification_token")
+        .await;
+    assert_ne!(token, expired_token);
+
+    let response = app
+        .post_verify_email(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_only_resend_to_accounts_awaiting_verification() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": random_email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let emails_sent = app
+        .email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .len();
+
+    for email in [random_email, get_random_email()] {
+        let response = app
+            .post_verify_email_resend(&serde_json::json!({ "email": email }))
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    let received = app
+        .email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled");
+    assert_eq!(received.len(), emails_sent);
+
+    app.clean_up().await
+}
+
+#[tokio::test]
+async fn should_limit_resends_per_email() {
+    let mut app = TestApp::new().await;
+
+    let body = serde_json::json!({ "email": get_random_email() });
+
+    for _ in 0..VERIFY_EMAIL_RESEND_RATE_LIMIT.max_requests {
+        let response = app.post_verify_email_resend(&body).await;
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    let response = app.post_verify_email_resend(&body).await;
+    assert_eq!(response.status().as_u16(), 429);
+
+    app.clean_up().await
+}
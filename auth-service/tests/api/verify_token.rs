@@ -1,4 +1,7 @@
-use auth_service::utils::constants::JWT_COOKIE_NAME;
+use auth_service::{
+    routes::{CredentialKind, VerifyTokenResponse},
+    utils::constants::JWT_COOKIE_NAME,
+};
 
 use crate::helpers::get_random_email;
 
@@ -20,6 +23,9 @@ async fn should_return_200_if_valid_token() {
 
     assert_eq!(response.status().as_u16(), 201);
 
+    let response = app.verify_email(&random_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
     let login_body = serde_json::json!({
         "email": random_email,
         "password": "password123",
@@ -45,6 +51,14 @@ async fn should_return_200_if_valid_token() {
     let response = app.post_verify_token(&verify_token_body).await;
 
     assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<VerifyTokenResponse>()
+        .await
+        .expect("Could not deserialize response body to VerifyTokenResponse");
+    assert_eq!(body.kind, CredentialKind::User);
+    assert_eq!(body.sub, random_email);
+
     app.clean_up().await
 }
 
@@ -0,0 +1,349 @@
+use std::time::Duration;
+
+use auth_service::{
+    domain::{ErrorResponse, WebhookSecret, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER},
+    routes::{CreateWebhookResponse, WebhookDeliveryResponse, WebhookResponse},
+};
+use secrecy::Secret;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::helpers::{get_random_email, TestApp};
+
+async fn create_webhook(app: &TestApp, url: &str, events: &[&str]) -> CreateWebhookResponse {
+    let response = app
+        .post_webhook(&serde_json::json!({ "url": url, "events": events }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    response
+        .json::<CreateWebhookResponse>()
+        .await
+        .expect("Could not deserialize response body to CreateWebhookResponse")
+}
+
+async fn signup(app: &TestApp) -> String {
+    let email = get_random_email();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    email
+}
+
+// deliveries are sent in the background, so wait for the latest one to get where the test expects
+async fn wait_for_delivery(
+    app: &TestApp,
+    webhook_id: &str,
+    done: impl Fn(&WebhookDeliveryResponse) -> bool,
+) -> WebhookDeliveryResponse {
+    for _ in 0..50 {
+        let response = app.get_webhook_deliveries(webhook_id).await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        let deliveries = response
+            .json::<Vec<WebhookDeliveryResponse>>()
+            .await
+            .expect("Could not deserialize response body to Vec<WebhookDeliveryResponse>");
+
+        if let Some(delivery) = deliveries.into_iter().next().filter(|d| done(d)) {
+            return delivery;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("Webhook delivery didn't reach the expected state");
+}
+
+#[tokio::test]
+async fn should_create_list_and_delete_webhooks() {
+    let mut app = TestApp::new().await;
+    app.login_as_admin().await;
+
+    let created = create_webhook(
+        &app,
+        "https://example.com/hooks",
+        &["user.signed_up", "user.deleted"],
+    )
+    .await;
+    assert!(created.secret.starts_with("whsec_"));
+    assert_eq!(
+        created.webhook.events,
+        vec!["user.signed_up", "user.deleted"]
+    );
+
+    let response = app.get_webhooks().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<serde_json::Value>()
+        .await
+        .expect("Could not deserialize response body");
+    assert_eq!(body.as_array().map(Vec::len), Some(1));
+    assert!(body[0].get("secret").is_none());
+
+    let id = created.webhook.id.to_string();
+    let response = app.delete_webhook(&id).await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app.delete_webhook(&id).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let webhooks = app
+        .get_webhooks()
+        .await
+        .json::<Vec<WebhookResponse>>()
+        .await
+        .expect("Could not deserialize response body to Vec<WebhookResponse>");
+    assert!(webhooks.is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_for_invalid_webhooks() {
+    let mut app = TestApp::new().await;
+    app.login_as_admin().await;
+
+    let test_cases = [
+        (
+            serde_json::json!({ "url": "not a url", "events": ["user.signed_up"] }),
+            "Invalid webhook URL or events",
+        ),
+        (
+            serde_json::json!({ "url": "ftp://example.com/hooks", "events": ["user.signed_up"] }),
+            "Invalid webhook URL or events",
+        ),
+        (
+            serde_json::json!({ "url": "https://example.com/hooks", "events": ["user.updated"] }),
+            "Unknown event type",
+        ),
+        (
+            serde_json::json!({ "url": "https://example.com/hooks", "events": [] }),
+            "Invalid webhook URL or events",
+        ),
+    ];
+
+    for (test_case, message) in test_cases {
+        let response = app.post_webhook(&test_case).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Failed for input: {:?}",
+            test_case
+        );
+
+        let body = response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse");
+        assert_eq!(body.error, message);
+    }
+
+    let responses = [
+        (app.delete_webhook("not-a-uuid").await, "Invalid webhook id"),
+        (
+            app.get_webhook_deliveries("not-a-uuid").await,
+            "Invalid webhook id",
+        ),
+        (
+            app.post_redeliver_webhook("not-a-uuid", &uuid::Uuid::new_v4().to_string())
+                .await,
+            "Invalid webhook id",
+        ),
+        (
+            app.post_redeliver_webhook(&uuid::Uuid::new_v4().to_string(), "not-a-uuid")
+                .await,
+            "Invalid webhook delivery id",
+        ),
+    ];
+
+    for (response, message) in responses {
+        assert_eq!(response.status().as_u16(), 400);
+        let body = response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse");
+        assert_eq!(body.error, message);
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_403_without_webhooks_permission() {
+    let mut app = TestApp::new().await;
+
+    let email = signup(&app).await;
+    let response = app.verify_email(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password123",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_webhook(&serde_json::json!({
+            "url": "https://example.com/hooks",
+            "events": ["user.signed_up"]
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.get_webhooks().await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_deliver_signed_events_to_subscribers() {
+    let mut app = TestApp::new().await;
+    app.login_as_admin().await;
+
+    let receiver = MockServer::start().await;
+    Mock::given(path("/hooks"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&receiver)
+        .await;
+
+    let created = create_webhook(
+        &app,
+        &format!("{}/hooks", receiver.uri()),
+        &["user.signed_up"],
+    )
+    .await;
+    let id = created.webhook.id.to_string();
+
+    let email = signup(&app).await;
+
+    let delivery = wait_for_delivery(&app, &id, |d| d.status == "delivered").await;
+    assert_eq!(delivery.kind, "user.signed_up");
+    assert_eq!(delivery.attempts, 1);
+    assert!(delivery.delivered_at.is_some());
+
+    let requests = receiver.received_requests().await.unwrap();
+    let request = &requests[0];
+    let body = String::from_utf8(request.body.clone()).unwrap();
+
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["type"], "user.signed_up");
+    assert_eq!(payload["data"]["email"], email.as_str());
+
+    let timestamp: i64 = request.headers[WEBHOOK_TIMESTAMP_HEADER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let signature = request.headers[WEBHOOK_SIGNATURE_HEADER].to_str().unwrap();
+    let secret = WebhookSecret::parse(Secret::new(created.secret)).unwrap();
+    assert_eq!(signature, secret.sign(timestamp, &body));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_retry_failed_deliveries_and_allow_redelivery() {
+    let mut app = TestApp::new().await;
+    app.login_as_admin().await;
+
+    let receiver = MockServer::start().await;
+    Mock::given(path("/hooks"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&receiver)
+        .await;
+
+    let created = create_webhook(
+        &app,
+        &format!("{}/hooks", receiver.uri()),
+        &["user.signed_up"],
+    )
+    .await;
+    let id = created.webhook.id.to_string();
+
+    signup(&app).await;
+
+    let failed = wait_for_delivery(&app, &id, |d| d.attempts == 1).await;
+    assert_eq!(failed.status, "pending");
+    assert!(failed.next_attempt_at > failed.created_at);
+    assert!(failed.last_error.as_deref().unwrap().contains("500"));
+
+    receiver.reset().await;
+    Mock::given(path("/hooks"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&receiver)
+        .await;
+
+    let response = app
+        .post_redeliver_webhook(&id, &failed.id.to_string())
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+
+    let delivered = wait_for_delivery(&app, &id, |d| d.status == "delivered").await;
+    assert_eq!(delivered.id, failed.id);
+    assert_eq!(delivered.attempts, 2);
+    assert_eq!(delivered.last_error, None);
+
+    let response = app
+        .post_redeliver_webhook(&id, &uuid::Uuid::new_v4().to_string())
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_follow_redirects_to_private_hosts() {
+    let mut app = TestApp::new().await;
+    app.login_as_admin().await;
+
+    let internal = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(0)
+        .mount(&internal)
+        .await;
+
+    let receiver = MockServer::start().await;
+    Mock::given(path("/hooks"))
+        .and(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(307)
+                .insert_header("Location", format!("{}/internal", internal.uri()).as_str()),
+        )
+        .mount(&receiver)
+        .await;
+
+    let created = create_webhook(
+        &app,
+        &format!("{}/hooks", receiver.uri()),
+        &["user.signed_up"],
+    )
+    .await;
+
+    signup(&app).await;
+
+    let failed =
+        wait_for_delivery(&app, &created.webhook.id.to_string(), |d| d.attempts == 1).await;
+    assert_eq!(failed.status, "pending");
+    assert!(failed.last_error.as_deref().unwrap().contains("307"));
+
+    app.clean_up().await;
+}
@@ -1 +1,4 @@
+// The shared helpers are only exercised by the `api` test target.
+#![allow(dead_code)]
+
 mod api;